@@ -3,6 +3,7 @@ package errors
 import (
 	"fmt"
 	"reflect"
+	"strings"
 
 	"github.com/osohq/go-oso/types"
 )
@@ -138,6 +139,18 @@ func (e *PolarFileNotFoundError) Error() string {
 	return fmt.Sprintf("Could not find file: %s", e.file)
 }
 
+type ImportCycleError struct {
+	chain []string
+}
+
+func NewImportCycleError(chain []string) *ImportCycleError {
+	return &ImportCycleError{chain: chain}
+}
+
+func (e *ImportCycleError) Error() string {
+	return fmt.Sprintf("Import cycle: %s", strings.Join(e.chain, " -> "))
+}
+
 type UnimplementedOperationError struct {
 	operation string
 }
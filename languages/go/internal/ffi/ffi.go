@@ -108,6 +108,71 @@ func (p PolarFfi) Load(s string, filename *string) error {
 	return nil
 }
 
+func (p PolarFfi) Imports(s string, filename string) ([]string, error) {
+	cString := C.CString(s)
+	defer C.free(unsafe.Pointer(cString))
+	cFilename := C.CString(filename)
+	defer C.free(unsafe.Pointer(cFilename))
+	result := C.polar_imports(p.ptr, cString, cFilename)
+	if result == nil {
+		return nil, getError()
+	}
+	defer C.string_free(result)
+	var imports []string
+	err := json.Unmarshal([]byte(C.GoString(result)), &imports)
+	return imports, err
+}
+
+// An annotation on a rule definition, with its arguments as Polar terms.
+type Annotation struct {
+	Name string       `json:"name"`
+	Args []types.Term `json:"args"`
+}
+
+func (p PolarFfi) Annotations(name string) ([][]Annotation, error) {
+	cName := C.CString(name)
+	defer C.free(unsafe.Pointer(cName))
+	result := C.polar_annotations(p.ptr, cName)
+	if result == nil {
+		return nil, getError()
+	}
+	defer C.string_free(result)
+	var annotations [][]Annotation
+	err := json.Unmarshal([]byte(C.GoString(result)), &annotations)
+	return annotations, err
+}
+
+// The documentation of a rule definition or rule type.
+type RuleDocs struct {
+	Signature   string       `json:"signature"`
+	Doc         *string      `json:"doc"`
+	Annotations []Annotation `json:"annotations"`
+}
+
+// The documentation of a constant.
+type ConstantDocs struct {
+	Definition string  `json:"definition"`
+	Doc        *string `json:"doc"`
+}
+
+// The documentation of the loaded rules, rule types and constants.
+type PolicyDocs struct {
+	Rules     map[string][]RuleDocs   `json:"rules"`
+	Types     map[string][]RuleDocs   `json:"types"`
+	Constants map[string]ConstantDocs `json:"constants"`
+}
+
+func (p PolarFfi) Docs() (*PolicyDocs, error) {
+	result := C.polar_docs(p.ptr)
+	if result == nil {
+		return nil, getError()
+	}
+	defer C.string_free(result)
+	var docs PolicyDocs
+	err := json.Unmarshal([]byte(C.GoString(result)), &docs)
+	return &docs, err
+}
+
 func (p PolarFfi) ClearRules() error {
 	result := C.polar_clear_rules(p.ptr)
 	processMessages(p)
@@ -165,6 +230,16 @@ func (p PolarFfi) RegisterConstant(term types.Term, name string) error {
 	return nil
 }
 
+func (p PolarFfi) IgnoreWarning(code string) error {
+	cCode := C.CString(code)
+	defer C.free(unsafe.Pointer(cCode))
+	result := C.polar_ignore_warning(p.ptr, cCode)
+	if result == 0 {
+		return getError()
+	}
+	return nil
+}
+
 type QueryFfi struct {
 	ptr *C.polar_Query
 }
@@ -215,6 +290,18 @@ func (q QueryFfi) QuestionResult(callID uint64, answer bool) error {
 	return nil
 }
 
+func (q QueryFfi) SetNow(now types.Term) error {
+	s, err := ffiSerialize(now)
+	if err != nil {
+		return err
+	}
+	result := C.polar_query_set_now(q.ptr, s)
+	if result == 0 {
+		return getError()
+	}
+	return nil
+}
+
 func (q QueryFfi) ApplicationError(message string) error {
 	result := C.polar_application_error(q.ptr, C.CString(message))
 	if result == 0 {
@@ -17,10 +17,34 @@ polar_Polar *polar_new(void);
 
 int32_t polar_load(polar_Polar *polar_ptr, const char *src, const char *filename);
 
+/**
+ * The files that `src`, the contents of the policy file `filename`, imports and that have not
+ * been loaded yet, as a JSON list of paths. The host loads them, after their own imports,
+ * before it loads `src`.
+ */
+const char *polar_imports(polar_Polar *polar_ptr, const char *src, const char *filename);
+
+/**
+ * The annotations on each definition of the rule `name`, as a JSON list with a list of
+ * annotations per definition.
+ */
+const char *polar_annotations(polar_Polar *polar_ptr, const char *name);
+
+/**
+ * The documentation of the loaded rules, rule types and constants, as a JSON object with
+ * `rules`, `types` and `constants` fields.
+ */
+const char *polar_docs(polar_Polar *polar_ptr);
+
 int32_t polar_clear_rules(polar_Polar *polar_ptr);
 
 int32_t polar_register_constant(polar_Polar *polar_ptr, const char *name, const char *value);
 
+/**
+ * Stop reporting warnings with the code `code`, e.g., `"undefined-rule"`.
+ */
+int32_t polar_ignore_warning(polar_Polar *polar_ptr, const char *code);
+
 polar_Query *polar_next_inline_query(polar_Polar *polar_ptr, uint32_t trace);
 
 polar_Query *polar_new_query_from_term(polar_Polar *polar_ptr,
@@ -61,6 +85,11 @@ const char *polar_query_source_info(polar_Query *query_ptr);
 
 int32_t polar_bind(polar_Query *query_ptr, const char *name, const char *value);
 
+/**
+ * Pin the time returned by `now()` in the query to `now`, a serialized timestamp term.
+ */
+int32_t polar_query_set_now(polar_Query *query_ptr, const char *now);
+
 uint64_t polar_get_external_id(polar_Polar *polar_ptr);
 
 /**
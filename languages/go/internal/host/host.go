@@ -6,6 +6,7 @@ import (
 	"fmt"
 	"math"
 	"reflect"
+	"time"
 
 	"github.com/osohq/go-oso/errors"
 	"github.com/osohq/go-oso/interfaces"
@@ -233,6 +234,20 @@ func (h Host) ToPolar(v interface{}) (*Value, error) {
 		return h.ToPolar(None{})
 	}
 	switch v := v.(type) {
+	case time.Time:
+		inner := ValueTimestamp{Seconds: v.Unix(), Nanos: uint32(v.Nanosecond())}
+		return &Value{inner}, nil
+	case types.NumericDecimal:
+		inner := ValueNumber{v}
+		return &Value{inner}, nil
+	case time.Duration:
+		// Count nanoseconds forward from the whole seconds, like Polar.
+		seconds, nanos := int64(v/time.Second), int64(v%time.Second)
+		if nanos < 0 {
+			seconds, nanos = seconds-1, nanos+int64(time.Second)
+		}
+		inner := ValueDuration{Seconds: seconds, Nanos: uint32(nanos)}
+		return &Value{inner}, nil
 	case bool:
 		inner := ValueBoolean(v)
 		return &Value{inner}, nil
@@ -313,6 +328,20 @@ func (h Host) ToPolar(v interface{}) (*Value, error) {
 		inner := ValueList(slice)
 		return &Value{inner}, nil
 	case reflect.Map:
+		// A map to empty structs is a set
+		if rt.Type().Elem() == reflect.TypeOf(struct{}{}) {
+			elements := make([]types.Term, 0, rt.Len())
+			iter := rt.MapRange()
+			for iter.Next() {
+				converted, err := h.ToPolar(iter.Key().Interface())
+				if err != nil {
+					return nil, err
+				}
+				elements = append(elements, types.Term{*converted})
+			}
+			inner := ValueSet(elements)
+			return &Value{inner}, nil
+		}
 		fields := make(map[types.Symbol]types.Term)
 		iter := rt.MapRange()
 		for iter.Next() {
@@ -363,11 +392,35 @@ func (h Host) ToGo(v types.Term) (interface{}, error) {
 			return int64(number), nil
 		case NumericFloat:
 			return float64(number), nil
+		case NumericDecimal:
+			// Go has no exact decimal type, so keep the digits as they are.
+			return number, nil
 		}
 	case ValueString:
 		return string(inner), nil
 	case ValueList:
 		return h.ListToGo(inner)
+	case ValueTimestamp:
+		return time.Unix(inner.Seconds, int64(inner.Nanos)).UTC(), nil
+	case ValueDuration:
+		maxSeconds := int64(math.MaxInt64 / time.Second)
+		if inner.Seconds >= maxSeconds || inner.Seconds < -maxSeconds {
+			return nil, fmt.Errorf("Duration of %v seconds is out of range for time.Duration", inner.Seconds)
+		}
+		return time.Duration(inner.Seconds)*time.Second + time.Duration(inner.Nanos), nil
+	case ValueSet:
+		retSet := make(map[interface{}]struct{})
+		for _, v := range inner {
+			ret, err := h.ToGo(v)
+			if err != nil {
+				return nil, err
+			}
+			if ret != nil && !reflect.TypeOf(ret).Comparable() {
+				return nil, fmt.Errorf("Cannot convert set element %v to a Go map key", ret)
+			}
+			retSet[ret] = struct{}{}
+		}
+		return retSet, nil
 	case ValueDictionary:
 		retMap := make(map[string]interface{})
 		for k, v := range inner.Fields {
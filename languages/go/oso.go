@@ -29,6 +29,7 @@ func NewOso() (Oso, error) {
 
 /*
 Load Polar policy from a ".polar" file, checking that all inline queries succeed.
+The files it imports that have not been loaded yet are loaded first.
 */
 func (o Oso) LoadFile(f string) error {
 	return (*o.p).loadFile(f)
@@ -41,6 +42,60 @@ func (o Oso) LoadString(s string) error {
 	return (*o.p).loadString(s)
 }
 
+/*
+An annotation on a rule definition, e.g., `@owner("billing-team")` has the
+Name "owner" and the Args ["billing-team"].
+*/
+type Annotation struct {
+	Name string
+	Args []interface{}
+}
+
+/*
+Return the annotations on each definition of the rule `rule`, in the order
+the definitions were loaded.
+*/
+func (o Oso) Annotations(rule string) ([][]Annotation, error) {
+	return (*o.p).annotations(rule)
+}
+
+/*
+The documentation of a rule definition or rule type: its signature, e.g.,
+`allow(actor: User, "read", resource)`, its `##` doc comment, if any, and its
+annotations.
+*/
+type RuleDocs struct {
+	Signature   string
+	Doc         *string
+	Annotations []Annotation
+}
+
+/*
+The documentation of a constant: its definition, e.g.,
+`const ADMIN_ROLES = ["owner", "admin"]`, and its `##` doc comment, if any.
+*/
+type ConstantDocs struct {
+	Definition string
+	Doc        *string
+}
+
+/*
+The documentation of the loaded rules, rule types and constants, by name.
+*/
+type PolicyDocs struct {
+	Rules     map[string][]RuleDocs
+	Types     map[string][]RuleDocs
+	Constants map[string]ConstantDocs
+}
+
+/*
+Return the documentation of the loaded rules, rule types and constants, from
+their `##` doc comments.
+*/
+func (o Oso) Docs() (PolicyDocs, error) {
+	return (*o.p).docs()
+}
+
 /*
 Clear all rules from the Oso knowledge base (i.e., remove all loaded policies).
 */
@@ -72,6 +127,13 @@ func (o Oso) RegisterConstant(value interface{}, name string) error {
 	return (*o.p).registerConstant(value, name)
 }
 
+/*
+Stop reporting warnings with the given code, e.g., "undefined-rule".
+*/
+func (o Oso) IgnoreWarning(code string) error {
+	return (*o.p).ignoreWarning(code)
+}
+
 /*
 Query the policy using a query string; the query is run in a new Go routine.
 Accepts the string to query for.
@@ -10,6 +10,7 @@ import (
 	"os"
 	"path/filepath"
 	"reflect"
+	"time"
 
 	"github.com/osohq/go-oso/errors"
 	"github.com/osohq/go-oso/internal/ffi"
@@ -39,9 +40,12 @@ func newPolar() (*Polar, error) {
 		"Boolean":    reflect.TypeOf(true),
 		"Integer":    reflect.TypeOf(int(1)),
 		"Float":      reflect.TypeOf(float64(1.0)),
+		"Decimal":    reflect.TypeOf(NumericDecimal("")),
 		"String":     reflect.TypeOf(""),
 		"List":       reflect.TypeOf(make([]interface{}, 0)),
 		"Dictionary": reflect.TypeOf(make(map[string]interface{})),
+		"Timestamp":  reflect.TypeOf(time.Time{}),
+		"Duration":   reflect.TypeOf(time.Duration(0)),
 	}
 
 	for k, v := range builtinClasses {
@@ -80,6 +84,12 @@ func (p Polar) checkInlineQueries() error {
 }
 
 func (p Polar) loadFile(f string) error {
+	return p.loadFileWithImports(f, []string{}, map[string]bool{})
+}
+
+// Load a policy file after the files it imports. importers is the chain of files
+// that imported it, and loaded the files loaded so far.
+func (p Polar) loadFileWithImports(f string, importers []string, loaded map[string]bool) error {
 	if filepath.Ext(f) != ".polar" {
 		return errors.NewPolarFileExtensionError(f)
 	}
@@ -88,11 +98,35 @@ func (p Polar) loadFile(f string) error {
 	if err != nil {
 		return err
 	}
+	imports, err := p.ffiPolar.Imports(string(data), f)
+	if err != nil {
+		return err
+	}
+	importers = append(importers, f)
+	for _, imported := range imports {
+		for i, importer := range importers {
+			if importer == imported {
+				chain := append(append([]string{}, importers[i:]...), imported)
+				return errors.NewImportCycleError(chain)
+			}
+		}
+		if !loaded[imported] {
+			err = p.loadFileWithImports(imported, importers, loaded)
+			if err != nil {
+				return err
+			}
+		}
+	}
 	err = p.ffiPolar.Load(string(data), &f)
 	if err != nil {
 		return err
 	}
-	return p.checkInlineQueries()
+	err = p.checkInlineQueries()
+	if err != nil {
+		return err
+	}
+	loaded[f] = true
+	return nil
 }
 
 func (p Polar) loadString(s string) error {
@@ -103,6 +137,69 @@ func (p Polar) loadString(s string) error {
 	return p.checkInlineQueries()
 }
 
+func (p Polar) annotations(rule string) ([][]Annotation, error) {
+	definitions, err := p.ffiPolar.Annotations(rule)
+	if err != nil {
+		return nil, err
+	}
+	result := make([][]Annotation, len(definitions))
+	for i, definition := range definitions {
+		if result[i], err = p.toGoAnnotations(definition); err != nil {
+			return nil, err
+		}
+	}
+	return result, nil
+}
+
+func (p Polar) toGoAnnotations(annotations []ffi.Annotation) ([]Annotation, error) {
+	result := make([]Annotation, len(annotations))
+	for i, annotation := range annotations {
+		args, err := p.host.ListToGo(annotation.Args)
+		if err != nil {
+			return nil, err
+		}
+		result[i] = Annotation{Name: annotation.Name, Args: args}
+	}
+	return result, nil
+}
+
+func (p Polar) docs() (PolicyDocs, error) {
+	docs, err := p.ffiPolar.Docs()
+	if err != nil {
+		return PolicyDocs{}, err
+	}
+	result := PolicyDocs{Constants: make(map[string]ConstantDocs, len(docs.Constants))}
+	if result.Rules, err = p.toGoRuleDocs(docs.Rules); err != nil {
+		return PolicyDocs{}, err
+	}
+	if result.Types, err = p.toGoRuleDocs(docs.Types); err != nil {
+		return PolicyDocs{}, err
+	}
+	for name, constant := range docs.Constants {
+		result.Constants[name] = ConstantDocs{Definition: constant.Definition, Doc: constant.Doc}
+	}
+	return result, nil
+}
+
+func (p Polar) toGoRuleDocs(rules map[string][]ffi.RuleDocs) (map[string][]RuleDocs, error) {
+	result := make(map[string][]RuleDocs, len(rules))
+	for name, definitions := range rules {
+		result[name] = make([]RuleDocs, len(definitions))
+		for i, definition := range definitions {
+			annotations, err := p.toGoAnnotations(definition.Annotations)
+			if err != nil {
+				return nil, err
+			}
+			result[name][i] = RuleDocs{
+				Signature:   definition.Signature,
+				Doc:         definition.Doc,
+				Annotations: annotations,
+			}
+		}
+	}
+	return result, nil
+}
+
 func (p Polar) clearRules() error {
 	return p.ffiPolar.ClearRules()
 }
@@ -218,3 +315,7 @@ func (p Polar) registerConstant(value interface{}, name string) error {
 	}
 	return p.ffiPolar.RegisterConstant(Term{*polarValue}, name)
 }
+
+func (p Polar) ignoreWarning(code string) error {
+	return p.ffiPolar.IgnoreWarning(code)
+}
@@ -6,6 +6,7 @@ import (
 	"fmt"
 	"os"
 	"reflect"
+	"time"
 
 	"github.com/osohq/go-oso/errors"
 	"github.com/osohq/go-oso/interfaces"
@@ -35,6 +36,18 @@ func newQuery(ffiQuery ffi.QueryFfi, host host.Host) Query {
 	}
 }
 
+/*
+Pin the time returned by `now()` in the query, e.g., to test time-bound rules.
+By default, it is the time the query starts. Must be called before `Next()`.
+*/
+func (q *Query) SetNow(now time.Time) error {
+	value, err := q.host.ToPolar(now)
+	if err != nil {
+		return err
+	}
+	return q.ffiQuery.SetNow(types.Term{*value})
+}
+
 func (q *Query) resultsChannel() (<-chan map[string]interface{}, <-chan error) {
 	results := make(chan map[string]interface{}, 1)
 	errors := make(chan error, 1)
@@ -1,11 +1,17 @@
 package oso_test
 
 import (
+	"fmt"
+	"io/ioutil"
+	"os"
+	"path/filepath"
 	"reflect"
 	"strings"
 	"testing"
+	"time"
 
 	oso "github.com/osohq/go-oso"
+	osoErrors "github.com/osohq/go-oso/errors"
 	. "github.com/osohq/go-oso/types"
 )
 
@@ -43,6 +49,56 @@ func TestLoadFile(t *testing.T) {
 	}
 }
 
+func TestLoadFileImports(t *testing.T) {
+	dir, err := ioutil.TempDir("", "imports-")
+	if err != nil {
+		t.Fatal(err)
+	}
+	defer os.RemoveAll(dir)
+	write := func(name string, src string) string {
+		path := filepath.Join(dir, name)
+		if err := os.MkdirAll(filepath.Dir(path), 0755); err != nil {
+			t.Fatal(err)
+		}
+		if err := ioutil.WriteFile(path, []byte(src), 0644); err != nil {
+			t.Fatal(err)
+		}
+		return path
+	}
+	write("shared/roles.polar", `role("admin");`)
+	write("shared/users.polar", "import \"roles.polar\";\nuser(\"alice\", r) if role(r);")
+	policy := write("policy.polar",
+		"import \"./shared/users.polar\";\nimport \"shared/roles.polar\";\nallow(u) if user(u, _);")
+
+	// Both files import shared/roles.polar, which is only loaded once.
+	o, err := oso.NewOso()
+	if err != nil {
+		t.Fatalf("Failed to set up Oso: %v", err)
+	}
+	if err = o.LoadFile(policy); err != nil {
+		t.Fatal(err.Error())
+	}
+	results, errs := o.QueryStr("allow(u)")
+	if err = <-errs; err != nil {
+		t.Error(err.Error())
+	}
+	var got []interface{}
+	for elem := range results {
+		got = append(got, elem["u"])
+	}
+	if !reflect.DeepEqual(got, []interface{}{"alice"}) {
+		t.Errorf("Expected [alice], got: %v", got)
+	}
+
+	a := write("a.polar", `import "b.polar";`)
+	b := write("b.polar", `import "a.polar";`)
+	err = o.LoadFile(a)
+	expected := fmt.Sprintf("Import cycle: %s -> %s -> %s", a, b, a)
+	if err == nil || err.Error() != expected {
+		t.Errorf("Expected %q, got: %v", expected, err)
+	}
+}
+
 func TestLoadString(t *testing.T) {
 	var o oso.Oso
 	var err error
@@ -56,6 +112,109 @@ func TestLoadString(t *testing.T) {
 
 }
 
+func TestErrorKinds(t *testing.T) {
+	var o oso.Oso
+	var err error
+	if o, err = oso.NewOso(); err != nil {
+		t.Fatalf("Failed to set up Oso: %v", err)
+	}
+
+	// Every syntax error in a source is reported at once.
+	err = o.LoadString("f(x) if x = ;\ng(x) if true;\nh(x) if x ==;")
+	polarErr, ok := err.(*osoErrors.FormattedPolarError)
+	if !ok {
+		t.Fatalf("Expected a Polar error, got: %v", err)
+	}
+	parseErr, ok := polarErr.Kind.ErrorKindVariant.(ErrorKindParse)
+	if !ok {
+		t.Fatalf("Expected a parse error, got: %v", polarErr)
+	}
+	if multiple, ok := parseErr.ParseErrorVariant.(ParseErrorMultiple); !ok || len(multiple.Errors) != 2 {
+		t.Errorf("Expected two syntax errors, got: %v", polarErr)
+	}
+	if !strings.HasPrefix(polarErr.Formatted, "found 2 syntax errors") {
+		t.Errorf("Unexpected message: %s", polarErr.Formatted)
+	}
+
+	if err = o.LoadString(`bad(x) if regex_match("(", x);`); err != nil {
+		t.Fatal(err.Error())
+	}
+	_, errs := o.QueryStr(`bad("x")`)
+	err = <-errs
+	polarErr, ok = err.(*osoErrors.FormattedPolarError)
+	if !ok {
+		t.Fatalf("Expected a Polar error, got: %v", err)
+	}
+	runtimeErr, ok := polarErr.Kind.ErrorKindVariant.(ErrorKindRuntime)
+	if !ok {
+		t.Fatalf("Expected a runtime error, got: %v", polarErr)
+	}
+	if regexErr, ok := runtimeErr.RuntimeErrorVariant.(RuntimeErrorInvalidRegex); !ok || regexErr.Pattern != "(" {
+		t.Errorf("Expected an invalid regex error, got: %v", polarErr)
+	}
+}
+
+func TestAnnotations(t *testing.T) {
+	o, err := oso.NewOso()
+	if err != nil {
+		t.Fatalf("Failed to set up Oso: %v", err)
+	}
+	if err = o.LoadString("@owner(\"billing-team\") @tag(\"pii\", 1) f(1);\nf(2);"); err != nil {
+		t.Fatal(err.Error())
+	}
+	got, err := o.Annotations("f")
+	if err != nil {
+		t.Fatal(err.Error())
+	}
+	expected := [][]oso.Annotation{
+		{
+			{Name: "owner", Args: []interface{}{"billing-team"}},
+			{Name: "tag", Args: []interface{}{"pii", int64(1)}},
+		},
+		{},
+	}
+	if !reflect.DeepEqual(got, expected) {
+		t.Errorf("Expected %v, got: %v", expected, got)
+	}
+	if got, err = o.Annotations("g"); err != nil || len(got) != 0 {
+		t.Errorf("Expected no annotations, got: %v, %v", got, err)
+	}
+}
+
+func TestDocs(t *testing.T) {
+	o, err := oso.NewOso()
+	if err != nil {
+		t.Fatalf("Failed to set up Oso: %v", err)
+	}
+	policy := "## Who owns a resource.\n@tag(\"pii\") owner(user, _resource) if user = \"alice\";\n" +
+		"## The admin roles.\nconst ADMIN_ROLES = [\"owner\"];"
+	if err = o.LoadString(policy); err != nil {
+		t.Fatal(err.Error())
+	}
+	got, err := o.Docs()
+	if err != nil {
+		t.Fatal(err.Error())
+	}
+	ownerDoc := "Who owns a resource."
+	rolesDoc := "The admin roles."
+	expected := oso.PolicyDocs{
+		Rules: map[string][]oso.RuleDocs{
+			"owner": {{
+				Signature:   "owner(user, _resource)",
+				Doc:         &ownerDoc,
+				Annotations: []oso.Annotation{{Name: "tag", Args: []interface{}{"pii"}}},
+			}},
+		},
+		Types: map[string][]oso.RuleDocs{},
+		Constants: map[string]oso.ConstantDocs{
+			"ADMIN_ROLES": {Definition: "const ADMIN_ROLES = [\"owner\"]", Doc: &rolesDoc},
+		},
+	}
+	if !reflect.DeepEqual(got, expected) {
+		t.Errorf("Expected %v, got: %v", expected, got)
+	}
+}
+
 func TestClearRules(t *testing.T) {
 
 }
@@ -144,6 +303,106 @@ func TestQueryRule(t *testing.T) {
 
 }
 
+func TestSetConversions(t *testing.T) {
+	var o oso.Oso
+	var err error
+	if o, err = oso.NewOso(); err != nil {
+		t.Fatalf("Failed to set up Oso: %v", err)
+	}
+
+	o.LoadString("shared(a, b, s) if intersection(a, b, s);")
+	a := map[int]struct{}{1: {}, 2: {}, 3: {}}
+	b := map[int]struct{}{2: {}, 3: {}, 4: {}}
+	results, errors := o.QueryRule("shared", a, b, ValueVariable("s"))
+
+	if err = <-errors; err != nil {
+		t.Error(err.Error())
+	} else {
+		var got []map[string]interface{}
+		expected := map[string]interface{}{
+			"s": map[interface{}]struct{}{int64(2): {}, int64(3): {}},
+		}
+		for elem := range results {
+			got = append(got, elem)
+		}
+		if len(got) != 1 {
+			t.Errorf("Received incorrect number of results: %v", got)
+		} else if !reflect.DeepEqual(got[0], expected) {
+			t.Errorf("Expected: %v, got: %v", expected, got[0])
+		}
+	}
+}
+
+func TestDecimalConversions(t *testing.T) {
+	var o oso.Oso
+	var err error
+	if o, err = oso.NewOso(); err != nil {
+		t.Fatalf("Failed to set up Oso: %v", err)
+	}
+
+	o.LoadString("add(a, b, a + b);")
+	results, errors := o.QueryRule("add", NumericDecimal("1.50"), 1, ValueVariable("x"))
+
+	if err = <-errors; err != nil {
+		t.Error(err.Error())
+	} else {
+		var got []map[string]interface{}
+		expected := map[string]interface{}{"x": NumericDecimal("2.50")}
+		for elem := range results {
+			got = append(got, elem)
+		}
+		if len(got) != 1 {
+			t.Errorf("Received incorrect number of results: %v", got)
+		} else if !reflect.DeepEqual(got[0], expected) {
+			t.Errorf("Expected: %v, got: %v", expected, got[0])
+		}
+	}
+}
+
+func TestTimeConversions(t *testing.T) {
+	var o oso.Oso
+	var err error
+	if o, err = oso.NewOso(); err != nil {
+		t.Fatalf("Failed to set up Oso: %v", err)
+	}
+
+	o.LoadString("later(t, d, t + d);")
+	expiry := time.Date(2021, 6, 1, 9, 0, 0, 0, time.UTC)
+	results, errors := o.QueryRule("later", expiry, -1500*time.Millisecond, ValueVariable("x"))
+
+	if err = <-errors; err != nil {
+		t.Error(err.Error())
+	} else {
+		var got []map[string]interface{}
+		expected := map[string]interface{}{"x": expiry.Add(-1500 * time.Millisecond)}
+		for elem := range results {
+			got = append(got, elem)
+		}
+		if len(got) != 1 {
+			t.Errorf("Received incorrect number of results: %v", got)
+		} else if !reflect.DeepEqual(got[0], expected) {
+			t.Errorf("Expected: %v, got: %v", expected, got[0])
+		}
+	}
+
+	o.LoadString("allow(expiry) if now() < expiry;")
+	for now, expected := range map[time.Time]int{expiry.Add(-time.Second): 1, expiry: 0} {
+		query, err := o.NewQueryFromRule("allow", expiry)
+		if err != nil {
+			t.Fatal(err)
+		}
+		if err = query.SetNow(now); err != nil {
+			t.Fatal(err)
+		}
+		got, err := query.GetAllResults()
+		if err != nil {
+			t.Error(err.Error())
+		} else if len(got) != expected {
+			t.Errorf("Expected %v results at %v, got: %v", expected, now, got)
+		}
+	}
+}
+
 func TestIsAllowed(t *testing.T) {
 	var o oso.Oso
 	var err error
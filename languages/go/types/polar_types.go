@@ -22,6 +22,14 @@ type Dictionary struct {
 	Fields map[Symbol]Term `json:"fields"`
 }
 
+// Duration struct
+type Duration struct {
+	// Seconds
+	Seconds int64 `json:"seconds"`
+	// Nanos
+	Nanos uint32 `json:"nanos"`
+}
+
 // ErrorKindParse newtype
 type ErrorKindParse ParseError
 
@@ -205,6 +213,14 @@ type ExternalInstance struct {
 	Repr *string `json:"repr"`
 }
 
+// FormattedPolarError struct
+type FormattedPolarError struct {
+	// Kind
+	Kind ErrorKind `json:"kind"`
+	// Formatted
+	Formatted string `json:"formatted"`
+}
+
 // InstanceLiteral struct
 type InstanceLiteral struct {
 	// Tag
@@ -446,6 +462,22 @@ func (variant *NumericFloat) UnmarshalJSON(b []byte) error {
 
 func (NumericFloat) isNumeric() {}
 
+// NumericDecimal newtype
+type NumericDecimal string
+
+func (variant NumericDecimal) MarshalJSON() ([]byte, error) {
+	return json.Marshal(string(variant))
+}
+
+func (variant *NumericDecimal) UnmarshalJSON(b []byte) error {
+	inner := string(*variant)
+	err := json.Unmarshal(b, &inner)
+	*variant = NumericDecimal(inner)
+	return err
+}
+
+func (NumericDecimal) isNumeric() {}
+
 // Numeric enum
 type NumericVariant interface {
 	isNumeric()
@@ -500,6 +532,17 @@ func (result *Numeric) UnmarshalJSON(b []byte) error {
 		*result = Numeric{variant}
 		return nil
 
+	case "Decimal":
+		var variant NumericDecimal
+		if variantValue != nil {
+			err := json.Unmarshal(*variantValue, &variant)
+			if err != nil {
+				return err
+			}
+		}
+		*result = Numeric{variant}
+		return nil
+
 	}
 
 	return fmt.Errorf("Cannot deserialize Numeric: %s", string(b))
@@ -518,6 +561,11 @@ func (variant Numeric) MarshalJSON() ([]byte, error) {
 			"Float": inner,
 		})
 
+	case NumericDecimal:
+		return json.Marshal(map[string]NumericDecimal{
+			"Decimal": inner,
+		})
+
 	}
 
 	return nil, fmt.Errorf("unexpected variant of %v", variant)
@@ -1261,6 +1309,8 @@ func (ParseErrorInvalidToken) isParseError() {}
 type ParseErrorUnrecognizedEOF struct {
 	// Loc
 	Loc uint64 `json:"loc"`
+	// Expected
+	Expected []string `json:"expected"`
 }
 
 func (ParseErrorUnrecognizedEOF) isParseError() {}
@@ -1271,6 +1321,8 @@ type ParseErrorUnrecognizedToken struct {
 	Token string `json:"token"`
 	// Loc
 	Loc uint64 `json:"loc"`
+	// Expected
+	Expected []string `json:"expected"`
 }
 
 func (ParseErrorUnrecognizedToken) isParseError() {}
@@ -1317,6 +1369,64 @@ type ParseErrorWrongValueType struct {
 
 func (ParseErrorWrongValueType) isParseError() {}
 
+// ParseErrorResourceBlock struct
+type ParseErrorResourceBlock struct {
+	// Loc
+	Loc uint64 `json:"loc"`
+	// Msg
+	Msg string `json:"msg"`
+}
+
+func (ParseErrorResourceBlock) isParseError() {}
+
+// ParseErrorModule struct
+type ParseErrorModule struct {
+	// Loc
+	Loc uint64 `json:"loc"`
+	// Msg
+	Msg string `json:"msg"`
+}
+
+func (ParseErrorModule) isParseError() {}
+
+// ParseErrorImport struct
+type ParseErrorImport struct {
+	// Loc
+	Loc uint64 `json:"loc"`
+	// Msg
+	Msg string `json:"msg"`
+}
+
+func (ParseErrorImport) isParseError() {}
+
+// ParseErrorConstant struct
+type ParseErrorConstant struct {
+	// Loc
+	Loc uint64 `json:"loc"`
+	// Msg
+	Msg string `json:"msg"`
+}
+
+func (ParseErrorConstant) isParseError() {}
+
+// ParseErrorBuiltinRule struct
+type ParseErrorBuiltinRule struct {
+	// Loc
+	Loc uint64 `json:"loc"`
+	// Msg
+	Msg string `json:"msg"`
+}
+
+func (ParseErrorBuiltinRule) isParseError() {}
+
+// ParseErrorMultiple struct
+type ParseErrorMultiple struct {
+	// Errors
+	Errors []FormattedPolarError `json:"errors"`
+}
+
+func (ParseErrorMultiple) isParseError() {}
+
 // ParseError enum
 type ParseErrorVariant interface {
 	isParseError()
@@ -1448,6 +1558,72 @@ func (result *ParseError) UnmarshalJSON(b []byte) error {
 		*result = ParseError{variant}
 		return nil
 
+	case "ResourceBlock":
+		var variant ParseErrorResourceBlock
+		if variantValue != nil {
+			err := json.Unmarshal(*variantValue, &variant)
+			if err != nil {
+				return err
+			}
+		}
+		*result = ParseError{variant}
+		return nil
+
+	case "Module":
+		var variant ParseErrorModule
+		if variantValue != nil {
+			err := json.Unmarshal(*variantValue, &variant)
+			if err != nil {
+				return err
+			}
+		}
+		*result = ParseError{variant}
+		return nil
+
+	case "Import":
+		var variant ParseErrorImport
+		if variantValue != nil {
+			err := json.Unmarshal(*variantValue, &variant)
+			if err != nil {
+				return err
+			}
+		}
+		*result = ParseError{variant}
+		return nil
+
+	case "Constant":
+		var variant ParseErrorConstant
+		if variantValue != nil {
+			err := json.Unmarshal(*variantValue, &variant)
+			if err != nil {
+				return err
+			}
+		}
+		*result = ParseError{variant}
+		return nil
+
+	case "BuiltinRule":
+		var variant ParseErrorBuiltinRule
+		if variantValue != nil {
+			err := json.Unmarshal(*variantValue, &variant)
+			if err != nil {
+				return err
+			}
+		}
+		*result = ParseError{variant}
+		return nil
+
+	case "Multiple":
+		var variant ParseErrorMultiple
+		if variantValue != nil {
+			err := json.Unmarshal(*variantValue, &variant)
+			if err != nil {
+				return err
+			}
+		}
+		*result = ParseError{variant}
+		return nil
+
 	}
 
 	return fmt.Errorf("Cannot deserialize ParseError: %s", string(b))
@@ -1501,6 +1677,36 @@ func (variant ParseError) MarshalJSON() ([]byte, error) {
 			"WrongValueType": inner,
 		})
 
+	case ParseErrorResourceBlock:
+		return json.Marshal(map[string]ParseErrorResourceBlock{
+			"ResourceBlock": inner,
+		})
+
+	case ParseErrorModule:
+		return json.Marshal(map[string]ParseErrorModule{
+			"Module": inner,
+		})
+
+	case ParseErrorImport:
+		return json.Marshal(map[string]ParseErrorImport{
+			"Import": inner,
+		})
+
+	case ParseErrorConstant:
+		return json.Marshal(map[string]ParseErrorConstant{
+			"Constant": inner,
+		})
+
+	case ParseErrorBuiltinRule:
+		return json.Marshal(map[string]ParseErrorBuiltinRule{
+			"BuiltinRule": inner,
+		})
+
+	case ParseErrorMultiple:
+		return json.Marshal(map[string]ParseErrorMultiple{
+			"Multiple": inner,
+		})
+
 	}
 
 	return nil, fmt.Errorf("unexpected variant of %v", variant)
@@ -2074,6 +2280,16 @@ type RuntimeErrorFileLoading struct {
 
 func (RuntimeErrorFileLoading) isRuntimeError() {}
 
+// RuntimeErrorInvalidRegex struct
+type RuntimeErrorInvalidRegex struct {
+	// Pattern
+	Pattern string `json:"pattern"`
+	// Msg
+	Msg string `json:"msg"`
+}
+
+func (RuntimeErrorInvalidRegex) isRuntimeError() {}
+
 // RuntimeError enum
 type RuntimeErrorVariant interface {
 	isRuntimeError()
@@ -2205,6 +2421,17 @@ func (result *RuntimeError) UnmarshalJSON(b []byte) error {
 		*result = RuntimeError{variant}
 		return nil
 
+	case "InvalidRegex":
+		var variant RuntimeErrorInvalidRegex
+		if variantValue != nil {
+			err := json.Unmarshal(*variantValue, &variant)
+			if err != nil {
+				return err
+			}
+		}
+		*result = RuntimeError{variant}
+		return nil
+
 	}
 
 	return fmt.Errorf("Cannot deserialize RuntimeError: %s", string(b))
@@ -2258,6 +2485,11 @@ func (variant RuntimeError) MarshalJSON() ([]byte, error) {
 			"FileLoading": inner,
 		})
 
+	case RuntimeErrorInvalidRegex:
+		return json.Marshal(map[string]RuntimeErrorInvalidRegex{
+			"InvalidRegex": inner,
+		})
+
 	}
 
 	return nil, fmt.Errorf("unexpected variant of %v", variant)
@@ -2283,6 +2515,14 @@ type Term struct {
 	Value Value `json:"value"`
 }
 
+// Timestamp struct
+type Timestamp struct {
+	// Seconds
+	Seconds int64 `json:"seconds"`
+	// Nanos
+	Nanos uint32 `json:"nanos"`
+}
+
 // Trace struct
 type Trace struct {
 	// Node
@@ -2427,6 +2667,54 @@ func (variant *ValueList) UnmarshalJSON(b []byte) error {
 
 func (ValueList) isValue() {}
 
+// ValueSet newtype
+type ValueSet []Term
+
+func (variant ValueSet) MarshalJSON() ([]byte, error) {
+	return json.Marshal([]Term(variant))
+}
+
+func (variant *ValueSet) UnmarshalJSON(b []byte) error {
+	inner := []Term(*variant)
+	err := json.Unmarshal(b, &inner)
+	*variant = ValueSet(inner)
+	return err
+}
+
+func (ValueSet) isValue() {}
+
+// ValueTimestamp newtype
+type ValueTimestamp Timestamp
+
+func (variant ValueTimestamp) MarshalJSON() ([]byte, error) {
+	return json.Marshal(Timestamp(variant))
+}
+
+func (variant *ValueTimestamp) UnmarshalJSON(b []byte) error {
+	inner := Timestamp(*variant)
+	err := json.Unmarshal(b, &inner)
+	*variant = ValueTimestamp(inner)
+	return err
+}
+
+func (ValueTimestamp) isValue() {}
+
+// ValueDuration newtype
+type ValueDuration Duration
+
+func (variant ValueDuration) MarshalJSON() ([]byte, error) {
+	return json.Marshal(Duration(variant))
+}
+
+func (variant *ValueDuration) UnmarshalJSON(b []byte) error {
+	inner := Duration(*variant)
+	err := json.Unmarshal(b, &inner)
+	*variant = ValueDuration(inner)
+	return err
+}
+
+func (ValueDuration) isValue() {}
+
 // ValueVariable newtype
 type ValueVariable Symbol
 
@@ -2595,6 +2883,39 @@ func (result *Value) UnmarshalJSON(b []byte) error {
 		*result = Value{variant}
 		return nil
 
+	case "Set":
+		var variant ValueSet
+		if variantValue != nil {
+			err := json.Unmarshal(*variantValue, &variant)
+			if err != nil {
+				return err
+			}
+		}
+		*result = Value{variant}
+		return nil
+
+	case "Timestamp":
+		var variant ValueTimestamp
+		if variantValue != nil {
+			err := json.Unmarshal(*variantValue, &variant)
+			if err != nil {
+				return err
+			}
+		}
+		*result = Value{variant}
+		return nil
+
+	case "Duration":
+		var variant ValueDuration
+		if variantValue != nil {
+			err := json.Unmarshal(*variantValue, &variant)
+			if err != nil {
+				return err
+			}
+		}
+		*result = Value{variant}
+		return nil
+
 	case "Variable":
 		var variant ValueVariable
 		if variantValue != nil {
@@ -2676,6 +2997,21 @@ func (variant Value) MarshalJSON() ([]byte, error) {
 			"List": inner,
 		})
 
+	case ValueSet:
+		return json.Marshal(map[string]ValueSet{
+			"Set": inner,
+		})
+
+	case ValueTimestamp:
+		return json.Marshal(map[string]ValueTimestamp{
+			"Timestamp": inner,
+		})
+
+	case ValueDuration:
+		return json.Marshal(map[string]ValueDuration{
+			"Duration": inner,
+		})
+
 	case ValueVariable:
 		return json.Marshal(map[string]ValueVariable{
 			"Variable": inner,
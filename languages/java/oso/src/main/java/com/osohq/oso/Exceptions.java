@@ -277,6 +277,12 @@ public class Exceptions {
     }
   }
 
+  public static class ImportCycleError extends PolarRuntimeException {
+    public ImportCycleError(List<String> chain) {
+      super("Import cycle: " + String.join(" -> ", chain));
+    }
+  }
+
   public static class DuplicateClassAliasError extends PolarRuntimeException {
     public DuplicateClassAliasError(String alias, String oldClass, String newClass) {
       super(
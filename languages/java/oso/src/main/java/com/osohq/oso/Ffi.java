@@ -5,8 +5,11 @@ import java.io.FileOutputStream;
 import java.io.IOException;
 import java.io.InputStream;
 import java.io.OutputStream;
+import java.util.ArrayList;
+import java.util.List;
 import jnr.ffi.LibraryLoader;
 import jnr.ffi.Pointer;
+import org.json.JSONArray;
 import org.json.JSONException;
 import org.json.JSONObject;
 
@@ -37,6 +40,31 @@ public class Ffi {
       return checkResult(result);
     }
 
+    protected List<String> imports(String src, String filename) throws Exceptions.OsoException {
+      Pointer importsPtr = checkResult(polarLib.polar_imports(ptr, src, filename));
+      JSONArray imports = new JSONArray(importsPtr.getString(0));
+      polarLib.string_free(importsPtr);
+      List<String> files = new ArrayList<String>();
+      for (int i = 0; i < imports.length(); i++) {
+        files.add(imports.getString(i));
+      }
+      return files;
+    }
+
+    protected JSONArray annotations(String name) throws Exceptions.OsoException {
+      Pointer annotationsPtr = checkResult(polarLib.polar_annotations(ptr, name));
+      JSONArray annotations = new JSONArray(annotationsPtr.getString(0));
+      polarLib.string_free(annotationsPtr);
+      return annotations;
+    }
+
+    protected JSONObject docs() throws Exceptions.OsoException {
+      Pointer docsPtr = checkResult(polarLib.polar_docs(ptr));
+      JSONObject docs = new JSONObject(docsPtr.getString(0));
+      polarLib.string_free(docsPtr);
+      return docs;
+    }
+
     protected int clearRules() throws Exceptions.OsoException {
       int result = polarLib.polar_clear_rules(ptr);
       processMessages();
@@ -71,6 +99,10 @@ public class Ffi {
       return checkResult(polarLib.polar_register_constant(ptr, name, value));
     }
 
+    protected int ignoreWarning(String code) throws Exceptions.OsoException {
+      return checkResult(polarLib.polar_ignore_warning(ptr, code));
+    }
+
     protected Pointer nextMessage() throws Exceptions.OsoException {
       return polarLib.polar_next_polar_message(ptr);
     }
@@ -114,6 +146,10 @@ public class Ffi {
       return checkResult(polarLib.polar_application_error(ptr, message));
     }
 
+    protected int setNow(String now) throws Exceptions.OsoException {
+      return checkResult(polarLib.polar_query_set_now(ptr, now));
+    }
+
     protected QueryEvent nextEvent() throws Exceptions.OsoException {
       Pointer eventPtr = polarLib.polar_next_query_event(ptr);
       processMessages();
@@ -199,6 +235,12 @@ public class Ffi {
 
     int polar_load(Pointer polar_ptr, String src, String filename);
 
+    Pointer polar_imports(Pointer polar_ptr, String src, String filename);
+
+    Pointer polar_annotations(Pointer polar_ptr, String name);
+
+    Pointer polar_docs(Pointer polar_ptr);
+
     int polar_clear_rules(Pointer polar_ptr);
 
     Pointer polar_new();
@@ -219,12 +261,16 @@ public class Ffi {
 
     int polar_application_error(Pointer query_ptr, String message);
 
+    int polar_query_set_now(Pointer query_ptr, String now);
+
     int query_free(Pointer query);
 
     int string_free(Pointer s);
 
     int polar_register_constant(Pointer polar_ptr, String name, String value);
 
+    int polar_ignore_warning(Pointer polar_ptr, String code);
+
     Pointer polar_next_polar_message(Pointer polar_ptr);
 
     Pointer polar_next_query_message(Pointer query_ptr);
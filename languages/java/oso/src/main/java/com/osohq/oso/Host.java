@@ -1,6 +1,9 @@
 package com.osohq.oso;
 
 import java.lang.reflect.Constructor;
+import java.math.BigDecimal;
+import java.time.Duration;
+import java.time.Instant;
 import java.util.*;
 import java.util.stream.Collectors;
 import java.util.stream.DoubleStream;
@@ -175,12 +178,26 @@ public class Host implements Cloneable {
       } else {
         jVal.put("Number", Map.of("Float", value));
       }
+    } else if (value != null && value.getClass() == BigDecimal.class) {
+      jVal.put("Number", Map.of("Decimal", ((BigDecimal) value).toPlainString()));
     } else if (value != null && value.getClass() == String.class) {
       jVal.put("String", value);
     } else if (value != null && value.getClass().isArray()) {
       jVal.put("List", javaArrayToPolar(value));
     } else if (value != null && value instanceof List) {
       jVal.put("List", javaListToPolar((List<Object>) value));
+    } else if (value != null && value instanceof Set) {
+      jVal.put("Set", javaListToPolar(new ArrayList<Object>((Set<Object>) value)));
+    } else if (value != null && value instanceof Instant) {
+      Instant instant = (Instant) value;
+      jVal.put(
+          "Timestamp",
+          new JSONObject(Map.of("seconds", instant.getEpochSecond(), "nanos", instant.getNano())));
+    } else if (value != null && value instanceof Duration) {
+      Duration duration = (Duration) value;
+      jVal.put(
+          "Duration",
+          new JSONObject(Map.of("seconds", duration.getSeconds(), "nanos", duration.getNano())));
     } else if (value != null && value instanceof Map) {
       Map<String, JSONObject> jMap = javaMaptoPolar((Map<Object, Object>) value);
       jVal.put("Dictionary", new JSONObject().put("fields", jMap));
@@ -282,9 +299,19 @@ public class Host implements Cloneable {
               }
             }
             return (Double) f;
+          case "Decimal":
+            return new BigDecimal(num.getString("Decimal"));
         }
       case "List":
         return polarListToJava(value.getJSONArray(tag));
+      case "Set":
+        return new HashSet<Object>(polarListToJava(value.getJSONArray(tag)));
+      case "Timestamp":
+        JSONObject timestamp = value.getJSONObject(tag);
+        return Instant.ofEpochSecond(timestamp.getLong("seconds"), timestamp.getLong("nanos"));
+      case "Duration":
+        JSONObject duration = value.getJSONObject(tag);
+        return Duration.ofSeconds(duration.getLong("seconds"), duration.getLong("nanos"));
       case "Dictionary":
         return polarDictToJava(value.getJSONObject(tag).getJSONObject("fields"));
       case "ExternalInstance":
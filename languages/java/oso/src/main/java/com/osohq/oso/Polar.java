@@ -7,9 +7,14 @@ import java.io.BufferedReader;
 import java.io.FileNotFoundException;
 import java.io.IOException;
 import java.io.InputStreamReader;
+import java.math.BigDecimal;
 import java.nio.file.Files;
 import java.nio.file.Paths;
+import java.time.Duration;
+import java.time.Instant;
 import java.util.*;
+import org.json.JSONArray;
+import org.json.JSONObject;
 
 public class Polar {
   private Ffi.Polar ffiPolar;
@@ -26,7 +31,11 @@ public class Polar {
     registerClass(Boolean.class, "Boolean");
     registerClass(Integer.class, "Integer");
     registerClass(Double.class, "Float");
+    registerClass(BigDecimal.class, "Decimal");
     registerClass(List.class, "List");
+    registerClass(Set.class, "Set");
+    registerClass(Instant.class, "Timestamp");
+    registerClass(Duration.class, "Duration");
     registerClass(Map.class, "Dictionary");
     registerClass(String.class, "String");
   }
@@ -40,15 +49,107 @@ public class Polar {
     ffiPolar.clearRules();
   }
 
+  /**
+   * The annotations on each definition of a rule, in the order the definitions were loaded. Each
+   * annotation is a map with its "name" and a list of its "args", e.g., {@code {name=owner,
+   * args=[billing-team]}} for {@code @owner("billing-team")}.
+   *
+   * @throws Exceptions.OsoException
+   */
+  public List<List<Map<String, Object>>> annotations(String rule) throws Exceptions.OsoException {
+    JSONArray definitions = ffiPolar.annotations(rule);
+    List<List<Map<String, Object>>> result = new ArrayList<List<Map<String, Object>>>();
+    for (int i = 0; i < definitions.length(); i++) {
+      result.add(toJavaAnnotations(definitions.getJSONArray(i)));
+    }
+    return result;
+  }
+
+  /**
+   * The documentation of the loaded rules, rule types and constants, from their {@code ##} doc
+   * comments. The "rules" and "types" maps list the definitions of each rule, as maps with their
+   * "signature", "doc" and "annotations"; the "constants" map has the "definition" and "doc" of
+   * each constant.
+   *
+   * @throws Exceptions.OsoException
+   */
+  public Map<String, Object> docs() throws Exceptions.OsoException {
+    JSONObject docs = ffiPolar.docs();
+    Map<String, Object> result = new HashMap<String, Object>();
+    result.put("rules", toJavaRuleDocs(docs.getJSONObject("rules")));
+    result.put("types", toJavaRuleDocs(docs.getJSONObject("types")));
+    JSONObject constants = docs.getJSONObject("constants");
+    Map<String, Map<String, Object>> javaConstants = new HashMap<String, Map<String, Object>>();
+    for (String name : constants.keySet()) {
+      JSONObject constant = constants.getJSONObject(name);
+      Map<String, Object> javaConstant = new HashMap<String, Object>();
+      javaConstant.put("definition", constant.getString("definition"));
+      javaConstant.put("doc", constant.optString("doc", null));
+      javaConstants.put(name, javaConstant);
+    }
+    result.put("constants", javaConstants);
+    return result;
+  }
+
+  private Map<String, List<Map<String, Object>>> toJavaRuleDocs(JSONObject rules)
+      throws Exceptions.OsoException {
+    Map<String, List<Map<String, Object>>> result =
+        new HashMap<String, List<Map<String, Object>>>();
+    for (String name : rules.keySet()) {
+      JSONArray definitions = rules.getJSONArray(name);
+      List<Map<String, Object>> javaDefinitions = new ArrayList<Map<String, Object>>();
+      for (int i = 0; i < definitions.length(); i++) {
+        JSONObject definition = definitions.getJSONObject(i);
+        Map<String, Object> javaDefinition = new HashMap<String, Object>();
+        javaDefinition.put("signature", definition.getString("signature"));
+        javaDefinition.put("doc", definition.optString("doc", null));
+        javaDefinition.put(
+            "annotations", toJavaAnnotations(definition.getJSONArray("annotations")));
+        javaDefinitions.add(javaDefinition);
+      }
+      result.put(name, javaDefinitions);
+    }
+    return result;
+  }
+
+  private List<Map<String, Object>> toJavaAnnotations(JSONArray annotations)
+      throws Exceptions.OsoException {
+    List<Map<String, Object>> result = new ArrayList<Map<String, Object>>();
+    for (int i = 0; i < annotations.length(); i++) {
+      JSONObject annotation = annotations.getJSONObject(i);
+      JSONArray args = annotation.getJSONArray("args");
+      List<Object> javaArgs = new ArrayList<Object>();
+      for (int j = 0; j < args.length(); j++) {
+        javaArgs.add(host.toJava(args.getJSONObject(j)));
+      }
+      Map<String, Object> javaAnnotation = new HashMap<String, Object>();
+      javaAnnotation.put("name", annotation.getString("name"));
+      javaAnnotation.put("args", javaArgs);
+      result.add(javaAnnotation);
+    }
+    return result;
+  }
+
   /**
    * Enqueue a polar policy file to be loaded. File contents are loaded into a String and saved
    * here, so changes to the file made after calls to loadFile will not be recognized. If the
-   * filename already exists in the load queue, replace it.
+   * filename already exists in the load queue, replace it. The files it imports that have not
+   * been loaded yet are loaded first.
    *
    * @throws Exceptions.PolarFileExtensionError On incorrect file extension.
+   * @throws Exceptions.ImportCycleError If files import each other in a cycle.
    * @throws IOException If unable to open or read the file.
    */
   public void loadFile(String filename) throws IOException, OsoException {
+    loadFileWithImports(filename, new ArrayList<String>(), new HashSet<String>());
+  }
+
+  /**
+   * Load a Polar policy file after the files it imports. {@code importers} is the chain of files
+   * that imported it, and {@code loaded} the files loaded so far.
+   */
+  private void loadFileWithImports(String filename, List<String> importers, Set<String> loaded)
+      throws IOException, OsoException {
     Optional<String> ext =
         Optional.ofNullable(filename)
             .filter(f -> f.contains("."))
@@ -59,11 +160,29 @@ public class Polar {
       throw new Exceptions.PolarFileExtensionError(filename);
     }
 
+    String policy;
     try {
-      loadStr(new String(Files.readAllBytes(Paths.get(filename))), filename);
+      policy = new String(Files.readAllBytes(Paths.get(filename)));
     } catch (FileNotFoundException e) {
       throw new Exceptions.PolarFileNotFoundError(filename);
     }
+
+    importers.add(filename);
+    for (String imported : ffiPolar.imports(policy, filename)) {
+      int i = importers.indexOf(imported);
+      if (i >= 0) {
+        List<String> chain = new ArrayList<String>(importers.subList(i, importers.size()));
+        chain.add(imported);
+        throw new Exceptions.ImportCycleError(chain);
+      }
+      if (!loaded.contains(imported)) {
+        loadFileWithImports(imported, importers, loaded);
+      }
+    }
+    importers.remove(importers.size() - 1);
+
+    loadStr(policy, filename);
+    loaded.add(filename);
   }
 
   /**
@@ -92,6 +211,14 @@ public class Polar {
     return new Query(ffiPolar.newQueryFromStr(query), host.clone());
   }
 
+  /** Query for a predicate, parsing it first, with `now()` pinned to `now`. */
+  public Query query(String query, Instant now) throws Exceptions.OsoException {
+    Host new_host = host.clone();
+    Ffi.Query ffiQuery = ffiPolar.newQueryFromStr(query);
+    ffiQuery.setNow(new_host.toPolarTerm(now).toString());
+    return new Query(ffiQuery, new_host);
+  }
+
   /** Query for a predicate. */
   public Query query(Predicate query) throws Exceptions.OsoException {
     Host new_host = host.clone();
@@ -99,6 +226,15 @@ public class Polar {
     return new Query(ffiPolar.newQueryFromTerm(pred), new_host);
   }
 
+  /** Query for a predicate, with `now()` pinned to `now`. */
+  public Query query(Predicate query, Instant now) throws Exceptions.OsoException {
+    Host new_host = host.clone();
+    String pred = new_host.toPolarTerm(query).toString();
+    Ffi.Query ffiQuery = ffiPolar.newQueryFromTerm(pred);
+    ffiQuery.setNow(new_host.toPolarTerm(now).toString());
+    return new Query(ffiQuery, new_host);
+  }
+
   /**
    * Query for a rule.
    *
@@ -188,6 +324,11 @@ public class Polar {
     ffiPolar.registerConstant(host.toPolarTerm(value).toString(), name);
   }
 
+  /** Stop reporting warnings with the given code, e.g., "undefined-rule". */
+  public void ignoreWarning(String code) throws Exceptions.OsoException {
+    ffiPolar.ignoreWarning(code);
+  }
+
   /** Confirm that all queued inline queries succeed. */
   private void checkInlineQueries()
       throws Exceptions.OsoException, Exceptions.InlineQueryFailedError {
@@ -7,6 +7,11 @@ import static org.junit.jupiter.api.Assertions.assertTrue;
 
 import java.io.File;
 import java.io.FileWriter;
+import java.math.BigDecimal;
+import java.nio.file.Files;
+import java.nio.file.Path;
+import java.time.Duration;
+import java.time.Instant;
 import java.util.*;
 import org.json.*;
 import org.junit.jupiter.api.BeforeEach;
@@ -194,6 +199,53 @@ public class PolarTest {
     assertEquals(m, java);
   }
 
+  @Test
+  public void testSetFFIRoundTrip() throws Exception {
+    Set<Integer> s = Set.of(1, 2, 3);
+    JSONObject polar = p.host.toPolarTerm(s);
+    Object java = p.host.toJava(polar);
+    assertEquals(s, java);
+    assertTrue(p.query("{1, 2} matches Set").results().size() == 1);
+  }
+
+  @Test
+  public void testDecimalFFIRoundTrip() throws Exception {
+    BigDecimal price = new BigDecimal("1.50");
+    assertEquals(price, p.host.toJava(p.host.toPolarTerm(price)));
+
+    p.loadStr("add(a, b, a + b);");
+    List<HashMap<String, Object>> results =
+        p.queryRule("add", price, 1, new Variable("x")).results();
+    assertEquals(List.of(Map.of("x", new BigDecimal("2.50"))), results);
+    assertTrue(p.query("1.50d matches Decimal").results().size() == 1);
+  }
+
+  @Test
+  public void testTimeFFIRoundTrip() throws Exception {
+    Instant t = Instant.parse("1969-12-31T23:59:59.500Z");
+    assertEquals(t, p.host.toJava(p.host.toPolarTerm(t)));
+    Duration d = Duration.ofMillis(-1500);
+    assertEquals(d, p.host.toJava(p.host.toPolarTerm(d)));
+
+    p.loadStr("later(t, d, t + d);");
+    Instant expiry = Instant.parse("2021-06-01T09:00:00Z");
+    List<HashMap<String, Object>> results =
+        p.queryRule("later", expiry, Duration.ofSeconds(90), new Variable("x")).results();
+    assertEquals(List.of(Map.of("x", expiry.plusSeconds(90))), results);
+    assertTrue(
+        p.query("now() matches Timestamp and duration(\"1s\") matches Duration").results().size()
+            == 1);
+  }
+
+  @Test
+  public void testPinnedNow() throws Exception {
+    p.loadStr("allow(expiry) if now() < expiry;");
+    Instant expiry = Instant.parse("2021-06-01T09:00:00Z");
+    Predicate allow = new Predicate("allow", List.of(expiry));
+    assertTrue(p.query(allow, expiry.minusSeconds(1)).hasMoreElements());
+    assertFalse(p.query(allow, expiry).hasMoreElements());
+  }
+
   @Test
   public void testJavaClassFFIRoundTrip() throws Exception {
     MyClass instance = new MyClass("test", 1);
@@ -440,7 +492,7 @@ public class PolarTest {
     String rule = "1";
     Exceptions.UnrecognizedToken e =
         assertThrows(Exceptions.UnrecognizedToken.class, () -> p.loadStr(rule));
-    assertEquals("did not expect to find the token '1' at line 1, column 1", e.getMessage());
+    assertEquals("did not expect to find the token '1', expected `?=`, `@` or a name at line 1, column 1", e.getMessage());
   }
 
   /**** TEST LOADING ****/
@@ -494,6 +546,79 @@ public class PolarTest {
         p.query("g(x)").results().equals(List.of(Map.of("x", 1), Map.of("x", 2), Map.of("x", 3))));
   }
 
+  private static String writePolicy(Path dir, String name, String src) throws Exception {
+    Path path = dir.resolve(name);
+    Files.createDirectories(path.getParent());
+    Files.write(path, src.getBytes());
+    return path.toString();
+  }
+
+  @Test
+  public void testLoadFileImports() throws Exception {
+    Path dir = Files.createTempDirectory("imports-");
+    writePolicy(dir, "shared/roles.polar", "role(\"admin\");");
+    writePolicy(
+        dir, "shared/users.polar", "import \"roles.polar\";\nuser(\"alice\", r) if role(r);");
+    String policy =
+        writePolicy(
+            dir,
+            "policy.polar",
+            "import \"./shared/users.polar\";\nimport \"shared/roles.polar\";\n"
+                + "allow(u) if user(u, _);");
+
+    // Both files import shared/roles.polar, which is only loaded once.
+    p.loadFile(policy);
+    assertEquals(List.of(Map.of("u", "alice")), p.query("allow(u)").results());
+    assertEquals(List.of(Map.of("r", "admin")), p.query("role(r)").results());
+
+    String a = writePolicy(dir, "a.polar", "import \"b.polar\";");
+    String b = writePolicy(dir, "b.polar", "import \"a.polar\";");
+    Exceptions.ImportCycleError e =
+        assertThrows(Exceptions.ImportCycleError.class, () -> p.loadFile(a));
+    assertEquals("Import cycle: " + a + " -> " + b + " -> " + a, e.getMessage());
+  }
+
+  @Test
+  public void testAnnotations() throws Exception {
+    p.loadStr("@owner(\"billing-team\") @tag(\"pii\", 1) f(1);\nf(2);");
+    assertEquals(
+        List.of(
+            List.of(
+                Map.of("name", "owner", "args", List.of("billing-team")),
+                Map.of("name", "tag", "args", List.of("pii", 1))),
+            List.of()),
+        p.annotations("f"));
+    assertTrue(p.annotations("g").isEmpty());
+  }
+
+  @Test
+  public void testDocs() throws Exception {
+    p.loadStr(
+        "## Who owns a resource.\n"
+            + "@tag(\"pii\") owner(user, _resource) if user = \"alice\";\n"
+            + "## The admin roles.\n"
+            + "const ADMIN_ROLES = [\"owner\"];");
+    assertEquals(
+        Map.of(
+            "rules",
+            Map.of(
+                "owner",
+                List.of(
+                    Map.of(
+                        "signature", "owner(user, _resource)",
+                        "doc", "Who owns a resource.",
+                        "annotations", List.of(Map.of("name", "tag", "args", List.of("pii")))))),
+            "types",
+            Map.of(),
+            "constants",
+            Map.of(
+                "ADMIN_ROLES",
+                Map.of(
+                    "definition", "const ADMIN_ROLES = [\"owner\"]",
+                    "doc", "The admin roles."))),
+        p.docs());
+  }
+
   @Test
   public void testClearRules() throws Exception {
     p.loadFile("src/test/java/com/osohq/oso/test.polar");
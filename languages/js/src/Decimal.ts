@@ -0,0 +1,15 @@
+/**
+ * Polar exact decimal, e.g., `1.50d`. JavaScript has no exact decimal type,
+ * so the digits are kept as a string, scale included.
+ */
+export class Decimal {
+  readonly value: string;
+
+  constructor(value: string) {
+    this.value = value;
+  }
+
+  toString(): string {
+    return this.value;
+  }
+}
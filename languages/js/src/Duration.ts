@@ -0,0 +1,25 @@
+/**
+ * Polar duration, e.g., the result of `duration("1h30m")` or of subtracting
+ * two timestamps. Like a `Date`, a duration before zero has negative `seconds`,
+ * and `nanos` counts forward from them.
+ */
+export class Duration {
+  readonly seconds: number;
+  readonly nanos: number;
+
+  constructor(seconds: number, nanos = 0) {
+    this.seconds = seconds;
+    this.nanos = nanos;
+  }
+
+  /** A duration of `ms` milliseconds. */
+  static fromMilliseconds(ms: number): Duration {
+    const seconds = Math.floor(ms / 1000);
+    return new Duration(seconds, Math.round((ms - seconds * 1000) * 1e6));
+  }
+
+  /** The length of the duration in milliseconds. */
+  get milliseconds(): number {
+    return this.seconds * 1000 + this.nanos / 1e6;
+  }
+}
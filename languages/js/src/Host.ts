@@ -5,6 +5,8 @@ import {
   UnregisteredInstanceError,
   UnexpectedPolarTypeError,
 } from './errors';
+import { Decimal } from './Decimal';
+import { Duration } from './Duration';
 import { ancestors, repr } from './helpers';
 import type { Polar as FfiPolar } from './polar_wasm_api';
 import { Predicate } from './Predicate';
@@ -16,6 +18,9 @@ import {
   isPolarNum,
   isPolarBool,
   isPolarList,
+  isPolarSet,
+  isPolarTimestamp,
+  isPolarDuration,
   isPolarDict,
   isPolarInstance,
   isPolarPredicate,
@@ -255,6 +260,21 @@ export class Host {
         return { value: { String: v } };
       case Array.isArray(v):
         return { value: { List: v.map((el: unknown) => this.toPolar(el)) } };
+      case v instanceof Set:
+        return {
+          value: { Set: Array.from(v, (el: unknown) => this.toPolar(el)) },
+        };
+      case v instanceof Date:
+        const ms = v.getTime();
+        const seconds = Math.floor(ms / 1000);
+        const nanos = (ms - seconds * 1000) * 1e6;
+        return { value: { Timestamp: { seconds, nanos } } };
+      case v instanceof Decimal:
+        return { value: { Number: { Decimal: v.value } } };
+      case v instanceof Duration:
+        return {
+          value: { Duration: { seconds: v.seconds, nanos: v.nanos } },
+        };
       case v instanceof Predicate:
         const args = v.args.map((el: unknown) => this.toPolar(el));
         return { value: { Call: { name: v.name, args } } };
@@ -300,6 +320,8 @@ export class Host {
               );
             return f;
         }
+      } else if ('Decimal' in t.Number) {
+        return new Decimal(t.Number.Decimal);
       } else {
         return t.Number.Integer;
       }
@@ -307,6 +329,14 @@ export class Host {
       return t.Boolean;
     } else if (isPolarList(t)) {
       return await Promise.all(t.List.map(async el => await this.toJs(el)));
+    } else if (isPolarSet(t)) {
+      return new Set(await Promise.all(t.Set.map(el => this.toJs(el))));
+    } else if (isPolarTimestamp(t)) {
+      const { seconds, nanos } = t.Timestamp;
+      // Dates only have millisecond precision.
+      return new Date(seconds * 1000 + Math.floor(nanos / 1e6));
+    } else if (isPolarDuration(t)) {
+      return new Duration(t.Duration.seconds, t.Duration.nanos);
     } else if (isPolarDict(t)) {
       const { fields } = t.Dictionary;
       let entries =
@@ -1,3 +1,9 @@
+import { mkdirSync, mkdtempSync, writeFileSync } from 'fs';
+import { tmpdir } from 'os';
+import { dirname, join } from 'path';
+
+import { Decimal } from './Decimal';
+import { Duration } from './Duration';
 import { Polar } from './Polar';
 import { Variable } from './Variable';
 import {
@@ -31,6 +37,7 @@ import {
 } from '../test/classes';
 import {
   DuplicateClassAliasError,
+  ImportCycleError,
   InlineQueryFailedError,
   InvalidConstructorError,
   KwargsError,
@@ -323,6 +330,73 @@ describe('conversions between JS + Polar values', () => {
     expect(result).toStrictEqual([map()]);
   });
 
+  test('converts sets in both directions', async () => {
+    const p = new Polar();
+    await p.loadStr('shared(a, b, s) if intersection(a, b, s);');
+    const result = await qvar(p, 'x = {1, "a", {2}}', 'x');
+    expect(result).toStrictEqual([new Set([1, 'a', new Set([2])])]);
+    const shared = await queryRule(
+      p,
+      'shared',
+      new Set([1, 2, 3]),
+      new Set([2, 3, 4]),
+      new Variable('s')
+    );
+    expect(shared).toStrictEqual([map({ s: new Set([2, 3]) })]);
+    expect(await query(p, '{1} matches Set')).toHaveLength(1);
+  });
+
+  test('converts decimals in both directions', async () => {
+    const p = new Polar();
+    await p.loadStr('add(a, b, a + b);');
+    const sum = await queryRule(
+      p,
+      'add',
+      new Decimal('1.50'),
+      1,
+      new Variable('x')
+    );
+    expect(sum).toStrictEqual([map({ x: new Decimal('2.50') })]);
+    expect(await qvar(p, 'x = 1.5d', 'x')).toStrictEqual([new Decimal('1.5')]);
+    expect(await query(p, '1.50d matches Decimal')).toHaveLength(1);
+  });
+
+  test('converts timestamps and durations in both directions', async () => {
+    const p = new Polar();
+    await p.loadStr('later(t, d, t + d);');
+    const t = new Date('2021-06-01T09:00:00Z');
+    const later = await queryRule(
+      p,
+      'later',
+      t,
+      Duration.fromMilliseconds(1500),
+      new Variable('x')
+    );
+    expect(later).toStrictEqual([
+      map({ x: new Date('2021-06-01T09:00:01.500Z') }),
+    ]);
+    const result = await qvar(p, 'x = duration("1m30s")', 'x');
+    expect(result).toStrictEqual([new Duration(90, 0)]);
+    expect(
+      await query(
+        p,
+        'now() matches Timestamp and duration("1s") matches Duration'
+      )
+    ).toHaveLength(1);
+  });
+
+  test('pins now() per query', async () => {
+    const p = new Polar();
+    await p.loadStr('allow(expiry) if now() < expiry;');
+    const expiry = new Date('2021-06-01T09:00:00Z');
+    const allowed = async (now: Date) => {
+      const results = p.query(pred('allow', expiry), { now });
+      return (await results.next()).done === false;
+    };
+    expect(await allowed(new Date('2021-06-01T08:59:59Z'))).toBe(true);
+    expect(await allowed(expiry)).toBe(false);
+  });
+
   test('handles Generator external call results', async () => {
     const actor = new Actor('sam');
     const p = new Polar();
@@ -415,6 +489,38 @@ describe('#loadFile', () => {
     expect(await qvar(p, 'f(x)', 'x')).toStrictEqual([1, 2, 3]);
     expect(await qvar(p, 'g(x)', 'x')).toStrictEqual([1, 2, 3]);
   });
+
+  test('loads the files a policy imports first', async () => {
+    const dir = mkdtempSync(join(tmpdir(), 'imports-'));
+    const write = (name: string, src: string) => {
+      const path = join(dir, name);
+      mkdirSync(dirname(path), { recursive: true });
+      writeFileSync(path, src);
+      return path;
+    };
+    write('shared/roles.polar', 'role("admin");');
+    write(
+      'shared/users.polar',
+      'import "roles.polar";\nuser("alice", r) if role(r);'
+    );
+    const policy = write(
+      'policy.polar',
+      'import "./shared/users.polar";\nimport "shared/roles.polar";\nallow(u) if user(u, _);'
+    );
+
+    // Both files import shared/roles.polar, which is only loaded once.
+    const p = new Polar();
+    await p.loadFile(policy);
+    expect(await qvar(p, 'allow(u)', 'u')).toStrictEqual(['alice']);
+    expect(await qvar(p, 'role(r)', 'r')).toStrictEqual(['admin']);
+
+    const a = write('a.polar', 'import "b.polar";');
+    const b = write('b.polar', 'import "a.polar";');
+    await expect(p.loadFile(a)).rejects.toThrow(ImportCycleError);
+    await expect(p.loadFile(a)).rejects.toThrow(
+      `Import cycle: ${a} -> ${b} -> ${a}`
+    );
+  });
 });
 
 describe('#clearRules', () => {
@@ -434,6 +540,55 @@ describe('#clearRules', () => {
   });
 });
 
+describe('#annotations', () => {
+  test('returns the annotations on each definition of a rule', async () => {
+    const p = new Polar();
+    await p.loadStr('@owner("billing-team") @tag("pii", 1) f(1);\nf(2);');
+    expect(await p.annotations('f')).toStrictEqual([
+      [
+        { name: 'owner', args: ['billing-team'] },
+        { name: 'tag', args: ['pii', 1] },
+      ],
+      [],
+    ]);
+    expect(await p.annotations('g')).toStrictEqual([]);
+  });
+});
+
+describe('#docs', () => {
+  test('returns the docs of rules and constants', async () => {
+    const p = new Polar();
+    await p.loadStr(
+      '## Who owns a resource.\n@tag("pii") owner(user, _resource) if user = "alice";\n' +
+        '## The admin roles.\nconst ADMIN_ROLES = ["owner"];'
+    );
+    expect(await p.docs()).toStrictEqual({
+      rules: new Map([
+        [
+          'owner',
+          [
+            {
+              signature: 'owner(user, _resource)',
+              doc: 'Who owns a resource.',
+              annotations: [{ name: 'tag', args: ['pii'] }],
+            },
+          ],
+        ],
+      ]),
+      types: new Map(),
+      constants: new Map([
+        [
+          'ADMIN_ROLES',
+          {
+            definition: 'const ADMIN_ROLES = ["owner"]',
+            doc: 'The admin roles.',
+          },
+        ],
+      ]),
+    });
+  });
+});
+
 describe('#query', () => {
   test('makes basic queries', async () => {
     const p = new Polar();
@@ -686,7 +841,7 @@ describe('errors', () => {
       const p = new Polar();
       const rule = '1;';
       expect(p.loadStr(rule)).rejects.toThrow(
-        "did not expect to find the token '1' at line 1, column 1"
+        "did not expect to find the token '1', expected `?=`, `@` or a name at line 1, column 1"
       );
     });
 
@@ -2,6 +2,7 @@ const extname = require('path')?.extname;
 const createInterface = require('readline')?.createInterface;
 
 import {
+  ImportCycleError,
   InlineQueryFailedError,
   InvalidConstructorError,
   PolarError,
@@ -10,12 +11,32 @@ import {
 } from './errors';
 import { Query } from './Query';
 import { Host } from './Host';
+import { Decimal } from './Decimal';
+import { Duration } from './Duration';
 import { Polar as FfiPolar } from './polar_wasm_api';
 import { Predicate } from './Predicate';
 import { processMessage } from './messages';
-import type { Class, Options, QueryResult } from './types';
+import type {
+  Annotation,
+  Class,
+  ConstantDocs,
+  Options,
+  PolarTerm,
+  PolicyDocs,
+  QueryOpts,
+  QueryResult,
+  RuleDocs,
+} from './types';
 import { isConstructor, printError, PROMPT, readFile, repr } from './helpers';
 
+/** An annotation as returned by the runtime, with Polar arguments. */
+type FfiAnnotation = { name: string; args: PolarTerm[] };
+
+/** The documentation of a rule as returned by the runtime. */
+type FfiRuleDocs = Omit<RuleDocs, 'annotations'> & {
+  annotations: FfiAnnotation[];
+};
+
 /** Create and manage an instance of the Polar runtime. */
 export class Polar {
   /**
@@ -44,8 +65,12 @@ export class Polar {
     this.registerClass(Boolean);
     this.registerClass(Number, 'Integer');
     this.registerClass(Number, 'Float');
+    this.registerClass(Decimal);
     this.registerClass(String);
     this.registerClass(Array, 'List');
+    this.registerClass(Set);
+    this.registerClass(Date, 'Timestamp');
+    this.registerClass(Duration);
     this.registerClass(Object, 'Dictionary');
   }
 
@@ -81,9 +106,81 @@ export class Polar {
   }
 
   /**
-   * Load a Polar policy file.
+   * Return the annotations on each definition of the rule `name`, in the
+   * order the definitions were loaded.
+   */
+  async annotations(name: string): Promise<Annotation[][]> {
+    const definitions: FfiAnnotation[][] = this.#ffiPolar.annotations(name);
+    return await Promise.all(
+      definitions.map(annotations =>
+        Promise.all(annotations.map(a => this.toJsAnnotation(a)))
+      )
+    );
+  }
+
+  /**
+   * Return the documentation of the loaded rules, rule types and constants,
+   * from their `##` doc comments.
+   */
+  async docs(): Promise<PolicyDocs> {
+    const docs: {
+      rules: Map<string, FfiRuleDocs[]>;
+      types: Map<string, FfiRuleDocs[]>;
+      constants: Map<string, ConstantDocs>;
+    } = this.#ffiPolar.docs();
+    return {
+      rules: await this.toJsRuleDocs(docs.rules),
+      types: await this.toJsRuleDocs(docs.types),
+      constants: docs.constants,
+    };
+  }
+
+  private async toJsRuleDocs(
+    rules: Map<string, FfiRuleDocs[]>
+  ): Promise<Map<string, RuleDocs[]>> {
+    const result: Map<string, RuleDocs[]> = new Map();
+    for (const [name, definitions] of rules.entries()) {
+      result.set(
+        name,
+        await Promise.all(
+          definitions.map(async definition => ({
+            ...definition,
+            annotations: await Promise.all(
+              definition.annotations.map(a => this.toJsAnnotation(a))
+            ),
+          }))
+        )
+      );
+    }
+    return result;
+  }
+
+  private async toJsAnnotation(annotation: FfiAnnotation): Promise<Annotation> {
+    return {
+      name: annotation.name,
+      args: await Promise.all(annotation.args.map(arg => this.#host.toJs(arg))),
+    };
+  }
+
+  /**
+   * Load a Polar policy file, after the files it imports that have not been
+   * loaded yet.
    */
   async loadFile(file: string): Promise<void> {
+    await this.loadFileWithImports(file, [], new Set());
+  }
+
+  /**
+   * Load a Polar policy file after the files it imports. `importers` is the
+   * chain of files that imported it, and `loaded` the files loaded so far.
+   *
+   * @internal
+   */
+  private async loadFileWithImports(
+    file: string,
+    importers: string[],
+    loaded: Set<string>
+  ): Promise<void> {
     if (!extname) {
       throw new PolarError('loadFile is not supported in the browser');
     }
@@ -95,7 +192,17 @@ export class Polar {
       if (e.code === 'ENOENT') throw new PolarFileNotFoundError(file);
       throw e;
     }
+    importers.push(file);
+    const imports: string[] = this.#ffiPolar.imports(contents, file);
+    for (const imported of imports) {
+      const i = importers.indexOf(imported);
+      if (i >= 0) throw new ImportCycleError([...importers.slice(i), imported]);
+      if (!loaded.has(imported))
+        await this.loadFileWithImports(imported, importers, loaded);
+    }
+    importers.pop();
     await this.loadStr(contents, file);
+    loaded.add(file);
   }
 
   /**
@@ -120,7 +227,7 @@ export class Polar {
   /**
    * Query for a Polar predicate or string.
    */
-  query(q: Predicate | string): QueryResult {
+  query(q: Predicate | string, opts?: QueryOpts): QueryResult {
     const host = Host.clone(this.#host);
     let ffiQuery;
     if (typeof q === 'string') {
@@ -130,7 +237,7 @@ export class Polar {
       ffiQuery = this.#ffiPolar.newQueryFromTerm(term);
     }
     this.processMessages();
-    return new Query(ffiQuery, host).results;
+    return new Query(ffiQuery, host, opts?.now).results;
   }
 
   /**
@@ -157,6 +264,13 @@ export class Polar {
     this.#ffiPolar.registerConstant(name, JSON.stringify(term));
   }
 
+  /**
+   * Stop reporting warnings with the given code, e.g., `'undefined-rule'`.
+   */
+  ignoreWarning(code: string): void {
+    this.#ffiPolar.ignoreWarning(code);
+  }
+
   /** Start a REPL session. */
   async repl(files?: string[]): Promise<void> {
     if (createInterface == null) {
@@ -42,8 +42,9 @@ export class Query {
   #host: Host;
   results: QueryResult;
 
-  constructor(ffiQuery: FfiQuery, host: Host) {
+  constructor(ffiQuery: FfiQuery, host: Host, now?: Date) {
     ffiQuery.setLoggingOptions(...getLogLevelsFromEnv());
+    if (now !== undefined) ffiQuery.setNow(now.toISOString());
     this.#ffiQuery = ffiQuery;
     this.#calls = new Map();
     this.#host = host;
@@ -34,6 +34,13 @@ export class DuplicateInstanceRegistrationError extends PolarError {
   }
 }
 
+export class ImportCycleError extends PolarError {
+  constructor(chain: string[]) {
+    super(`Import cycle: ${chain.join(' -> ')}`);
+    Object.setPrototypeOf(this, ImportCycleError.prototype);
+  }
+}
+
 export class InlineQueryFailedError extends PolarError {
   constructor(source: string) {
     super(`Inline query failed: ${source}`);
@@ -9,14 +9,41 @@ enum MessageKind {
   Warning = 'Warning',
 }
 
+/**
+ * Zero-based position in a Polar source.
+ *
+ * @internal
+ */
+interface Position {
+  row: number;
+  column: number;
+}
+
+/**
+ * Part of a Polar source that a warning refers to. `end` is exclusive.
+ *
+ * @internal
+ */
+interface SourceRange {
+  filename: string | null;
+  start: Position;
+  end: Position;
+}
+
 /**
  * JSON payload containing a message emitted by the Polar VM.
  *
+ * Warnings also carry the stable code of the lint that produced them, its
+ * severity, and the range of source they refer to.
+ *
  * @internal
  */
 interface Message {
   kind: MessageKind;
   msg: string;
+  code: string | null;
+  severity: 'Info' | 'Warning' | null;
+  range: SourceRange | null;
 }
 
 /**
@@ -32,7 +32,7 @@ export function isPolarOperator(s: string): s is PolarOperator {
  * @internal
  */
 interface PolarNum {
-  Number: PolarFloat | PolarInt;
+  Number: PolarFloat | PolarInt | PolarDecimal;
 }
 
 /**
@@ -64,6 +64,15 @@ interface PolarInt {
   Integer: number;
 }
 
+/**
+ * Polar exact decimal type, as a string of digits.
+ *
+ * @internal
+ */
+interface PolarDecimal {
+  Decimal: string;
+}
+
 /**
  * Polar boolean type.
  *
@@ -102,6 +111,63 @@ export function isPolarList(v: PolarValue): v is PolarList {
   return (v as PolarList).List !== undefined;
 }
 
+/**
+ * Polar set type.
+ *
+ * @internal
+ */
+interface PolarSet {
+  Set: PolarTerm[];
+}
+
+/**
+ * Type guard to test if a Polar value received from across the WebAssembly
+ * boundary is a Polar set.
+ *
+ * @internal
+ */
+export function isPolarSet(v: PolarValue): v is PolarSet {
+  return (v as PolarSet).Set !== undefined;
+}
+
+/**
+ * Polar timestamp type, in seconds and nanoseconds since the Unix epoch.
+ *
+ * @internal
+ */
+interface PolarTimestamp {
+  Timestamp: { seconds: number; nanos: number };
+}
+
+/**
+ * Type guard to test if a Polar value received from across the WebAssembly
+ * boundary is a Polar timestamp.
+ *
+ * @internal
+ */
+export function isPolarTimestamp(v: PolarValue): v is PolarTimestamp {
+  return (v as PolarTimestamp).Timestamp !== undefined;
+}
+
+/**
+ * Polar duration type, in seconds and nanoseconds.
+ *
+ * @internal
+ */
+interface PolarDuration {
+  Duration: { seconds: number; nanos: number };
+}
+
+/**
+ * Type guard to test if a Polar value received from across the WebAssembly
+ * boundary is a Polar duration.
+ *
+ * @internal
+ */
+export function isPolarDuration(v: PolarValue): v is PolarDuration {
+  return (v as PolarDuration).Duration !== undefined;
+}
+
 /**
  * Polar dictionary type.
  *
@@ -197,6 +263,9 @@ type PolarValue =
   | PolarNum
   | PolarBool
   | PolarList
+  | PolarSet
+  | PolarTimestamp
+  | PolarDuration
   | PolarDict
   | PolarPredicate
   | PolarVariable
@@ -224,6 +293,9 @@ function isPolarValue(v: any): v is PolarValue {
     isPolarNum(v) ||
     isPolarBool(v) ||
     isPolarList(v) ||
+    isPolarSet(v) ||
+    isPolarTimestamp(v) ||
+    isPolarDuration(v) ||
     isPolarDict(v) ||
     isPolarPredicate(v) ||
     isPolarVariable(v) ||
@@ -427,6 +499,54 @@ export type QueryResult = AsyncGenerator<
   undefined | void
 >;
 
+/**
+ * An annotation on a rule definition, e.g., `@owner("billing-team")` has the
+ * name `'owner'` and the arguments `['billing-team']`.
+ */
+export interface Annotation {
+  name: string;
+  args: any[];
+}
+
+/**
+ * The documentation of a rule definition or rule type, e.g., the signature
+ * `'allow(actor: User, "read", resource)'`, its `##` doc comment and its
+ * annotations.
+ */
+export interface RuleDocs {
+  signature: string;
+  doc?: string;
+  annotations: Annotation[];
+}
+
+/**
+ * The documentation of a constant, e.g., the definition
+ * `'const ADMIN_ROLES = ["owner", "admin"]'` and its `##` doc comment.
+ */
+export interface ConstantDocs {
+  definition: string;
+  doc?: string;
+}
+
+/**
+ * The documentation of the loaded rules, rule types and constants, by name.
+ */
+export interface PolicyDocs {
+  rules: Map<string, RuleDocs[]>;
+  types: Map<string, RuleDocs[]>;
+  constants: Map<string, ConstantDocs>;
+}
+
+/**
+ * Optional configuration for a single query.
+ *
+ * `now` pins the time returned by `now()` in the query, e.g., to test
+ * time-bound rules. By default, it is the time the query starts.
+ */
+export interface QueryOpts {
+  now?: Date;
+}
+
 /**
  * An object with string keys.
  *
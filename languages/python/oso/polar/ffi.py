@@ -24,6 +24,32 @@ class Polar:
         process_messages(self.next_message)
         check_result(result)
 
+    def imports(self, string, filename):
+        """The files a policy file imports that have not been loaded yet."""
+        imports = lib.polar_imports(self.ptr, to_c_str(string), to_c_str(str(filename)))
+        imports = check_result(imports)
+        try:
+            return json.loads(ffi.string(imports).decode())
+        finally:
+            lib.string_free(imports)
+
+    def annotations(self, name):
+        """The annotations on each definition of the rule ``name``."""
+        annotations = lib.polar_annotations(self.ptr, to_c_str(name))
+        annotations = check_result(annotations)
+        try:
+            return json.loads(ffi.string(annotations).decode())
+        finally:
+            lib.string_free(annotations)
+
+    def docs(self):
+        """The documentation of the loaded rules, rule types and constants."""
+        docs = check_result(lib.polar_docs(self.ptr))
+        try:
+            return json.loads(ffi.string(docs).decode())
+        finally:
+            lib.string_free(docs)
+
     def clear_rules(self):
         """Clear all rules from the Polar KB"""
         result = lib.polar_clear_rules(self.ptr)
@@ -58,6 +84,10 @@ class Polar:
         process_messages(self.next_message)
         check_result(result)
 
+    def ignore_warning(self, code):
+        result = lib.polar_ignore_warning(self.ptr, to_c_str(code))
+        check_result(result)
+
     def next_message(self):
         return lib.polar_next_polar_message(self.ptr)
 
@@ -113,6 +143,11 @@ class Query:
         process_messages(self.next_message)
         check_result(result)
 
+    def set_now(self, now):
+        result = lib.polar_query_set_now(self.ptr, ffi_serialize(now))
+        process_messages(self.next_message)
+        check_result(result)
+
 
 class QueryEvent:
     def __init__(self, ptr):
@@ -1,5 +1,7 @@
 """Translate between Polar and the host language (Python)."""
 
+from datetime import datetime, timedelta, timezone
+from decimal import Decimal
 from math import inf, isnan, nan
 
 from .exceptions import (
@@ -15,6 +17,21 @@ from .variable import Variable
 from .predicate import Predicate
 from .expression import Expression, Pattern
 
+EPOCH = datetime(1970, 1, 1, tzinfo=timezone.utc)
+
+
+def timedelta_to_polar(delta):
+    """Convert a timedelta to Polar seconds and nanoseconds."""
+    return {
+        "seconds": delta.days * 86400 + delta.seconds,
+        "nanos": delta.microseconds * 1000,
+    }
+
+
+def timedelta_from_polar(value):
+    """Convert Polar seconds and nanoseconds to a timedelta."""
+    return timedelta(seconds=value["seconds"], microseconds=value["nanos"] // 1000)
+
 
 class Host:
     """Maintain mappings and caches for Python classes & instances."""
@@ -153,10 +170,22 @@ class Host:
             elif isnan(v):
                 v = "NaN"
             val = {"Number": {"Float": v}}
+        # Polar decimals are always finite; others are converted like floats.
+        elif isinstance(v, Decimal) and v.is_finite():
+            val = {"Number": {"Decimal": format(v, "f")}}
+        elif isinstance(v, Decimal):
+            return self.to_polar(float(v))
         elif type(v) == str:
             val = {"String": v}
         elif type(v) == list:
             val = {"List": [self.to_polar(i) for i in v]}
+        elif type(v) in (set, frozenset):
+            val = {"Set": [self.to_polar(i) for i in v]}
+        # Naive datetimes are not points in time, so they stay Python instances.
+        elif isinstance(v, datetime) and v.tzinfo is not None:
+            val = {"Timestamp": timedelta_to_polar(v - EPOCH)}
+        elif isinstance(v, timedelta):
+            val = {"Duration": timedelta_to_polar(v)}
         elif type(v) == dict:
             val = {
                 "Dictionary": {"fields": {k: self.to_polar(v) for k, v in v.items()}}
@@ -219,9 +248,17 @@ class Host:
                         raise PolarRuntimeError(
                             f'Expected a floating point number, got "{number}"'
                         )
+            elif "Decimal" in value[tag]:
+                return Decimal(number)
             return number
         elif tag == "List":
             return [self.to_python(e) for e in value[tag]]
+        elif tag == "Set":
+            return {self.to_python(e) for e in value[tag]}
+        elif tag == "Timestamp":
+            return EPOCH + timedelta_from_polar(value[tag])
+        elif tag == "Duration":
+            return timedelta_from_polar(value[tag])
         elif tag == "Dictionary":
             return {k: self.to_python(v) for k, v in value[tag]["fields"].items()}
         elif tag == "ExternalInstance":
@@ -1,6 +1,7 @@
 """Communicate with the Polar virtual machine: load rules, make queries, etc."""
 
 from datetime import datetime, timedelta
+from decimal import Decimal
 import os
 from pathlib import Path
 import sys
@@ -15,6 +16,7 @@ except ImportError:
 
 from .exceptions import (
     PolarRuntimeError,
+    FileLoadingError,
     InlineQueryFailedError,
     ParserError,
     PolarFileExtensionError,
@@ -72,11 +74,15 @@ class Polar:
         self.register_class(bool, name="Boolean")
         self.register_class(int, name="Integer")
         self.register_class(float, name="Float")
+        self.register_class(Decimal, name="Decimal")
         self.register_class(list, name="List")
         self.register_class(dict, name="Dictionary")
+        self.register_class(set, name="Set")
         self.register_class(str, name="String")
         self.register_class(datetime, name="Datetime")
         self.register_class(timedelta, name="Timedelta")
+        self.register_class(datetime, name="Timestamp")
+        self.register_class(timedelta, name="Duration")
 
         # Pre-registered classes.
         for name, cls in classes.items():
@@ -87,8 +93,13 @@ class Polar:
         del self.ffi_polar
 
     def load_file(self, policy_file):
-        """Load Polar policy from a ".polar" file."""
-        policy_file = Path(policy_file)
+        """Load Polar policy from a ".polar" file, after the files it imports
+        that have not been loaded yet."""
+        self._load_file(Path(policy_file), importers=[], loaded=set())
+
+    def _load_file(self, policy_file, importers, loaded):
+        """Load a policy file after its imports. ``importers`` is the chain of
+        files that imported it, and ``loaded`` the files loaded so far."""
         extension = policy_file.suffix
         fname = str(policy_file)
         if not extension == ".polar":
@@ -100,7 +111,18 @@ class Polar:
         except FileNotFoundError:
             raise PolarFileNotFoundError(fname)
 
-        self.load_str(file_data.decode("utf-8"), policy_file)
+        policy = file_data.decode("utf-8")
+        importers.append(fname)
+        for imported in self.ffi_polar.imports(policy, fname):
+            if imported in importers:
+                chain = importers[importers.index(imported) :] + [imported]
+                raise FileLoadingError(f"Import cycle: {' -> '.join(chain)}")
+            if imported not in loaded:
+                self._load_file(Path(imported), importers, loaded)
+        importers.pop()
+
+        self.load_str(policy, policy_file)
+        loaded.add(fname)
 
     def load_str(self, string, filename=None):
         """Load a Polar string, checking that all inline queries succeed."""
@@ -121,10 +143,48 @@ class Polar:
     def clear_rules(self):
         self.ffi_polar.clear_rules()
 
-    def query(self, query, *, bindings=None, accept_expression=False):
+    def annotations(self, rule_name):
+        """Return the annotations on each definition of the rule ``rule_name``,
+        in the order the definitions were loaded.
+
+        Each annotation is a dict with its ``"name"`` and its ``"args"``, e.g.,
+        ``{"name": "owner", "args": ["billing-team"]}`` for
+        ``@owner("billing-team")``.
+        """
+        return [
+            [self._annotation(annotation) for annotation in definition]
+            for definition in self.ffi_polar.annotations(rule_name)
+        ]
+
+    def docs(self):
+        """Return the documentation of the loaded rules, rule types and
+        constants, from their ``##`` doc comments.
+
+        The result is a dict with ``"rules"`` and ``"types"``, which map each
+        rule name to a list of definitions with their ``"signature"``,
+        ``"doc"`` and ``"annotations"``, and ``"constants"``, which maps each
+        constant name to its ``"definition"`` and ``"doc"``.
+        """
+        docs = self.ffi_polar.docs()
+        for definitions in [*docs["rules"].values(), *docs["types"].values()]:
+            for definition in definitions:
+                definition["annotations"] = [
+                    self._annotation(annotation)
+                    for annotation in definition["annotations"]
+                ]
+        return docs
+
+    def _annotation(self, annotation):
+        return {
+            "name": annotation["name"],
+            "args": [self.host.to_python(arg) for arg in annotation["args"]],
+        }
+
+    def query(self, query, *, bindings=None, accept_expression=False, now=None):
         """Query for a predicate, parsing it if necessary.
 
         :param query: The predicate to query for.
+        :param now: A timezone-aware datetime to use as ``now()`` in the query.
 
         :return: The result of the query.
         """
@@ -138,7 +198,7 @@ class Polar:
         else:
             raise InvalidQueryTypeError()
 
-        for res in Query(query, host=host, bindings=bindings).run():
+        for res in Query(query, host=host, bindings=bindings, now=now).run():
             yield res
 
     def query_rule(self, name, *args, **kwargs):
@@ -195,6 +255,10 @@ class Polar:
         """Register `value` as a Polar constant variable called `name`."""
         self.ffi_polar.register_constant(self.host.to_polar(value), name)
 
+    def ignore_warning(self, code):
+        """Stop reporting warnings with the given code, e.g., ``"undefined-rule"``."""
+        self.ffi_polar.ignore_warning(code)
+
     def get_class(self, name):
         """Return class registered for ``name``.
 
@@ -23,12 +23,14 @@ class QueryResult:
 class Query:
     """Execute a Polar query through the FFI/event interface."""
 
-    def __init__(self, ffi_query, *, host=None, bindings=None):
+    def __init__(self, ffi_query, *, host=None, bindings=None, now=None):
         self.ffi_query = ffi_query
         self.host = host
         self.calls = {}
         for (k, v) in (bindings or {}).items():
             self.bind(k, v)
+        if now is not None:
+            self.set_now(now)
 
     def __del__(self):
         del self.host
@@ -38,6 +40,10 @@ class Query:
         """Bind `name` to `value` for the duration of the query."""
         self.ffi_query.bind(name, self.host.to_polar(value))
 
+    def set_now(self, now):
+        """Pin the time returned by ``now()`` to the timezone-aware datetime ``now``."""
+        self.ffi_query.set_now(self.host.to_polar(now))
+
     def run(self):
         """Run the event loop and yield results."""
         assert self.ffi_query, "no query to run"
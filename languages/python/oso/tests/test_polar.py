@@ -1,4 +1,5 @@
-from datetime import datetime
+from datetime import datetime, timedelta, timezone
+from decimal import Decimal
 from math import inf, isnan, nan
 from pathlib import Path
 
@@ -43,6 +44,34 @@ def test_data_conversions(polar, qvar, query):
     assert repr(x) == "Variable('y')"
 
 
+def test_set_conversions(polar, qvar, query):
+    polar.load_str("shared(a, b, s) if intersection(a, b, s);")
+    assert qvar("x = {2, 1, 2}", "x", one=True) == {1, 2}
+    result = query(
+        Predicate("shared", [{1, 2, 3}, frozenset([2, 3, 4]), Variable("s")])
+    )
+    assert result == [{"s": {2, 3}}]
+    assert query("{1} matches Set")
+
+
+def test_timestamp_conversions(polar, qvar):
+    expiry = datetime(2021, 6, 1, 9, tzinfo=timezone.utc)
+    polar.load_str('allow(expiry) if now() < expiry - duration("1m");')
+    assert qvar('x = timestamp("2021-06-01T11:00:00+02:00")', "x", one=True) == expiry
+    assert qvar('x = duration("1h30m")', "x", one=True) == timedelta(minutes=90)
+    assert list(polar.query_rule("allow", expiry, now=expiry - timedelta(minutes=2)))
+    assert not list(polar.query_rule("allow", expiry, now=expiry))
+
+
+def test_decimal_conversions(polar, qvar):
+    polar.load_str("under_limit(spent) if spent + 0.10d <= 100.00d;")
+    assert qvar("x = 0.1d + 0.2d", "x", one=True) == Decimal("0.3")
+    assert qvar("x = 19.99d * 3", "x", one=True) == Decimal("59.97")
+    assert list(polar.query_rule("under_limit", Decimal("99.90")))
+    assert not list(polar.query_rule("under_limit", Decimal("99.91")))
+    assert list(polar.query_rule("under_limit", 99))
+
+
 def test_load_function(polar, query, qvar):
     """Make sure the load function works."""
     # Loading the same file twice doesn't mess stuff up.
@@ -71,6 +100,76 @@ def test_load_function(polar, query, qvar):
     assert query("g(x)") == [{"x": 1}, {"x": 2}, {"x": 3}]
 
 
+def test_load_file_imports(polar, qvar, tmp_path):
+    def write(name, src):
+        path = tmp_path / name
+        path.parent.mkdir(parents=True, exist_ok=True)
+        path.write_text(src)
+        return path
+
+    write("shared/roles.polar", 'role("admin");')
+    write(
+        "shared/users.polar",
+        'import "roles.polar";\nuser("alice", r) if role(r);',
+    )
+    policy = write(
+        "policy.polar",
+        'import "./shared/users.polar";\nimport "shared/roles.polar";\n'
+        "allow(u) if user(u, _);",
+    )
+
+    # Both files import shared/roles.polar, which is only loaded once.
+    polar.load_file(policy)
+    assert qvar("allow(u)", "u") == ["alice"]
+    assert qvar("role(r)", "r") == ["admin"]
+
+    write("a.polar", 'import "b.polar";')
+    write("b.polar", 'import "a.polar";')
+    with pytest.raises(exceptions.FileLoadingError) as e:
+        polar.load_file(tmp_path / "a.polar")
+    a, b = tmp_path / "a.polar", tmp_path / "b.polar"
+    assert str(e.value) == f"Import cycle: {a} -> {b} -> {a}"
+
+
+def test_annotations(polar):
+    polar.load_str('@owner("billing-team") @tag("pii", 1) f(1);\nf(2);')
+    assert polar.annotations("f") == [
+        [
+            {"name": "owner", "args": ["billing-team"]},
+            {"name": "tag", "args": ["pii", 1]},
+        ],
+        [],
+    ]
+    assert polar.annotations("g") == []
+
+
+def test_docs(polar):
+    polar.load_str(
+        """## Who owns a resource.
+        @tag("pii") owner(user, _resource) if user = "alice";
+        ## The admin roles.
+        const ADMIN_ROLES = ["owner"];"""
+    )
+    assert polar.docs() == {
+        "rules": {
+            "owner": [
+                {
+                    "signature": "owner(user, _resource)",
+                    "doc": "Who owns a resource.",
+                    "annotations": [{"name": "tag", "args": ["pii"]}],
+                }
+            ]
+        },
+        "types": {},
+        "constants": {
+            "ADMIN_ROLES": {
+                "definition": 'const ADMIN_ROLES = ["owner"]',
+                "doc": "The admin roles.",
+            }
+        },
+    }
+
+
 def test_clear_rules(polar, query):
     class Test:
         pass
@@ -409,7 +508,7 @@ def test_parser_errors(polar):
     """
     with pytest.raises(exceptions.UnrecognizedToken) as e:
         polar.load_str(rules)
-    assert str(e.value) == "did not expect to find the token '1' at line 2, column 5"
+    assert str(e.value) == "did not expect to find the token '1', expected `?=`, `@` or a name at line 2, column 5"
 
     # ExtraToken -- not sure what causes this
 
@@ -1,5 +1,6 @@
 # frozen_string_literal: true
 
+require 'oso/polar/duration'
 require 'oso/polar/errors'
 require 'oso/polar/ffi'
 require 'oso/polar/host'
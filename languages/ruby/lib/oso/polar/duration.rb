@@ -0,0 +1,32 @@
+# frozen_string_literal: true
+
+module Oso
+  module Polar
+    # Polar duration, e.g., the result of `duration("1h30m")` or of
+    # subtracting two timestamps.
+    class Duration
+      attr_reader :seconds, :nanos
+
+      # @param seconds [Integer] Negative for a duration before zero.
+      # @param nanos [Integer] Counted forward from `seconds`, less than a second.
+      def initialize(seconds, nanos: 0)
+        @seconds = seconds
+        @nanos = nanos
+      end
+
+      # @param other [Duration]
+      # @return [Boolean]
+      def ==(other)
+        other.is_a?(Duration) && seconds == other.seconds && nanos == other.nanos
+      end
+
+      # @see #==
+      alias eql? ==
+
+      # @return [Integer]
+      def hash
+        [seconds, nanos].hash
+      end
+    end
+  end
+end
@@ -13,12 +13,16 @@ module Oso
 
           attach_function :new, :polar_new, [], FFI::Polar
           attach_function :load, :polar_load, [FFI::Polar, :string, :string], :int32
+          attach_function :imports, :polar_imports, [FFI::Polar, :string, :string], FFI::Source
+          attach_function :annotations, :polar_annotations, [FFI::Polar, :string], FFI::Source
+          attach_function :docs, :polar_docs, [FFI::Polar], FFI::Source
           attach_function :clear_rules, :polar_clear_rules, [FFI::Polar], :int32
           attach_function :next_inline_query, :polar_next_inline_query, [FFI::Polar, :uint32], FFI::Query
           attach_function :new_id, :polar_get_external_id, [FFI::Polar], :uint64
           attach_function :new_query_from_str, :polar_new_query, [FFI::Polar, :string, :uint32], FFI::Query
           attach_function :new_query_from_term, :polar_new_query_from_term, [FFI::Polar, :string, :uint32], FFI::Query
           attach_function :register_constant, :polar_register_constant, [FFI::Polar, :string, :string], :int32
+          attach_function :ignore_warning, :polar_ignore_warning, [FFI::Polar, :string], :int32
           attach_function :next_message, :polar_next_polar_message, [FFI::Polar], FFI::Message
           attach_function :free, :polar_free, [FFI::Polar], :int32
         end
@@ -42,6 +46,36 @@ module Oso
           raise FFI::Error.get if loaded.zero?
         end
 
+        # @param src [String] Contents of a policy file.
+        # @param filename [String]
+        # @return [Array<String>] the files it imports that have not been loaded yet.
+        # @raise [FFI::Error] if the FFI call returns an error.
+        def imports(src, filename)
+          imports = Rust.imports(self, src, filename)
+          raise FFI::Error.get if imports.null?
+
+          JSON.parse(imports.to_s)
+        end
+
+        # @param name [String] Name of a rule.
+        # @return [Array<Array<Hash>>] the annotations on each definition of the rule.
+        # @raise [FFI::Error] if the FFI call returns an error.
+        def annotations(name)
+          annotations = Rust.annotations(self, name)
+          raise FFI::Error.get if annotations.null?
+
+          JSON.parse(annotations.to_s)
+        end
+
+        # @return [Hash] the documentation of the loaded rules, rule types and constants.
+        # @raise [FFI::Error] if the FFI call returns an error.
+        def docs
+          docs = Rust.docs(self)
+          raise FFI::Error.get if docs.null?
+
+          JSON.parse(docs.to_s)
+        end
+
         # @raise [FFI::Error] if the FFI call returns an error.
         def clear_rules
           cleared = Rust.clear_rules(self)
@@ -99,6 +133,13 @@ module Oso
           raise FFI::Error.get if registered.zero?
         end
 
+        # @param code [String]
+        # @raise [FFI::Error] if the FFI call returns an error.
+        def ignore_warning(code)
+          ignored = Rust.ignore_warning(self, code)
+          raise FFI::Error.get if ignored.zero?
+        end
+
         def next_message
           Rust.next_message(self)
         end
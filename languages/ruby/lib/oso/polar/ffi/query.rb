@@ -15,6 +15,7 @@ module Oso
           attach_function :call_result, :polar_call_result, [FFI::Query, :uint64, :string], :int32
           attach_function :question_result, :polar_question_result, [FFI::Query, :uint64, :int32], :int32
           attach_function :application_error, :polar_application_error, [FFI::Query, :string], :int32
+          attach_function :set_now, :polar_query_set_now, [FFI::Query, :string], :int32
           attach_function :next_event, :polar_next_query_event, [FFI::Query], FFI::QueryEvent
           attach_function :next_message, :polar_next_query_message, [FFI::Query], FFI::Message
           attach_function :source, :polar_query_source_info, [FFI::Query], FFI::Source
@@ -55,6 +56,13 @@ module Oso
           raise FFI::Error.get if res.zero?
         end
 
+        # @param now [Hash<String, Object>] a timestamp term.
+        # @raise [FFI::Error] if the FFI call returns an error.
+        def now=(now)
+          res = Rust.set_now(self, JSON.dump(now))
+          raise FFI::Error.get if res.zero?
+        end
+
         # @return [::Oso::Polar::QueryEvent]
         # @raise [FFI::Error] if the FFI call returns an error.
         def next_event
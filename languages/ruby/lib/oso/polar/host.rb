@@ -1,5 +1,8 @@
 # frozen_string_literal: true
 
+require 'bigdecimal'
+require 'set'
+
 module Oso
   module Polar
     # Translate between Polar and the host language (Ruby).
@@ -163,10 +166,18 @@ module Oso
                     value = 'NaN'
                   end
                   { 'Number' => { 'Float' => value } }
+                when value.instance_of?(BigDecimal)
+                  { 'Number' => { 'Decimal' => value.to_s('F') } }
                 when value.instance_of?(String)
                   { 'String' => value }
                 when value.instance_of?(Array)
                   { 'List' => value.map { |el| to_polar(el) } }
+                when value.instance_of?(Set)
+                  { 'Set' => value.map { |el| to_polar(el) } }
+                when value.is_a?(Time)
+                  { 'Timestamp' => { 'seconds' => value.to_i, 'nanos' => value.nsec } }
+                when value.instance_of?(Duration)
+                  { 'Duration' => { 'seconds' => value.seconds, 'nanos' => value.nanos } }
                 when value.instance_of?(Hash)
                   { 'Dictionary' => { 'fields' => value.transform_values { |v| to_polar(v) } } }
                 when value.instance_of?(Predicate)
@@ -195,6 +206,8 @@ module Oso
           value
         when 'Number'
           num = value.values.first
+          return BigDecimal(num) if value.key? 'Decimal'
+
           if value.key? 'Float'
             case num
             when 'Infinity'
@@ -212,6 +225,12 @@ module Oso
           num
         when 'List'
           value.map { |el| to_ruby(el) }
+        when 'Set'
+          value.map { |el| to_ruby(el) }.to_set
+        when 'Timestamp'
+          Time.at(value['seconds'] + Rational(value['nanos'], 1_000_000_000)).utc
+        when 'Duration'
+          Duration.new(value['seconds'], nanos: value['nanos'])
         when 'Dictionary'
           value['fields'].transform_values { |v| to_ruby(v) }
         when 'ExternalInstance'
@@ -45,7 +45,11 @@ module Oso
         register_class PolarBoolean, name: 'Boolean'
         register_class Integer
         register_class Float
+        register_class BigDecimal, name: 'Decimal'
         register_class Array, name: 'List'
+        register_class Set
+        register_class Time, name: 'Timestamp'
+        register_class Duration
         register_class Hash, name: 'Dictionary'
         register_class String
       end
@@ -58,19 +62,45 @@ module Oso
         self
       end
 
-      # Load a Polar policy file.
+      # The annotations on each definition of a rule, in the order the
+      # definitions were loaded, e.g., `{ 'name' => 'owner', 'args' =>
+      # ['billing-team'] }` for `@owner("billing-team")`.
+      #
+      # @param rule_name [String]
+      # @return [Array<Array<Hash>>]
+      # @raise [Error] if the FFI call raises one.
+      def annotations(rule_name)
+        ffi_polar.annotations(rule_name).map do |definition|
+          definition.map { |annotation| to_ruby_annotation(annotation) }
+        end
+      end
+
+      # The documentation of the loaded rules, rule types and constants, from
+      # their `##` doc comments. `'rules'` and `'types'` map each rule name to
+      # its definitions, with their `'signature'`, `'doc'` and `'annotations'`,
+      # and `'constants'` maps each constant name to its `'definition'` and
+      # `'doc'`.
+      #
+      # @return [Hash]
+      # @raise [Error] if the FFI call raises one.
+      def docs
+        docs = ffi_polar.docs
+        (docs['rules'].values + docs['types'].values).flatten.each do |definition|
+          definition['annotations'] = definition['annotations'].map { |annotation| to_ruby_annotation(annotation) }
+        end
+        docs
+      end
+
+      # Load a Polar policy file, after the files it imports that have not
+      # been loaded yet.
       #
       # @param name [String]
       # @raise [PolarFileExtensionError] if provided filename has invalid extension.
       # @raise [PolarFileNotFoundError] if provided filename does not exist.
+      # @raise [FileLoadingError] if files import each other in a cycle.
       # @return [self] for chaining.
       def load_file(name)
-        raise PolarFileExtensionError, name unless File.extname(name) == '.polar'
-
-        file_data = File.open(name, &:read)
-        load_str(file_data, filename: name)
-      rescue Errno::ENOENT
-        raise PolarFileNotFoundError, name
+        load_file_with_imports(name.to_s, importers: [], loaded: [])
       end
 
       # Load a Polar string into the KB.
@@ -100,15 +130,17 @@ module Oso
 
       # Query for a Polar predicate or string.
       #
-      # @overload query(query)
+      # @overload query(query, now: nil)
       #   @param query [String]
+      #   @param now [Time] the time returned by `now()` in the query.
       #   @return [Enumerator] of resulting bindings
       #   @raise [Error] if the FFI call raises one.
-      # @overload query(query)
+      # @overload query(query, now: nil)
       #   @param query [Predicate]
+      #   @param now [Time] the time returned by `now()` in the query.
       #   @return [Enumerator] of resulting bindings
       #   @raise [Error] if the FFI call raises one.
-      def query(query)
+      def query(query, now: nil)
         new_host = host.dup
         case query
         when String
@@ -118,7 +150,7 @@ module Oso
         else
           raise InvalidQueryTypeError
         end
-        Query.new(ffi_query, host: new_host).results
+        Query.new(ffi_query, host: new_host, now: now).results
       end
 
       # Query for a rule.
@@ -155,6 +187,16 @@ module Oso
         self
       end
 
+      # Stop reporting warnings with the given code, e.g., "undefined-rule".
+      #
+      # @param code [String]
+      # @return [self] for chaining.
+      # @raise [FFI::Error] if the FFI call returns an error.
+      def ignore_warning(code)
+        ffi_polar.ignore_warning(code)
+        self
+      end
+
       # Start a REPL session.
       #
       # @param files [Array<String>]
@@ -175,6 +217,40 @@ module Oso
       # @return [FFI::Polar]
       attr_reader :ffi_polar
 
+      # @param annotation [Hash<String, Object>] an annotation with Polar arguments.
+      # @return [Hash<String, Object>] the annotation with Ruby arguments.
+      def to_ruby_annotation(annotation)
+        { 'name' => annotation['name'], 'args' => annotation['args'].map { |arg| host.to_ruby(arg) } }
+      end
+
+      # Load a Polar policy file after the files it imports.
+      #
+      # @param name [String]
+      # @param importers [Array<String>] the chain of files that imported it.
+      # @param loaded [Array<String>] the files loaded so far.
+      def load_file_with_imports(name, importers:, loaded:) # rubocop:disable Metrics/MethodLength
+        raise PolarFileExtensionError, name unless File.extname(name) == '.polar'
+
+        begin
+          file_data = File.open(name, &:read)
+        rescue Errno::ENOENT
+          raise PolarFileNotFoundError, name
+        end
+
+        importers.push(name)
+        ffi_polar.imports(file_data, name).each do |import|
+          if importers.include?(import)
+            chain = importers[importers.index(import)..-1] + [import]
+            raise FileLoadingError, "Import cycle: #{chain.join(' -> ')}"
+          end
+          load_file_with_imports(import, importers: importers, loaded: loaded) unless loaded.include?(import)
+        end
+        importers.pop
+
+        load_str(file_data, filename: name)
+        loaded.push(name)
+      end
+
       # The R and L in REPL for systems where readline is available.
       def repl_readline(prompt)
         while (buf = Readline.readline(prompt, true))
@@ -11,10 +11,12 @@ module Oso
 
       # @param ffi_query [FFI::Query]
       # @param host [Oso::Polar::Host]
-      def initialize(ffi_query, host:)
+      # @param now [Time] pins the time returned by `now()`; defaults to when the query starts.
+      def initialize(ffi_query, host:, now: nil)
         @calls = {}
         @ffi_query = ffi_query
         @host = host
+        ffi_query.now = host.to_polar(now) unless now.nil?
         @results = start
       end
 
@@ -1,6 +1,8 @@
 # frozen_string_literal: true
 
+require 'fileutils'
 require 'tempfile'
+require 'tmpdir'
 
 require_relative './helpers'
 
@@ -50,6 +52,40 @@ RSpec.describe Oso::Polar::Polar do # rubocop:disable Metrics/BlockLength
       expect(qvar(subject, 'f(x)', 'x', one: true)).to eq({ 'x' => [1, 'two', true], 'y' => { 'z' => false } })
     end
 
+    it 'converts sets in both directions' do
+      subject.load_str('shared(a, b, s) if intersection(a, b, s);')
+      expect(qvar(subject, 'x = {2, 1, 2}', 'x', one: true)).to eq(Set[1, 2])
+      result = subject.query_rule('shared', Set[1, 2, 3], Set[2, 3, 4], Oso::Polar::Variable.new('s')).to_a
+      expect(result).to eq([{ 's' => Set[2, 3] }])
+      expect(query(subject, '{1} matches Set').length).to be 1
+    end
+
+    it 'converts decimals in both directions' do
+      subject.load_str('add(a, b, a + b);')
+      result = subject.query_rule('add', BigDecimal('1.50'), 1, Oso::Polar::Variable.new('x')).to_a
+      expect(result).to eq([{ 'x' => BigDecimal('2.5') }])
+      expect(qvar(subject, 'x = 1.50d', 'x', one: true)).to eq(BigDecimal('1.5'))
+      expect(query(subject, '1.50d matches Decimal').length).to be 1
+    end
+
+    it 'converts timestamps and durations in both directions' do
+      subject.load_str('later(t, d, t + d);')
+      t = Time.utc(2021, 6, 1, 9)
+      result = subject.query_rule('later', t, Oso::Polar::Duration.new(1, nanos: 500_000_000),
+                                  Oso::Polar::Variable.new('x')).to_a
+      expect(result).to eq([{ 'x' => Time.utc(2021, 6, 1, 9, 0, 1.5) }])
+      expect(qvar(subject, 'x = duration("1m30s")', 'x', one: true)).to eq(Oso::Polar::Duration.new(90))
+      expect(query(subject, 'now() matches Timestamp and duration("1s") matches Duration').length).to be 1
+    end
+
+    it 'pins now() per query' do
+      subject.load_str('allow(expiry) if now() < expiry;')
+      expiry = Time.utc(2021, 6, 1, 9)
+      allow = Oso::Polar::Predicate.new('allow', args: [expiry])
+      expect(subject.query(allow, now: expiry - 1).to_a.length).to be 1
+      expect(subject.query(allow, now: expiry).to_a).to be_empty
+    end
+
     it 'converts predicates in both directions' do
       subject.load_str('f(x) if x = pred(1, 2);')
       expect(qvar(subject, 'f(x)', 'x')).to eq([Oso::Polar::Predicate.new('pred', args: [1, 2])])
@@ -115,7 +151,7 @@ RSpec.describe Oso::Polar::Polar do # rubocop:disable Metrics/BlockLength
 
       expect { subject.load_file(file.path) }.to raise_error do |e|
         expect(e).to be_an Oso::Polar::ParseError::UnrecognizedToken
-        expect(e.message).to eq("did not expect to find the token ';' at line 1, column 1 in file #{file.path}")
+        expect(e.message).to eq("did not expect to find the token ';', expected `?=`, `@` or a name at line 1, column 1 in file #{file.path}")
       end
     end
 
@@ -148,6 +184,35 @@ RSpec.describe Oso::Polar::Polar do # rubocop:disable Metrics/BlockLength
       expect(qvar(subject, 'f(x)', 'x')).to eq([1, 2, 3])
       expect(qvar(subject, 'g(x)', 'x')).to eq([1, 2, 3])
     end
+
+    it 'loads the files a policy imports first' do # rubocop:disable Metrics/BlockLength
+      Dir.mktmpdir do |dir|
+        write = lambda do |name, src|
+          path = File.join(dir, name)
+          FileUtils.mkdir_p(File.dirname(path))
+          File.write(path, src)
+          path
+        end
+        write.call('shared/roles.polar', 'role("admin");')
+        write.call('shared/users.polar', %(import "roles.polar";\nuser("alice", r) if role(r);))
+        policy = write.call(
+          'policy.polar',
+          %(import "./shared/users.polar";\nimport "shared/roles.polar";\nallow(u) if user(u, _);)
+        )
+
+        # Both files import shared/roles.polar, which is only loaded once.
+        subject.load_file(policy)
+        expect(qvar(subject, 'allow(u)', 'u')).to eq(['alice'])
+        expect(qvar(subject, 'role(r)', 'r')).to eq(['admin'])
+
+        a = write.call('a.polar', 'import "b.polar";')
+        b = write.call('b.polar', 'import "a.polar";')
+        expect { subject.load_file(a) }.to raise_error do |e|
+          expect(e).to be_an Oso::Polar::FileLoadingError
+          expect(e.message).to eq("Import cycle: #{a} -> #{b} -> #{a}")
+        end
+      end
+    end
   end
 
   context '#clear_rules' do
@@ -166,6 +231,45 @@ RSpec.describe Oso::Polar::Polar do # rubocop:disable Metrics/BlockLength
     end
   end
 
+  context '#annotations' do
+    it 'returns the annotations on each definition of a rule' do
+      subject.load_str(%(@owner("billing-team") @tag("pii", 1) f(1);\nf(2);))
+      expect(subject.annotations('f')).to eq(
+        [
+          [{ 'name' => 'owner', 'args' => ['billing-team'] }, { 'name' => 'tag', 'args' => ['pii', 1] }],
+          []
+        ]
+      )
+      expect(subject.annotations('g')).to eq([])
+    end
+  end
+
+  context '#docs' do
+    it 'returns the documentation of the loaded rules and constants' do
+      subject.load_str <<~POLAR
+        ## Who owns a resource.
+        @tag("pii") owner(user, _resource) if user = "alice";
+        ## The admin roles.
+        const ADMIN_ROLES = ["owner"];
+      POLAR
+      expect(subject.docs).to eq(
+        'rules' => {
+          'owner' => [
+            {
+              'signature' => 'owner(user, _resource)',
+              'doc' => 'Who owns a resource.',
+              'annotations' => [{ 'name' => 'tag', 'args' => ['pii'] }]
+            }
+          ]
+        },
+        'types' => {},
+        'constants' => {
+          'ADMIN_ROLES' => { 'definition' => 'const ADMIN_ROLES = ["owner"]', 'doc' => 'The admin roles.' }
+        }
+      )
+    end
+  end
+
   context '#query' do
     # test_basic_queries
     it 'is able to make basic queries' do
@@ -650,7 +754,7 @@ RSpec.describe Oso::Polar::Polar do # rubocop:disable Metrics/BlockLength
       POLAR
       expect { subject.load_str(rule) }.to raise_error do |e|
         expect(e).to be_an Oso::Polar::ParseError::UnrecognizedToken
-        expect(e.message).to eq("did not expect to find the token '1' at line 1, column 1")
+        expect(e.message).to eq("did not expect to find the token '1', expected `?=`, `@` or a name at line 1, column 1")
       end
     end
 
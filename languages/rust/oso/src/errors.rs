@@ -39,6 +39,17 @@ pub enum OsoError {
     #[error("{operation} are unimplemented in the oso Rust library")]
     UnimplementedOperation { operation: String },
 
+    /// An error loading a file that another policy file imports. `chain` lists the files from
+    /// the one that was loaded to the one that failed.
+    #[error("{source} (import chain: {})", chain.join(" -> "))]
+    ImportError {
+        source: Box<OsoError>,
+        chain: Vec<String>,
+    },
+
+    #[error("Import cycle: {}", chain.join(" -> "))]
+    ImportCycle { chain: Vec<String> },
+
     #[error("Inline query failed {location}")]
     InlineQueryFailedError { location: String },
 
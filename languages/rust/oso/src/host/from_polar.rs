@@ -3,9 +3,13 @@
 //! Polar types back to Rust types.
 
 use std::collections::hash_map::HashMap;
+use std::collections::{BTreeSet, HashSet};
 use std::convert::TryFrom;
+use std::hash::Hash;
+use std::time::{Duration, SystemTime};
 
 use impl_trait_for_tuples::*;
+use polar_core::terms::Decimal;
 
 use super::class::Instance;
 use super::PolarValue;
@@ -106,6 +110,36 @@ impl FromPolar for bool {
     }
 }
 
+impl FromPolar for Decimal {
+    fn from_polar(val: PolarValue) -> crate::Result<Self> {
+        if let PolarValue::Decimal(d) = val {
+            Ok(d)
+        } else {
+            Err(TypeError::expected("Decimal").user())
+        }
+    }
+}
+
+impl FromPolar for SystemTime {
+    fn from_polar(val: PolarValue) -> crate::Result<Self> {
+        if let PolarValue::Timestamp(t) = val {
+            Ok(t)
+        } else {
+            Err(TypeError::expected("Timestamp").user())
+        }
+    }
+}
+
+impl FromPolar for Duration {
+    fn from_polar(val: PolarValue) -> crate::Result<Self> {
+        if let PolarValue::Duration(d) = val {
+            Ok(d)
+        } else {
+            Err(TypeError::expected("Duration").user())
+        }
+    }
+}
+
 impl<T: FromPolar> FromPolar for HashMap<String, T> {
     fn from_polar(val: PolarValue) -> crate::Result<Self> {
         if let PolarValue::Map(map) = val {
@@ -135,6 +169,26 @@ impl<T: FromPolar> FromPolar for Vec<T> {
     }
 }
 
+/// Sets are converted from Polar sets or lists.
+impl<T: FromPolar + Eq + Hash> FromPolar for HashSet<T> {
+    fn from_polar(val: PolarValue) -> crate::Result<Self> {
+        match val {
+            PolarValue::Set(s) | PolarValue::List(s) => s.into_iter().map(T::from_polar).collect(),
+            _ => Err(TypeError::expected("Set").user()),
+        }
+    }
+}
+
+/// Sets are converted from Polar sets or lists.
+impl<T: FromPolar + Ord> FromPolar for BTreeSet<T> {
+    fn from_polar(val: PolarValue) -> crate::Result<Self> {
+        match val {
+            PolarValue::Set(s) | PolarValue::List(s) => s.into_iter().map(T::from_polar).collect(),
+            _ => Err(TypeError::expected("Set").user()),
+        }
+    }
+}
+
 impl<T: FromPolar> FromPolar for Option<T> {
     fn from_polar(val: PolarValue) -> crate::Result<Self> {
         // if the value is a Option<PolarValue>, convert from PolarValue
@@ -179,6 +233,9 @@ try_from_polar!(i64);
 try_from_polar!(f64);
 try_from_polar!(String);
 try_from_polar!(bool);
+try_from_polar!(Decimal);
+try_from_polar!(SystemTime);
+try_from_polar!(Duration);
 
 impl<T: FromPolar> TryFrom<PolarValue> for HashMap<String, T> {
     type Error = crate::OsoError;
@@ -196,6 +253,22 @@ impl<T: FromPolar> TryFrom<PolarValue> for Vec<T> {
     }
 }
 
+impl<T: FromPolar + Eq + Hash> TryFrom<PolarValue> for HashSet<T> {
+    type Error = crate::OsoError;
+
+    fn try_from(v: PolarValue) -> Result<Self, Self::Error> {
+        Self::from_polar(v)
+    }
+}
+
+impl<T: FromPolar + Ord> TryFrom<PolarValue> for BTreeSet<T> {
+    type Error = crate::OsoError;
+
+    fn try_from(v: PolarValue) -> Result<Self, Self::Error> {
+        Self::from_polar(v)
+    }
+}
+
 mod private {
     /// Prevents implementations of `FromPolarList` outside of this crate
     pub trait Sealed {}
@@ -216,9 +289,12 @@ impl FromPolar for Instance {
             PolarValue::Boolean(b) => Instance::new(b),
             PolarValue::Integer(i) => Instance::new(i),
             PolarValue::Float(f) => Instance::new(f),
-            PolarValue::List(v) => Instance::new(v),
+            PolarValue::Decimal(d) => Instance::new(d),
+            PolarValue::List(v) | PolarValue::Set(v) => Instance::new(v),
             PolarValue::String(s) => Instance::new(s),
             PolarValue::Map(d) => Instance::new(d),
+            PolarValue::Timestamp(t) => Instance::new(t),
+            PolarValue::Duration(d) => Instance::new(d),
             PolarValue::Instance(instance) => instance,
             v => {
                 tracing::warn!(value = ?v, "invalid conversion attempted");
@@ -14,6 +14,7 @@ mod value;
 pub use class::{Class, ClassBuilder, Instance};
 pub use from_polar::{FromPolar, FromPolarList};
 pub use to_polar::{PolarIterator, ToPolar, ToPolarList};
+pub(crate) use value::to_timestamp;
 pub use value::PolarValue;
 
 lazy_static::lazy_static! {
@@ -170,8 +171,12 @@ impl Host {
             PolarValue::Boolean(_) => class_tag == "Boolean",
             PolarValue::Map(_) => class_tag == "Dictionary",
             PolarValue::List(_) => class_tag == "List",
+            PolarValue::Set(_) => class_tag == "Set",
+            PolarValue::Timestamp(_) => class_tag == "Timestamp",
+            PolarValue::Duration(_) => class_tag == "Duration",
             PolarValue::Integer(_) => class_tag == "Integer",
             PolarValue::Float(_) => class_tag == "Float",
+            PolarValue::Decimal(_) => class_tag == "Decimal",
             PolarValue::String(_) => class_tag == "String",
             _ => false,
         };
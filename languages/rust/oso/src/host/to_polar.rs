@@ -2,8 +2,10 @@
 //! Rust types back to Polar types.
 
 use impl_trait_for_tuples::*;
+use polar_core::terms::Decimal;
 
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::time::{Duration, SystemTime};
 
 use super::DEFAULT_CLASSES;
 use crate::PolarValue;
@@ -172,6 +174,36 @@ impl<'a, T: Clone + ToPolar> ToPolar for &'a [T] {
     }
 }
 
+impl<T: ToPolar> ToPolar for HashSet<T> {
+    fn to_polar(self) -> PolarValue {
+        PolarValue::Set(self.into_iter().map(|v| v.to_polar()).collect())
+    }
+}
+
+impl<T: ToPolar> ToPolar for BTreeSet<T> {
+    fn to_polar(self) -> PolarValue {
+        PolarValue::Set(self.into_iter().map(|v| v.to_polar()).collect())
+    }
+}
+
+impl ToPolar for Decimal {
+    fn to_polar(self) -> PolarValue {
+        PolarValue::Decimal(self)
+    }
+}
+
+impl ToPolar for SystemTime {
+    fn to_polar(self) -> PolarValue {
+        PolarValue::Timestamp(self)
+    }
+}
+
+impl ToPolar for Duration {
+    fn to_polar(self) -> PolarValue {
+        PolarValue::Duration(self)
+    }
+}
+
 impl<T: ToPolar> ToPolar for HashMap<String, T> {
     fn to_polar(self) -> PolarValue {
         PolarValue::Map(self.into_iter().map(|(k, v)| (k, v.to_polar())).collect())
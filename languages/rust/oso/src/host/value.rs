@@ -1,5 +1,7 @@
 use polar_core::terms::*;
 use std::collections::hash_map::HashMap;
+use std::convert::TryFrom;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use crate::host::{Host, Instance};
 
@@ -15,10 +17,17 @@ use crate::host::{Host, Instance};
 pub enum PolarValue {
     Integer(i64),
     Float(f64),
+    /// An exact decimal, e.g., `1.50d`.
+    Decimal(Decimal),
     String(String),
     Boolean(bool),
     Map(HashMap<String, PolarValue>),
     List(Vec<PolarValue>),
+    /// The elements of a set, without duplicates.
+    Set(Vec<PolarValue>),
+    Timestamp(SystemTime),
+    /// Polar durations may be negative, but only non-negative ones can be converted.
+    Duration(std::time::Duration),
     Variable(String),
     Instance(Instance),
 }
@@ -29,9 +38,17 @@ impl PartialEq for PolarValue {
             (PolarValue::Boolean(b1), PolarValue::Boolean(b2)) => b1 == b2,
             (PolarValue::Float(f1), PolarValue::Float(f2)) => f1 == f2,
             (PolarValue::Integer(i1), PolarValue::Integer(i2)) => i1 == i2,
+            (PolarValue::Decimal(d1), PolarValue::Decimal(d2)) => {
+                Numeric::Decimal(*d1) == Numeric::Decimal(*d2)
+            }
             (PolarValue::List(l1), PolarValue::List(l2)) => l1 == l2,
+            (PolarValue::Set(s1), PolarValue::Set(s2)) => {
+                s1.len() == s2.len() && s1.iter().all(|v| s2.contains(v))
+            }
             (PolarValue::Map(m1), PolarValue::Map(m2)) => m1 == m2,
             (PolarValue::String(s1), PolarValue::String(s2)) => s1 == s2,
+            (PolarValue::Timestamp(t1), PolarValue::Timestamp(t2)) => t1 == t2,
+            (PolarValue::Duration(d1), PolarValue::Duration(d2)) => d1 == d2,
             _ => false,
         }
     }
@@ -50,6 +67,7 @@ impl PolarValue {
         let val = match term.value() {
             Value::Number(Numeric::Integer(i)) => PolarValue::Integer(*i),
             Value::Number(Numeric::Float(f)) => PolarValue::Float(*f),
+            Value::Number(Numeric::Decimal(d)) => PolarValue::Decimal(*d),
             Value::String(s) => PolarValue::String(s.clone()),
             Value::Boolean(b) => PolarValue::Boolean(*b),
             Value::Dictionary(dict) => {
@@ -71,6 +89,30 @@ impl PolarValue {
                 }
                 PolarValue::List(list)
             }
+            Value::Set(s) => {
+                let mut set = vec![];
+                for t in s.elements() {
+                    set.push(PolarValue::from_term(t, host)?);
+                }
+                PolarValue::Set(set)
+            }
+            Value::Timestamp(Timestamp { seconds, nanos }) => {
+                let whole_seconds = std::time::Duration::from_secs(seconds.unsigned_abs());
+                let nanos = std::time::Duration::from_nanos(u64::from(*nanos));
+                PolarValue::Timestamp(if *seconds < 0 {
+                    UNIX_EPOCH - whole_seconds + nanos
+                } else {
+                    UNIX_EPOCH + whole_seconds + nanos
+                })
+            }
+            Value::Duration(Duration { seconds, nanos }) => match u64::try_from(*seconds) {
+                Ok(seconds) => PolarValue::Duration(std::time::Duration::new(seconds, *nanos)),
+                Err(_) => {
+                    return Err(crate::OsoError::Custom {
+                        message: format!("Negative duration {} is not supported", term.to_polar()),
+                    })
+                }
+            },
             Value::Variable(Symbol(sym)) => PolarValue::Variable(sym.clone()),
             Value::Expression(_) => {
                 return Err(crate::OsoError::Custom {
@@ -95,6 +137,7 @@ This may mean you performed an operation in your policy over an unbound variable
         let value = match self {
             PolarValue::Integer(i) => Value::Number(Numeric::Integer(*i)),
             PolarValue::Float(f) => Value::Number(Numeric::Float(*f)),
+            PolarValue::Decimal(d) => Value::Number(Numeric::Decimal(*d)),
             PolarValue::String(s) => Value::String(s.clone()),
             PolarValue::Boolean(b) => Value::Boolean(*b),
             PolarValue::Map(map) => {
@@ -121,8 +164,27 @@ This may mean you performed an operation in your policy over an unbound variable
                 }
                 Value::List(list)
             }
+            PolarValue::Set(s) => Value::Set(Set::new(s.iter().map(|v| v.to_term(host)).collect())),
+            PolarValue::Timestamp(t) => Value::Timestamp(to_timestamp(*t)),
+            PolarValue::Duration(d) => Value::Duration(Duration {
+                seconds: d.as_secs() as i64,
+                nanos: d.subsec_nanos(),
+            }),
             PolarValue::Variable(s) => Value::Variable(Symbol(s.clone())),
         };
         Term::new_from_ffi(value)
     }
 }
+
+/// Convert a system time to a Polar timestamp. A time outside the years 0000 to 9999 is kept as
+/// is, and Polar reports an arithmetic error if it is added to or subtracted from.
+pub(crate) fn to_timestamp(time: SystemTime) -> Timestamp {
+    let nanos = match time.duration_since(UNIX_EPOCH) {
+        Ok(since_epoch) => since_epoch.as_nanos() as i128,
+        Err(before_epoch) => -(before_epoch.duration().as_nanos() as i128),
+    };
+    Timestamp {
+        seconds: nanos.div_euclid(1_000_000_000) as i64,
+        nanos: nanos.rem_euclid(1_000_000_000) as u32,
+    }
+}
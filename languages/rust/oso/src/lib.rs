@@ -81,11 +81,13 @@ mod host;
 mod oso;
 mod query;
 
-pub use crate::oso::Oso;
+pub use crate::oso::{Annotation, ConstantDocs, Oso, PolicyDocs, RuleDocs};
 pub use errors::{OsoError, Result};
 pub use host::{Class, ClassBuilder, FromPolar, FromPolarList, PolarValue, ToPolar, ToPolarList};
 pub use query::{Query, ResultSet};
 
+pub use polar_core::terms::Decimal;
+
 use polar_core::polar::Polar;
 
 /// Classes that can be used as types in Polar policies.
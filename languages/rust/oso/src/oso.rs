@@ -2,6 +2,7 @@
 
 use polar_core::terms::{Call, Symbol, Term, Value};
 
+use std::collections::BTreeMap;
 use std::fs::File;
 use std::io::Read;
 use std::sync::Arc;
@@ -9,7 +10,7 @@ use std::sync::Arc;
 use crate::host::Host;
 use crate::query::Query;
 use crate::OsoError;
-use crate::{ToPolar, ToPolarList};
+use crate::{PolarValue, ToPolar, ToPolarList};
 
 /// Oso is the main struct you interact with. It is an instance of the Oso authorization library
 /// and contains the polar language knowledge base and query engine.
@@ -19,6 +20,39 @@ pub struct Oso {
     host: Host,
 }
 
+/// An annotation on a rule definition, e.g., `@owner("billing-team")` has the name `owner` and
+/// the arguments `["billing-team"]`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Annotation {
+    pub name: String,
+    pub args: Vec<PolarValue>,
+}
+
+/// The documentation of a rule definition or rule type: its signature, e.g.,
+/// `allow(actor: User, "read", resource)`, its `##` doc comment and its annotations.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RuleDocs {
+    pub signature: String,
+    pub doc: Option<String>,
+    pub annotations: Vec<Annotation>,
+}
+
+/// The documentation of a constant: its definition, e.g.,
+/// `const ADMIN_ROLES = ["owner", "admin"]`, and its `##` doc comment.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConstantDocs {
+    pub definition: String,
+    pub doc: Option<String>,
+}
+
+/// The documentation of the loaded rules, rule types and constants, by name.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PolicyDocs {
+    pub rules: BTreeMap<String, Vec<RuleDocs>>,
+    pub types: BTreeMap<String, Vec<RuleDocs>>,
+    pub constants: BTreeMap<String, ConstantDocs>,
+}
+
 impl Default for Oso {
     fn default() -> Self {
         Self::new()
@@ -68,6 +102,83 @@ impl Oso {
         check_messages!(self.inner);
     }
 
+    /// The annotations on each definition of the rule `rule`, in the order the definitions were
+    /// loaded.
+    pub fn annotations(&self, rule: &str) -> crate::Result<Vec<Vec<Annotation>>> {
+        self.inner
+            .annotations(&Symbol(rule.to_string()))
+            .iter()
+            .map(|definition| self.to_annotations(definition))
+            .collect()
+    }
+
+    /// The documentation of the loaded rules, rule types and constants, from their `##` doc
+    /// comments.
+    pub fn docs(&self) -> crate::Result<PolicyDocs> {
+        let docs = self.inner.docs();
+        let to_rule_docs = |rules: &BTreeMap<Symbol, Vec<polar_core::docs::RuleDocs>>| {
+            rules
+                .iter()
+                .map(|(name, definitions)| {
+                    let definitions = definitions
+                        .iter()
+                        .map(|definition| {
+                            Ok(RuleDocs {
+                                signature: definition.signature.clone(),
+                                doc: definition.doc.clone(),
+                                annotations: self.to_annotations(&definition.annotations)?,
+                            })
+                        })
+                        .collect::<crate::Result<_>>()?;
+                    Ok((name.0.clone(), definitions))
+                })
+                .collect::<crate::Result<_>>()
+        };
+        Ok(PolicyDocs {
+            rules: to_rule_docs(&docs.rules)?,
+            types: to_rule_docs(&docs.types)?,
+            constants: docs
+                .constants
+                .into_iter()
+                .map(|(name, constant)| {
+                    let constant = ConstantDocs {
+                        definition: constant.definition,
+                        doc: constant.doc,
+                    };
+                    (name.0, constant)
+                })
+                .collect(),
+        })
+    }
+
+    fn to_annotations(
+        &self,
+        annotations: &[polar_core::rules::Annotation],
+    ) -> crate::Result<Vec<Annotation>> {
+        annotations
+            .iter()
+            .map(|annotation| {
+                Ok(Annotation {
+                    name: annotation.name.0.clone(),
+                    args: annotation
+                        .args
+                        .iter()
+                        .map(|arg| PolarValue::from_term(arg, &self.host))
+                        .collect::<crate::Result<_>>()?,
+                })
+            })
+            .collect()
+    }
+
+    /// Stop reporting warnings with the given code, e.g., `"undefined-rule"`.
+    pub fn ignore_warning(&self, code: &str) -> crate::Result<()> {
+        let code = code
+            .parse()
+            .map_err(|msg| OsoError::Custom { message: msg })?;
+        self.inner.ignore_warning(code);
+        Ok(())
+    }
+
     fn check_inline_queries(&self) -> crate::Result<()> {
         while let Some(q) = self.inner.next_inline_query(false) {
             let location = q.source_info();
@@ -83,19 +194,55 @@ impl Oso {
     }
 
     /// Load a file containing polar rules. All polar files must end in `.polar`
+    ///
+    /// The files the policy imports, e.g., `import "shared/roles.polar";`, are resolved
+    /// relative to it and loaded first, unless they have been loaded already.
     pub fn load_file<P: AsRef<std::path::Path>>(&self, file: P) -> crate::Result<()> {
-        let file = file.as_ref();
-        if !file.extension().map(|ext| ext == "polar").unwrap_or(false) {
-            return Err(crate::OsoError::IncorrectFileType {
-                filename: file.to_string_lossy().into_owned(),
-            });
+        self.load_file_with_imports(file.as_ref(), &mut vec![])?;
+        self.check_inline_queries()
+    }
+
+    /// Load a policy file after the files it imports. `importers` is the chain of files that
+    /// imported it, starting with the one passed to `load_file`.
+    fn load_file_with_imports(
+        &self,
+        file: &std::path::Path,
+        importers: &mut Vec<String>,
+    ) -> crate::Result<()> {
+        let filename = file.to_string_lossy().into_owned();
+        let imported = |importers: &[String], error: OsoError| {
+            if importers.is_empty() {
+                return error;
+            }
+            let mut chain = importers.to_vec();
+            chain.push(filename.clone());
+            OsoError::ImportError {
+                source: Box::new(error),
+                chain,
+            }
+        };
+        let policy = read_policy(file).map_err(|e| imported(importers, e))?;
+        let imports = self
+            .inner
+            .imports(&policy, &filename)
+            .map_err(|e| imported(importers, e.into()))?;
+
+        importers.push(filename.clone());
+        for import in imports {
+            if let Some(i) = importers.iter().position(|f| *f == import) {
+                let mut chain = importers[i..].to_vec();
+                chain.push(import);
+                return Err(OsoError::ImportCycle { chain });
+            }
+            if !self.inner.is_loaded(&import) {
+                self.load_file_with_imports(std::path::Path::new(&import), importers)?;
+            }
         }
-        let mut f = File::open(&file)?;
-        let mut policy = String::new();
-        f.read_to_string(&mut policy)?;
+        importers.pop();
+
         self.inner
-            .load(&policy, Some(file.to_string_lossy().into_owned()))?;
-        self.check_inline_queries()
+            .load(&policy, Some(filename.clone()))
+            .map_err(|e| imported(importers, e.into()))
     }
 
     /// Load a string of polar source directly.
@@ -169,6 +316,19 @@ impl Oso {
     }
 }
 
+/// Read a policy file, checking that it has the `.polar` extension.
+fn read_policy(file: &std::path::Path) -> crate::Result<String> {
+    if !file.extension().map(|ext| ext == "polar").unwrap_or(false) {
+        return Err(crate::OsoError::IncorrectFileType {
+            filename: file.to_string_lossy().into_owned(),
+        });
+    }
+    let mut f = File::open(&file)?;
+    let mut policy = String::new();
+    f.read_to_string(&mut policy)?;
+    Ok(policy)
+}
+
 // Make sure the `Oso` object is threadsafe
 #[cfg(test)]
 static_assertions::assert_impl_all!(Oso: Send, Sync);
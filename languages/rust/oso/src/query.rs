@@ -1,8 +1,9 @@
 use std::collections::BTreeMap;
 use std::collections::HashMap;
+use std::time::SystemTime;
 
 use crate::errors::OsoError;
-use crate::host::{Host, Instance, PolarIterator};
+use crate::host::{to_timestamp, Host, Instance, PolarIterator};
 use crate::{FromPolar, PolarValue};
 
 use polar_core::events::*;
@@ -31,6 +32,12 @@ impl Query {
         }
     }
 
+    /// Pin the time returned by `now()` in the policy, e.g., to test time-bound rules. By
+    /// default, it is the time the query starts running.
+    pub fn set_now(&mut self, now: SystemTime) {
+        self.inner.set_now(to_timestamp(now));
+    }
+
     pub fn next_result(&mut self) -> Option<crate::Result<ResultSet>> {
         loop {
             let event = self.inner.next()?;
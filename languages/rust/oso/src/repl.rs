@@ -6,10 +6,12 @@ use rustyline::Editor;
 use rustyline_derive::{Completer, Helper, Highlighter, Hinter};
 
 use oso::Oso;
+use polar_core::docs::PolicyDocs;
+use polar_core::formatter::format_source;
 use polar_core::formatting::to_polar::ToPolarString;
 
 use std::env;
-use std::fs::OpenOptions;
+use std::fs::{self, OpenOptions};
 
 pub fn load_files(oso: &mut Oso, files: &mut dyn Iterator<Item = String>) -> anyhow::Result<()> {
     for file in files {
@@ -18,6 +20,63 @@ pub fn load_files(oso: &mut Oso, files: &mut dyn Iterator<Item = String>) -> any
     Ok(())
 }
 
+/// Format policy files in place. With `--check`, report the files that are not formatted
+/// instead, and fail if there are any.
+pub fn format_files(args: &mut dyn Iterator<Item = String>) -> anyhow::Result<()> {
+    let (flags, files) = partition_flags(args, &["--check"])?;
+    let check = flags.iter().any(|flag| flag == "--check");
+    let mut unformatted = 0;
+    for arg in files {
+        let src = fs::read_to_string(&arg)?;
+        let formatted = format_source(&src).map_err(|e| anyhow::anyhow!("{}: {}", arg, e))?;
+        if formatted == src {
+            continue;
+        }
+        if check {
+            println!("{}", arg);
+            unformatted += 1;
+        } else {
+            fs::write(&arg, formatted)?;
+        }
+    }
+    if unformatted > 0 {
+        anyhow::bail!("{} file(s) are not formatted", unformatted);
+    }
+    Ok(())
+}
+
+/// Print the documentation of the rules in policy files, as Markdown or, with `--html`, as
+/// HTML.
+pub fn document_files(args: &mut dyn Iterator<Item = String>) -> anyhow::Result<()> {
+    let (flags, files) = partition_flags(args, &["--html"])?;
+    let html = flags.iter().any(|flag| flag == "--html");
+    let mut docs = PolicyDocs::default();
+    for arg in files {
+        let src = fs::read_to_string(&arg)?;
+        let file_docs =
+            PolicyDocs::from_source(&src).map_err(|e| anyhow::anyhow!("{}: {}", arg, e))?;
+        docs.extend(file_docs);
+    }
+    if html {
+        print!("{}", docs.to_html());
+    } else {
+        print!("{}", docs.to_markdown());
+    }
+    Ok(())
+}
+
+/// Split `args` into flags and files, failing on a flag that is not one of `known`.
+fn partition_flags(
+    args: &mut dyn Iterator<Item = String>,
+    known: &[&str],
+) -> anyhow::Result<(Vec<String>, Vec<String>)> {
+    let (flags, files): (Vec<_>, Vec<_>) = args.partition(|arg| arg.starts_with("--"));
+    if let Some(flag) = flags.iter().find(|flag| !known.contains(&flag.as_str())) {
+        anyhow::bail!("unknown flag {}", flag);
+    }
+    Ok((flags, files))
+}
+
 /// Attempt to create a new temporary directory to store
 /// and track the oso history
 pub fn try_create_history_file() -> Option<std::path::PathBuf> {
@@ -104,11 +163,22 @@ impl Drop for Repl {
 
 pub fn main() -> anyhow::Result<()> {
     tracing_subscriber::fmt::init();
+    let mut args = env::args().peekable();
+    let _ = args.next(); // skip the binary filename
+    match args.peek().map(String::as_str) {
+        Some("fmt") => {
+            let _ = args.next();
+            return format_files(&mut args);
+        }
+        Some("doc") => {
+            let _ = args.next();
+            return document_files(&mut args);
+        }
+        _ => {}
+    }
+
     let mut repl = Repl::new();
     let mut oso = Oso::new();
-
-    let mut args = env::args();
-    let _ = args.next(); // skip the binary filename
     load_files(&mut oso, &mut args)?;
     loop {
         // get input
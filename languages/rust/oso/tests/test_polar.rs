@@ -1,9 +1,13 @@
 /// Common tests for all integrations.
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::io::Write;
 use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-use oso::{Class, FromPolar, Oso, OsoError, PolarClass, PolarValue};
+use oso::{
+    Annotation, Class, ConstantDocs, Decimal, FromPolar, Oso, OsoError, PolarClass, PolarValue,
+    RuleDocs,
+};
 use polar_core::error as polar_error;
 
 use maplit::hashmap;
@@ -131,6 +135,102 @@ fn test_data_conversions_polar_values() -> oso::Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_data_conversions_sets() -> oso::Result<()> {
+    common::setup();
+
+    let mut test_oso = OsoTest::new();
+    test_oso.load_str("shared(a, b, s) if intersection(a, b, s);");
+
+    // Sets convert to and from Rust sets, and lists convert to sets.
+    let a: HashSet<i64> = [1, 2, 3].iter().cloned().collect();
+    let b: BTreeSet<String> = ["2", "3"].iter().map(|s| s.to_string()).collect();
+    let mut query = test_oso.oso.query_rule(
+        "shared",
+        (a, vec![2, 3, 4], PolarValue::Variable("s".to_owned())),
+    )?;
+    let s: BTreeSet<i64> = query.next().unwrap()?.get_typed("s")?;
+    assert_eq!(s, [2, 3].iter().cloned().collect());
+
+    let mut query = test_oso.oso.query_rule(
+        "shared",
+        (b, vec!["3"], PolarValue::Variable("s".to_owned())),
+    )?;
+    let s: HashSet<String> = query.next().unwrap()?.get_typed("s")?;
+    assert_eq!(s, ["3".to_owned()].iter().cloned().collect());
+
+    test_oso.qvar_one(
+        "x = {1, 2}",
+        "x",
+        PolarValue::Set(vec![PolarValue::Integer(1), PolarValue::Integer(2)]),
+    );
+    test_oso.qeval("{1, 2} matches Set");
+    Ok(())
+}
+
+#[test]
+fn test_data_conversions_decimals() -> oso::Result<()> {
+    common::setup();
+
+    let mut test_oso = OsoTest::new();
+    test_oso.load_str("add(a, b, a + b);");
+
+    // Decimals convert to and from `oso::Decimal`, keeping their scale.
+    let price: Decimal = "1.50".parse().unwrap();
+    let mut query = test_oso
+        .oso
+        .query_rule("add", (price, 1, PolarValue::Variable("x".to_owned())))?;
+    let x: Decimal = query.next().unwrap()?.get_typed("x")?;
+    assert_eq!(x.to_string(), "2.50");
+
+    test_oso.qvar_one("x = 1.5d", "x", PolarValue::Decimal(price));
+    test_oso.qeval("1.50d matches Decimal");
+    Ok(())
+}
+
+#[test]
+fn test_data_conversions_times() -> oso::Result<()> {
+    common::setup();
+
+    let mut test_oso = OsoTest::new();
+    test_oso.load_str("allow(expiry) if now() < expiry; later(t, d, t + d);");
+
+    // Timestamps and durations convert to and from `SystemTime` and `Duration`.
+    let expiry = UNIX_EPOCH + Duration::from_secs(1_622_538_000);
+    let mut query = test_oso.oso.query_rule(
+        "later",
+        (
+            expiry,
+            Duration::from_millis(1_500),
+            PolarValue::Variable("t".to_owned()),
+        ),
+    )?;
+    let t: SystemTime = query.next().unwrap()?.get_typed("t")?;
+    assert_eq!(t, expiry + Duration::from_millis(1_500));
+
+    test_oso.qvar_one(
+        r#"x = timestamp("2021-06-01T09:00:00Z")"#,
+        "x",
+        PolarValue::Timestamp(expiry),
+    );
+    test_oso.qvar_one(
+        r#"x = timestamp("1969-12-31T23:59:59.5Z")"#,
+        "x",
+        PolarValue::Timestamp(UNIX_EPOCH - Duration::from_millis(500)),
+    );
+    test_oso.qvar_one(r#"x = duration("1m30s")"#, "x", Duration::from_secs(90));
+    test_oso.qeval(r#"now() matches Timestamp and duration("1s") matches Duration"#);
+
+    // `now()` can be pinned per query.
+    let mut query = test_oso.oso.query_rule("allow", (expiry,))?;
+    query.set_now(expiry - Duration::from_secs(1));
+    assert!(query.next().is_some());
+    let mut query = test_oso.oso.query_rule("allow", (expiry,))?;
+    query.set_now(expiry);
+    assert!(query.next().is_none());
+    Ok(())
+}
+
 // TODO (dhatch): No predicate right now.
 #[ignore]
 #[test]
@@ -196,7 +296,7 @@ fn test_load_file_error_contains_filename() {
         assert_eq!(
             err.to_string(),
             format!(
-                "did not expect to find the token ';' at line 1, column 1 in file {}",
+                "did not expect to find the token ';', expected `?=`, `@` or a name at line 1, column 1 in file {}",
                 tempfile.path().to_string_lossy().into_owned()
             )
         );
@@ -269,6 +369,125 @@ fn test_load_multiple_files() -> oso::Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_annotations() -> oso::Result<()> {
+    common::setup();
+
+    let mut oso = test_oso();
+    oso.load_str("@owner(\"billing-team\") @tag(\"pii\", 1) f(1);\nf(2);");
+    let owner = Annotation {
+        name: "owner".to_string(),
+        args: vec![PolarValue::String("billing-team".to_string())],
+    };
+    let tag = Annotation {
+        name: "tag".to_string(),
+        args: vec![
+            PolarValue::String("pii".to_string()),
+            PolarValue::Integer(1),
+        ],
+    };
+    assert_eq!(oso.oso.annotations("f")?, vec![vec![owner, tag], vec![]]);
+    assert!(oso.oso.annotations("g")?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_docs() -> oso::Result<()> {
+    common::setup();
+
+    let mut oso = test_oso();
+    oso.load_str(
+        r#"## Who owns a resource.
+           @tag("pii") owner(user, _resource) if user = "alice";
+           ## The admin roles.
+           const ADMIN_ROLES = ["owner"];"#,
+    );
+    let docs = oso.oso.docs()?;
+    let owner = RuleDocs {
+        signature: "owner(user, _resource)".to_string(),
+        doc: Some("Who owns a resource.".to_string()),
+        annotations: vec![Annotation {
+            name: "tag".to_string(),
+            args: vec![PolarValue::String("pii".to_string())],
+        }],
+    };
+    assert_eq!(docs.rules["owner"], vec![owner]);
+    assert!(docs.types.is_empty());
+    let admin_roles = ConstantDocs {
+        definition: r#"const ADMIN_ROLES = ["owner"]"#.to_string(),
+        doc: Some("The admin roles.".to_string()),
+    };
+    assert_eq!(docs.constants["ADMIN_ROLES"], admin_roles);
+    Ok(())
+}
+
+#[test]
+fn test_load_file_imports() -> oso::Result<()> {
+    common::setup();
+
+    let dir = tempfile::tempdir().unwrap();
+    let write = |name: &str, src: &str| {
+        let path = dir.path().join(name);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, src).unwrap();
+        path
+    };
+    write("shared/roles.polar", "role(\"admin\");");
+    write(
+        "shared/users.polar",
+        "import \"roles.polar\";\nuser(\"alice\", r) if role(r);",
+    );
+    let policy = write(
+        "policy.polar",
+        "import \"shared/roles.polar\";\nimport \"./shared/users.polar\";\nallow(u) if user(u, _);",
+    );
+
+    // Both files import shared/roles.polar, which is only loaded once.
+    let mut oso = test_oso();
+    oso.oso.load_file(&policy)?;
+    assert_eq!(oso.qvar::<String>("allow(u)", "u"), vec!["alice"]);
+    assert_eq!(oso.qvar::<String>("role(r)", "r"), vec!["admin"]);
+
+    let name = |path: &str| dir.path().join(path).to_string_lossy().into_owned();
+    write("a.polar", "import \"b.polar\";");
+    write("b.polar", "import \"a.polar\";");
+    let err = test_oso()
+        .oso
+        .load_file(dir.path().join("a.polar"))
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        format!(
+            "Import cycle: {} -> {} -> {}",
+            name("a.polar"),
+            name("b.polar"),
+            name("a.polar")
+        )
+    );
+
+    write("c.polar", "import \"shared/missing.polar\";");
+    let err = test_oso()
+        .oso
+        .load_file(dir.path().join("c.polar"))
+        .unwrap_err();
+    assert!(
+        matches!(&err, OsoError::ImportError { source, chain }
+            if matches!(**source, OsoError::Io(_))
+                && *chain == vec![name("c.polar"), name("shared/missing.polar")]),
+        "Error was {:?}",
+        &err
+    );
+
+    // Imports are resolved by `load_file`, so a string cannot import files.
+    let err = test_oso().oso.load_str("import \"a.polar\";").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Only a policy file can import other files at line 1, column 8"
+    );
+
+    Ok(())
+}
+
 #[test]
 fn test_clear_rules() -> oso::Result<()> {
     common::setup();
@@ -923,3 +1142,15 @@ fn test_expression_error() {
     let err = oso.query_err("f(x)");
     assert!(err.contains("unbound"));
 }
+
+#[test]
+fn test_ignore_warning() {
+    common::setup();
+
+    let oso = Oso::new();
+    oso.ignore_warning("undefined-rule").unwrap();
+    assert!(matches!(
+        oso.ignore_warning("undefined-rules"),
+        Err(OsoError::Custom { .. })
+    ));
+}
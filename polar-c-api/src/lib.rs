@@ -1,5 +1,5 @@
 pub use polar_core::polar::{Polar, Query};
-use polar_core::{error, terms};
+use polar_core::{error, terms, terms::ToPolarString, traces};
 
 use std::cell::RefCell;
 use std::ffi::{CStr, CString};
@@ -102,6 +102,37 @@ pub extern "C" fn polar_load(
     })
 }
 
+/// The files that `src`, the contents of the policy file `filename`, imports and that have not
+/// been loaded yet, as a JSON list of paths. The host loads them, after their own imports,
+/// before it loads `src`.
+#[no_mangle]
+pub extern "C" fn polar_imports(
+    polar_ptr: *mut Polar,
+    src: *const c_char,
+    filename: *const c_char,
+) -> *const c_char {
+    ffi_try!({
+        let polar = unsafe { ffi_ref!(polar_ptr) };
+        let src = unsafe { ffi_string!(src) };
+        let filename = unsafe { ffi_string!(filename) };
+        match polar.imports(&src, &filename) {
+            Ok(imports) => {
+                let imports = imports
+                    .into_iter()
+                    .filter(|import| !polar.is_loaded(import))
+                    .collect::<Vec<_>>();
+                CString::new(serde_json::to_string(&imports).unwrap())
+                    .expect("JSON should not contain any 0 bytes")
+                    .into_raw()
+            }
+            Err(err) => {
+                set_error(err);
+                null()
+            }
+        }
+    })
+}
+
 #[no_mangle]
 pub extern "C" fn polar_clear_rules(polar_ptr: *mut Polar) -> i32 {
     ffi_try!({
@@ -135,6 +166,51 @@ pub extern "C" fn polar_register_constant(
     })
 }
 
+/// Stop reporting warnings with the code `code`, e.g., `"undefined-rule"`.
+#[no_mangle]
+pub extern "C" fn polar_ignore_warning(polar_ptr: *mut Polar, code: *const c_char) -> i32 {
+    ffi_try!({
+        let polar = unsafe { ffi_ref!(polar_ptr) };
+        let code = unsafe { ffi_string!(code) };
+        match code.parse() {
+            Ok(code) => {
+                polar.ignore_warning(code);
+                POLAR_SUCCESS
+            }
+            Err(msg) => {
+                set_error(error::RuntimeError::Unsupported { msg }.into());
+                POLAR_FAILURE
+            }
+        }
+    })
+}
+
+/// The annotations on each definition of the rule `name`, in the order the definitions were
+/// loaded, as a JSON list of lists of `{"name": ..., "args": [...]}` objects.
+#[no_mangle]
+pub extern "C" fn polar_annotations(polar_ptr: *mut Polar, name: *const c_char) -> *const c_char {
+    ffi_try!({
+        let polar = unsafe { ffi_ref!(polar_ptr) };
+        let name = unsafe { ffi_string!(name) };
+        let annotations = polar.annotations(&terms::Symbol::new(&name));
+        CString::new(serde_json::to_string(&annotations).unwrap())
+            .expect("JSON should not contain any 0 bytes")
+            .into_raw()
+    })
+}
+
+/// The documentation of the loaded rules, rule types and constants, as a JSON object with
+/// `rules`, `types` and `constants` fields.
+#[no_mangle]
+pub extern "C" fn polar_docs(polar_ptr: *mut Polar) -> *const c_char {
+    ffi_try!({
+        let polar = unsafe { ffi_ref!(polar_ptr) };
+        CString::new(serde_json::to_string(&polar.docs()).unwrap())
+            .expect("JSON should not contain any 0 bytes")
+            .into_raw()
+    })
+}
+
 // @Note(steve): trace is treated as a bool. 0 for false, anything else for true.
 // If we get more than one flag on these ffi methods, consider renaming it flags and making it a bitflags field.
 // Then we wont have to update the ffi to add new optional things like logging or tracing or whatever.
@@ -391,6 +467,60 @@ pub extern "C" fn polar_bind(
     })
 }
 
+/// Pin the time returned by `now()` in the query to `now`, a serialized timestamp term.
+#[no_mangle]
+pub extern "C" fn polar_query_set_now(query_ptr: *mut Query, now: *const c_char) -> i32 {
+    ffi_try!({
+        let query = unsafe { ffi_ref!(query_ptr) };
+        let now = unsafe { ffi_string!(now) };
+        match serde_json::from_str::<terms::Term>(&now) {
+            Ok(term) => match term.value() {
+                terms::Value::Timestamp(now) => {
+                    query.set_now(*now);
+                    POLAR_SUCCESS
+                }
+                _ => {
+                    set_error(
+                        error::RuntimeError::TypeError {
+                            msg: format!("Expected a timestamp, got: {}", term.to_polar()),
+                            stack_trace: None,
+                        }
+                        .into(),
+                    );
+                    POLAR_FAILURE
+                }
+            },
+            Err(e) => {
+                set_error(error::RuntimeError::Serialization { msg: e.to_string() }.into());
+                POLAR_FAILURE
+            }
+        }
+    })
+}
+
+/// Leave the nodes and bindings excluded by `filter`, a serialized trace filter such as
+/// `{"builtins": true, "temporaries": true, "rules": "^_"}`, out of the traces of results.
+#[no_mangle]
+pub extern "C" fn polar_query_set_trace_filter(
+    query_ptr: *mut Query,
+    filter: *const c_char,
+) -> i32 {
+    ffi_try!({
+        let query = unsafe { ffi_ref!(query_ptr) };
+        let filter = unsafe { ffi_string!(filter) };
+        match serde_json::from_str::<traces::TraceFilter>(&filter) {
+            Ok(filter) => {
+                query.set_trace_filter(filter);
+                POLAR_SUCCESS
+            }
+            Err(e) => {
+                set_error(error::RuntimeError::Serialization { msg: e.to_string() }.into());
+                POLAR_FAILURE
+            }
+        }
+    })
+}
+
 #[no_mangle]
 pub extern "C" fn polar_get_external_id(polar_ptr: *mut Polar) -> u64 {
     ffi_try!({
@@ -0,0 +1,88 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::counter::Counter;
+use crate::error::PolarResult;
+use crate::events::QueryEvent;
+use crate::runnable::Runnable;
+use crate::terms::Term;
+use crate::vm::{Goals, PolarVirtualMachine};
+
+/// The collector implements the aggregates in Polar, e.g., `findall(x, f(x), xs)`.
+///
+/// It is a `Runnable` that runs `goals` using `vm`, and collects the value of
+/// `template` for every result into the shared `values`. External calls made by
+/// the goals are passed through to the host as usual. It also collects the
+/// answers to calls of tabled rules.
+///
+/// The collector always succeeds; combining the collected values is left to the
+/// parent VM.
+#[derive(Clone)]
+pub struct Collector {
+    vm: PolarVirtualMachine,
+
+    /// The term whose value is collected for each result.
+    template: Term,
+
+    /// Values collected so far, shared with the parent VM.
+    values: Rc<RefCell<Vec<Term>>>,
+
+    /// Whether `run` has been called yet.
+    started: bool,
+}
+
+impl Collector {
+    pub fn new(
+        vm: &PolarVirtualMachine,
+        template: Term,
+        goals: Goals,
+        values: Rc<RefCell<Vec<Term>>>,
+    ) -> Self {
+        let vm = vm.clone_with_goals(goals);
+        Self {
+            vm,
+            template,
+            values,
+            started: false,
+        }
+    }
+}
+
+impl Runnable for Collector {
+    fn run(&mut self, _: Option<&mut Counter>) -> PolarResult<QueryEvent> {
+        if !self.started {
+            // The collector may be re-run when the parent VM backtracks,
+            // so start from a clean slate.
+            self.values.borrow_mut().clear();
+            self.started = true;
+        }
+
+        loop {
+            // Pass most events through, but collect results.
+            match self.vm.run(None)? {
+                QueryEvent::Done { .. } => return Ok(QueryEvent::Done { result: true }),
+                QueryEvent::Result { .. } => {
+                    let value = self.vm.deep_deref(&self.template);
+                    self.values.borrow_mut().push(value);
+                }
+                event => return Ok(event),
+            }
+        }
+    }
+
+    fn external_question_result(&mut self, call_id: u64, answer: bool) -> PolarResult<()> {
+        self.vm.external_question_result(call_id, answer)
+    }
+
+    fn external_call_result(&mut self, call_id: u64, term: Option<Term>) -> PolarResult<()> {
+        self.vm.external_call_result(call_id, term)
+    }
+
+    fn debug_command(&mut self, command: &str) -> PolarResult<()> {
+        self.vm.debug_command(command)
+    }
+
+    fn clone_runnable(&self) -> Box<dyn Runnable> {
+        Box::new(self.clone())
+    }
+}
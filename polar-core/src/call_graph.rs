@@ -0,0 +1,313 @@
+//! Static analysis of the calls between rules.
+//!
+//! The call graph has an edge from each generic rule to every rule called in the body of one of
+//! its definitions. Only calls in goal position count: calls made on application instances
+//! (`x.foo()`) and constructors (`new Foo()`) are not rule calls.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt::Write;
+
+use super::kb::KnowledgeBase;
+use super::terms::*;
+use super::visitor::{walk_operation, Visitor};
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CallGraph {
+    /// Map from each rule to the rules it calls.
+    pub calls: BTreeMap<Symbol, BTreeSet<Symbol>>,
+    /// Map from each rule to the rules it calls before any other goal. A cycle in these edges
+    /// is left recursion: the rule calls itself again before it can fail or bind anything.
+    /// Calls to tabled rules are left out, since they cannot recurse forever.
+    pub left_calls: BTreeMap<Symbol, BTreeSet<Symbol>>,
+}
+
+/// Record the rules called from a rule body.
+struct CallVisitor {
+    calls: BTreeSet<Symbol>,
+    left_calls: BTreeSet<Symbol>,
+    /// True while visiting a goal that would be the first one evaluated.
+    leftmost: bool,
+}
+
+impl CallVisitor {
+    fn new() -> Self {
+        Self {
+            calls: BTreeSet::new(),
+            left_calls: BTreeSet::new(),
+            leftmost: true,
+        }
+    }
+}
+
+impl Visitor for CallVisitor {
+    fn visit_call(&mut self, c: &Call) {
+        self.calls.insert(c.name.clone());
+        if self.leftmost {
+            self.left_calls.insert(c.name.clone());
+        }
+    }
+
+    fn visit_operation(&mut self, o: &Operation) {
+        match o.operator {
+            Operator::And | Operator::ForAll => {
+                let leftmost = self.leftmost;
+                for arg in &o.args {
+                    self.visit_term(arg);
+                    // Lookups and arithmetic hoisted out of a call by the rewriter come before
+                    // it, but cannot stop the recursion, so they don't count as goals here.
+                    self.leftmost = self.leftmost && is_hoisted(arg);
+                }
+                self.leftmost = leftmost;
+            }
+            Operator::Or | Operator::Not | Operator::If => walk_operation(self, o),
+            // The second argument of an aggregate is a goal.
+            operator if operator.is_aggregate() => self.visit_term(&o.args[1]),
+            // Other operators do not take goals as arguments.
+            _ => {}
+        }
+    }
+}
+
+/// Return true if `term` is an expression that the rewriter hoists into a temporary variable.
+fn is_hoisted(term: &Term) -> bool {
+    matches!(
+        term.value(),
+        Value::Expression(Operation {
+            operator: Operator::Add
+                | Operator::Sub
+                | Operator::Mul
+                | Operator::Div
+                | Operator::Mod
+                | Operator::Rem
+                | Operator::Dot
+                | Operator::New,
+            ..
+        })
+    )
+}
+
+impl CallGraph {
+    pub fn new(kb: &KnowledgeBase) -> Self {
+        let mut graph = Self::default();
+        for (name, generic_rule) in &kb.rules {
+            let mut visitor = CallVisitor::new();
+            for rule in generic_rule.rules() {
+                visitor.leftmost = true;
+                visitor.visit_term(&rule.body);
+            }
+            visitor
+                .left_calls
+                .retain(|callee| !matches!(kb.rules.get(callee), Some(rule) if rule.tabled));
+            graph.calls.insert(name.clone(), visitor.calls);
+            graph.left_calls.insert(name.clone(), visitor.left_calls);
+        }
+        graph
+    }
+
+    /// Return the groups of mutually recursive rules.
+    pub fn cycles(&self) -> Vec<Vec<Symbol>> {
+        strongly_connected_components(&self.calls)
+    }
+
+    /// Return the groups of mutually left-recursive rules. Querying any of these can loop until
+    /// the VM hits its stack limit.
+    pub fn left_recursive(&self) -> Vec<Vec<Symbol>> {
+        strongly_connected_components(&self.left_calls)
+    }
+
+    /// Return the rules that cannot be reached by a query for any of the `entry_points`.
+    pub fn unreachable(&self, entry_points: &[Symbol]) -> Vec<Symbol> {
+        let mut reached = BTreeSet::new();
+        let mut stack = entry_points.to_vec();
+        while let Some(name) = stack.pop() {
+            if reached.insert(name.clone()) {
+                if let Some(callees) = self.calls.get(&name) {
+                    stack.extend(callees.iter().cloned());
+                }
+            }
+        }
+        self.calls
+            .keys()
+            .filter(|name| !reached.contains(name))
+            .cloned()
+            .collect()
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("call graph serialization failed")
+    }
+
+    /// Render the graph as a Graphviz DOT digraph. Rules that are not defined are drawn dashed;
+    /// edges that take part in left recursion are drawn in red.
+    pub fn to_dot(&self) -> String {
+        let left_recursive = self
+            .left_recursive()
+            .into_iter()
+            .flatten()
+            .collect::<BTreeSet<_>>();
+        let mut dot = String::from("digraph rules {\n");
+        let callees = self.calls.values().flatten().collect::<BTreeSet<_>>();
+        for name in callees {
+            if !self.calls.contains_key(name) {
+                let _ = writeln!(dot, "  \"{}\" [style=dashed];", name);
+            }
+        }
+        for (caller, callees) in &self.calls {
+            let _ = writeln!(dot, "  \"{}\";", caller);
+            for callee in callees {
+                let left = self.left_calls[caller].contains(callee)
+                    && left_recursive.contains(caller)
+                    && left_recursive.contains(callee);
+                if left {
+                    let _ = writeln!(dot, "  \"{}\" -> \"{}\" [color=red];", caller, callee);
+                } else {
+                    let _ = writeln!(dot, "  \"{}\" -> \"{}\";", caller, callee);
+                }
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+/// Tarjan's algorithm, keeping only the components that contain a cycle: those with more than
+/// one member, or a single member that calls itself.
+fn strongly_connected_components(edges: &BTreeMap<Symbol, BTreeSet<Symbol>>) -> Vec<Vec<Symbol>> {
+    struct Tarjan<'a> {
+        edges: &'a BTreeMap<Symbol, BTreeSet<Symbol>>,
+        index: HashMap<&'a Symbol, usize>,
+        lowlink: HashMap<&'a Symbol, usize>,
+        stack: Vec<&'a Symbol>,
+        on_stack: BTreeSet<&'a Symbol>,
+        components: Vec<Vec<Symbol>>,
+    }
+
+    impl<'a> Tarjan<'a> {
+        fn visit(&mut self, v: &'a Symbol) {
+            let i = self.index.len();
+            self.index.insert(v, i);
+            self.lowlink.insert(v, i);
+            self.stack.push(v);
+            self.on_stack.insert(v);
+
+            for w in self.edges.get(v).into_iter().flatten() {
+                if !self.index.contains_key(w) {
+                    self.visit(w);
+                    let low = self.lowlink[v].min(self.lowlink[w]);
+                    self.lowlink.insert(v, low);
+                } else if self.on_stack.contains(w) {
+                    let low = self.lowlink[v].min(self.index[w]);
+                    self.lowlink.insert(v, low);
+                }
+            }
+
+            if self.lowlink[v] == self.index[v] {
+                let mut component = vec![];
+                while let Some(w) = self.stack.pop() {
+                    self.on_stack.remove(w);
+                    component.push(w.clone());
+                    if w == v {
+                        break;
+                    }
+                }
+                let calls_self = matches!(self.edges.get(v), Some(e) if e.contains(v));
+                if component.len() > 1 || calls_self {
+                    component.sort();
+                    self.components.push(component);
+                }
+            }
+        }
+    }
+
+    let mut tarjan = Tarjan {
+        edges,
+        index: HashMap::new(),
+        lowlink: HashMap::new(),
+        stack: vec![],
+        on_stack: BTreeSet::new(),
+        components: vec![],
+    };
+    for v in edges.keys() {
+        if !tarjan.index.contains_key(v) {
+            tarjan.visit(v);
+        }
+    }
+    tarjan.components.sort();
+    tarjan.components
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::polar::Polar;
+
+    fn call_graph(src: &str) -> CallGraph {
+        let polar = Polar::new();
+        polar.load_str(src).unwrap();
+        let kb = polar.kb.read().unwrap();
+        CallGraph::new(&kb)
+    }
+
+    #[test]
+    fn test_call_graph() {
+        let graph = call_graph(
+            r#"allow(actor, "read", resource) if owns(actor, resource) or admin(actor);
+               owns(actor, resource) if resource.owner = actor and not banned(actor);
+               admin(actor) if actor.role = "admin" and new Audit(actor).log();
+               banned(_actor) if false;
+               unused(x) if allow(x, "read", x);"#,
+        );
+        assert_eq!(
+            graph.calls[&sym!("allow")],
+            btreeset! {sym!("admin"), sym!("owns")}
+        );
+        assert_eq!(graph.calls[&sym!("owns")], btreeset! {sym!("banned")});
+        assert!(graph.calls[&sym!("admin")].is_empty());
+        assert_eq!(graph.left_calls[&sym!("owns")], BTreeSet::new());
+        assert!(graph.cycles().is_empty());
+        assert_eq!(graph.unreachable(&[sym!("allow")]), vec![sym!("unused")]);
+
+        let dot = graph.to_dot();
+        assert!(dot.contains("  \"allow\" -> \"owns\";\n"));
+    }
+
+    #[test]
+    fn test_recursion() {
+        let graph = call_graph(
+            r#"ancestor(x, y) if parent(x, y);
+               ancestor(x, y) if parent(x, z) and ancestor(z, y);
+               path(x, y) if edge(x, y);
+               path(x, y) if path(x, z) and edge(z, y);
+               even(x) if x = 0 or odd(x - 1);
+               odd(x) if even(x - 1);
+               parent("a", "b");
+               edge(1, 2);"#,
+        );
+        assert_eq!(
+            graph.cycles(),
+            vec![
+                vec![sym!("ancestor")],
+                vec![sym!("even"), sym!("odd")],
+                vec![sym!("path")]
+            ]
+        );
+        assert_eq!(
+            graph.left_recursive(),
+            vec![vec![sym!("even"), sym!("odd")], vec![sym!("path")]]
+        );
+        assert!(graph
+            .to_dot()
+            .contains("  \"path\" -> \"path\" [color=red];\n"));
+
+        // Tabled rules are not left-recursive.
+        let graph = call_graph(
+            r#"@table
+               path(x, y) if path(x, z) and edge(z, y);
+               path(x, y) if edge(x, y);
+               edge(1, 2);"#,
+        );
+        assert_eq!(graph.cycles(), vec![vec![sym!("path")]]);
+        assert!(graph.left_recursive().is_empty());
+    }
+}
@@ -0,0 +1,457 @@
+//! Timestamps and durations, e.g., `timestamp("2021-06-01T09:00:00Z")` and `duration("8h")`.
+//!
+//! Both are counted in seconds and nanoseconds, so they can be compared and added without loss
+//! of precision, every year from 0000 to 9999 can be represented, and the seconds of any
+//! practical value fit in a JavaScript number. Timestamps outside those years are out of range. Timestamps are counted from the Unix epoch, and
+//! can be parsed from and are displayed as RFC 3339 strings. Durations are displayed like
+//! `1h30m`.
+
+use serde::{Deserialize, Serialize};
+
+use std::convert::TryFrom;
+use std::fmt;
+
+const NANOS_PER_SECOND: i64 = 1_000_000_000;
+const SECONDS_PER_DAY: i64 = 86_400;
+/// The seconds of the first and last timestamps, 0000-01-01T00:00:00Z and 9999-12-31T23:59:59Z.
+const MIN_TIMESTAMP_SECONDS: i64 = -62_167_219_200;
+const MAX_TIMESTAMP_SECONDS: i64 = 253_402_300_799;
+
+/// The units of a duration string, largest first, with their lengths in nanoseconds.
+const DURATION_UNITS: [(&str, i128); 7] = [
+    ("d", 86_400_000_000_000),
+    ("h", 3_600_000_000_000),
+    ("m", 60_000_000_000),
+    ("s", 1_000_000_000),
+    ("ms", 1_000_000),
+    ("us", 1_000),
+    ("ns", 1),
+];
+
+/// A point in time since the Unix epoch, 1970-01-01T00:00:00Z. `nanos` is less than a second,
+/// so a timestamp before the epoch has negative `seconds` and counts `nanos` forward from them.
+#[derive(
+    Copy, Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+pub struct Timestamp {
+    pub seconds: i64,
+    pub nanos: u32,
+}
+
+/// A length of time, which may be negative. Like a timestamp, `nanos` is less than a second and
+/// counts forward from `seconds`, so `-1ns` is `-1` seconds and `999_999_999` nanoseconds.
+#[derive(
+    Copy, Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+pub struct Duration {
+    pub seconds: i64,
+    pub nanos: u32,
+}
+
+/// Split a number of nanoseconds into seconds and nanoseconds, if the seconds fit in an `i64`.
+fn split_nanos(nanos: i128) -> Option<(i64, u32)> {
+    let per_second = i128::from(NANOS_PER_SECOND);
+    let seconds = i64::try_from(nanos.div_euclid(per_second)).ok()?;
+    Some((seconds, nanos.rem_euclid(per_second) as u32))
+}
+
+fn join_nanos(seconds: i64, nanos: u32) -> i128 {
+    i128::from(seconds) * i128::from(NANOS_PER_SECOND) + i128::from(nanos)
+}
+
+impl Timestamp {
+    /// The current time, according to the system clock.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn now() -> Self {
+        let since_epoch = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .expect("system clock is set before the Unix epoch");
+        Self {
+            seconds: since_epoch.as_secs() as i64,
+            nanos: since_epoch.subsec_nanos(),
+        }
+    }
+
+    /// The current time, according to the system clock.
+    #[cfg(target_arch = "wasm32")]
+    pub fn now() -> Self {
+        let millis = js_sys::Date::now() as i64;
+        Self {
+            seconds: millis.div_euclid(1_000),
+            nanos: (millis.rem_euclid(1_000) * 1_000_000) as u32,
+        }
+    }
+
+    /// The timestamp `nanos` nanoseconds after the epoch, if it is in the years 0000 to 9999.
+    pub fn from_nanos(nanos: i128) -> Option<Self> {
+        split_nanos(nanos)
+            .filter(|(seconds, _)| {
+                (MIN_TIMESTAMP_SECONDS..=MAX_TIMESTAMP_SECONDS).contains(seconds)
+            })
+            .map(|(seconds, nanos)| Self { seconds, nanos })
+    }
+
+    /// The number of nanoseconds since the epoch.
+    pub fn as_nanos(self) -> i128 {
+        join_nanos(self.seconds, self.nanos)
+    }
+
+    /// Parse an RFC 3339 timestamp, e.g., `2021-06-01T09:00:00Z` or
+    /// `2021-06-01T11:00:00.5+02:00`.
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let invalid = || format!("invalid RFC 3339 timestamp {:?}", s);
+        let bytes = s.as_bytes();
+        let separators = [(4, b'-'), (7, b'-'), (13, b':'), (16, b':')];
+        if bytes.len() < 20
+            || separators.iter().any(|&(i, c)| bytes[i] != c)
+            || !matches!(bytes[10], b'T' | b't' | b' ')
+        {
+            return Err(invalid());
+        }
+        // The number in `len` digits at `start`, which must be at most `max`.
+        let field = |start: usize, len: usize, max: i64| {
+            bytes
+                .get(start..start + len)
+                .filter(|digits| digits.iter().all(u8::is_ascii_digit))
+                .map(|digits| {
+                    digits
+                        .iter()
+                        .fold(0, |n, digit| n * 10 + i64::from(digit - b'0'))
+                })
+                .filter(|n| *n <= max)
+                .ok_or_else(invalid)
+        };
+
+        let year = field(0, 4, 9999)?;
+        let month = field(5, 2, 12)?;
+        let day = field(8, 2, 31)?;
+        if month == 0 || day == 0 || day > days_in_month(year, month) {
+            return Err(invalid());
+        }
+        let seconds = field(11, 2, 23)? * 3_600 + field(14, 2, 59)? * 60 + field(17, 2, 59)?;
+
+        let mut i = 19;
+        let mut nanos = 0;
+        if bytes[i] == b'.' {
+            let start = i + 1;
+            i = start;
+            while i < bytes.len() && bytes[i].is_ascii_digit() {
+                i += 1;
+            }
+            let fraction = &s[start..i];
+            if fraction.is_empty() || fraction.len() > 9 {
+                return Err(invalid());
+            }
+            nanos = format!("{:0<9}", fraction).parse::<u32>().unwrap();
+        }
+
+        let offset = match &bytes[i..] {
+            b"Z" | b"z" => 0,
+            [sign, _, _, b':', _, _] if *sign == b'+' || *sign == b'-' => {
+                let offset = field(i + 1, 2, 23)? * 3_600 + field(i + 4, 2, 59)? * 60;
+                if *sign == b'-' {
+                    -offset
+                } else {
+                    offset
+                }
+            }
+            _ => return Err(invalid()),
+        };
+
+        // An offset may move the first or last day out of range.
+        let seconds = days_from_civil(year, month, day) * SECONDS_PER_DAY + seconds - offset;
+        Self::from_nanos(join_nanos(seconds, nanos)).ok_or_else(invalid)
+    }
+
+    pub fn checked_add(self, duration: Duration) -> Option<Self> {
+        Self::from_nanos(self.as_nanos() + duration.as_nanos())
+    }
+
+    pub fn checked_sub(self, duration: Duration) -> Option<Self> {
+        Self::from_nanos(self.as_nanos() - duration.as_nanos())
+    }
+
+    /// The duration from `earlier` to this timestamp.
+    pub fn checked_since(self, earlier: Self) -> Option<Duration> {
+        Duration::from_nanos(self.as_nanos() - earlier.as_nanos())
+    }
+}
+
+/// Display as an RFC 3339 timestamp in UTC, e.g., `2021-06-01T09:00:00.5Z`.
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (year, month, day) = civil_from_days(self.seconds.div_euclid(SECONDS_PER_DAY));
+        let time = self.seconds.rem_euclid(SECONDS_PER_DAY);
+        write!(
+            f,
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+            year,
+            month,
+            day,
+            time / 3_600,
+            time / 60 % 60,
+            time % 60
+        )?;
+        if self.nanos > 0 {
+            let fraction = format!("{:09}", self.nanos);
+            write!(f, ".{}", fraction.trim_end_matches('0'))?;
+        }
+        write!(f, "Z")
+    }
+}
+
+impl Duration {
+    /// Parse a duration made of whole numbers of units, e.g., `90s` or `-1h30m`. The units are
+    /// `d`, `h`, `m`, `s`, `ms`, `us` and `ns`.
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let invalid = || format!("invalid duration {:?}", s);
+        let (negative, mut rest) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s),
+        };
+        if rest.is_empty() {
+            return Err(invalid());
+        }
+        let mut nanos: i128 = 0;
+        while !rest.is_empty() {
+            let digits = rest
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(rest.len());
+            let units = rest[digits..]
+                .find(|c: char| c.is_ascii_digit())
+                .unwrap_or(rest.len() - digits);
+            let count = rest[..digits].parse::<i64>().map_err(|_| invalid())?;
+            let count = i128::from(count);
+            let unit = &rest[digits..digits + units];
+            let (_, length) = DURATION_UNITS
+                .iter()
+                .find(|(name, _)| *name == unit)
+                .ok_or_else(invalid)?;
+            nanos = nanos
+                .checked_add(count * length)
+                .ok_or_else(|| format!("duration {:?} is out of range", s))?;
+            rest = &rest[digits + units..];
+        }
+        Self::from_nanos(if negative { -nanos } else { nanos })
+            .ok_or_else(|| format!("duration {:?} is out of range", s))
+    }
+
+    /// The duration of `nanos` nanoseconds, if its seconds fit in an `i64`.
+    pub fn from_nanos(nanos: i128) -> Option<Self> {
+        split_nanos(nanos).map(|(seconds, nanos)| Self { seconds, nanos })
+    }
+
+    /// The number of nanoseconds in the duration.
+    pub fn as_nanos(self) -> i128 {
+        join_nanos(self.seconds, self.nanos)
+    }
+
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        Self::from_nanos(self.as_nanos() + other.as_nanos())
+    }
+
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        Self::from_nanos(self.as_nanos() - other.as_nanos())
+    }
+}
+
+/// Display in the largest units first, e.g., `1h30m`, or `0s` if the duration is zero.
+impl fmt::Display for Duration {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let nanos = self.as_nanos();
+        if nanos == 0 {
+            return write!(f, "0s");
+        }
+        if nanos < 0 {
+            write!(f, "-")?;
+        }
+        let mut rest = nanos.abs();
+        for (unit, length) in DURATION_UNITS.iter() {
+            let length = *length;
+            if rest >= length {
+                write!(f, "{}{}", rest / length, unit)?;
+                rest %= length;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn is_leap_year(year: i64) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// The number of days from 1970-01-01 to a date in the proleptic Gregorian calendar.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    // Count years from March, so that leap days fall at the end of the year.
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// The date `days` days after 1970-01-01, as a year, month and day. The inverse of
+/// `days_from_civil`.
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_from_march = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_from_march + 2) / 5 + 1;
+    let month = if month_from_march < 10 {
+        month_from_march + 3
+    } else {
+        month_from_march - 9
+    };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_timestamps() {
+        let parse = |s| Timestamp::parse(s).map(Timestamp::as_nanos);
+        assert_eq!(parse("1970-01-01T00:00:00Z"), Ok(0));
+        assert_eq!(parse("1970-01-01T00:00:01.5Z"), Ok(1_500_000_000));
+        assert_eq!(
+            Timestamp::parse("2021-06-01T09:00:00Z"),
+            Ok(Timestamp {
+                seconds: 1_622_538_000,
+                nanos: 0
+            })
+        );
+        assert_eq!(
+            parse("2021-06-01T11:00:00+02:00"),
+            parse("2021-06-01t09:00:00z")
+        );
+        assert_eq!(
+            Timestamp::parse("1969-12-31T23:59:59.25Z"),
+            Ok(Timestamp {
+                seconds: -1,
+                nanos: 250_000_000
+            })
+        );
+        assert_eq!(
+            parse("2020-02-29 00:00:00.000000001Z"),
+            Ok(1_582_934_400 * 1_000_000_000 + 1)
+        );
+        assert_eq!(
+            Timestamp::parse("9999-12-31T23:59:59Z").map(|t| t.seconds),
+            Ok(253_402_300_799)
+        );
+        assert_eq!(
+            Timestamp::parse("0000-01-01T00:00:00Z").map(|t| t.seconds),
+            Ok(-62_167_219_200)
+        );
+
+        for invalid in &[
+            "2021-06-01",
+            "2021-06-01T09:00:00",
+            "2021-06-01T24:00:00Z",
+            "2021-02-29T00:00:00Z",
+            "2021-06-01T09:00:00.Z",
+            "2021-06-01T09:00:00+0200",
+            "2021-06-01T09:00:00Z ",
+            "10000-01-01T00:00:00Z",
+            "0000-01-01T00:00:00+00:01",
+            "9999-12-31T23:59:59-00:01",
+        ] {
+            assert!(parse(invalid).is_err(), "{} should be invalid", invalid);
+        }
+    }
+
+    #[test]
+    fn test_timestamp_arithmetic_range() {
+        let last = Timestamp::parse("9999-12-31T23:59:59.999999999Z").unwrap();
+        let first = Timestamp::parse("0000-01-01T00:00:00Z").unwrap();
+        let ns = Duration::parse("1ns").unwrap();
+        assert_eq!(last.checked_add(ns), None);
+        assert_eq!(first.checked_sub(ns), None);
+        assert_eq!(
+            last.checked_sub(ns).map(|t| t.to_string()),
+            Some("9999-12-31T23:59:59.999999998Z".to_owned())
+        );
+        assert_eq!(
+            first.checked_add(Duration::parse("1000000h").unwrap()),
+            Some(Timestamp::parse("0114-01-29T16:00:00Z").unwrap())
+        );
+        assert_eq!(last.checked_add(Duration::parse("1000000h").unwrap()), None);
+        assert_eq!(
+            Timestamp::from_nanos(join_nanos(MAX_TIMESTAMP_SECONDS + 1, 0)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_display_timestamps() {
+        for s in &[
+            "1970-01-01T00:00:00Z",
+            "1969-12-31T23:59:59.999Z",
+            "2000-02-29T12:34:56.000000789Z",
+            "2021-06-01T09:00:00Z",
+            "0000-01-01T00:00:00Z",
+            "9999-12-31T23:59:59.999999999Z",
+        ] {
+            assert_eq!(&Timestamp::parse(s).unwrap().to_string(), s);
+        }
+        assert_eq!(
+            Timestamp::parse("2021-06-01T00:30:00-01:00")
+                .unwrap()
+                .to_string(),
+            "2021-06-01T01:30:00Z"
+        );
+    }
+
+    #[test]
+    fn test_durations() {
+        let parse = |s| Duration::parse(s).map(Duration::as_nanos);
+        assert_eq!(parse("90s"), Ok(90_000_000_000));
+        assert_eq!(parse("-1h30m"), Ok(-5_400_000_000_000));
+        assert_eq!(parse("1d1ms1us1ns"), Ok(86_400_000_000_000 + 1_001_001));
+        assert_eq!(
+            Duration::parse("-1ns"),
+            Ok(Duration {
+                seconds: -1,
+                nanos: 999_999_999
+            })
+        );
+        assert_eq!(
+            Duration::parse("3650000d").map(|d| d.seconds),
+            Ok(315_360_000_000)
+        );
+        for invalid in &[
+            "",
+            "-",
+            "1",
+            "h",
+            "1.5h",
+            "1 h",
+            "1y",
+            "99999999999999999999d",
+            "9223372036854775807s1s",
+        ] {
+            assert!(parse(invalid).is_err(), "{} should be invalid", invalid);
+        }
+
+        assert_eq!(Duration::default().to_string(), "0s");
+        assert_eq!(Duration::parse("90m").unwrap().to_string(), "1h30m");
+        assert_eq!(Duration::parse("-25h1ns").unwrap().to_string(), "-1d1h1ns");
+    }
+}
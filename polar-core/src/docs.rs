@@ -0,0 +1,377 @@
+//! Documentation of a policy, generated from the doc comments on its rules, rule types and
+//! constants.
+//!
+//! A doc comment is a run of lines starting with `##` right before a rule, rule type or
+//! constant. The documentation lists each generic rule and, for each of its definitions, the
+//! head as written in the policy and its doc comment and annotations, followed by the rule types
+//! and the constants the policy declares. It can be rendered as Markdown or HTML.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use super::error::PolarResult;
+use super::formatting::{format_params, ToPolarString};
+use super::parser::{parse_lines, Line};
+use super::rules::{Annotation, Constant, Rule};
+use super::terms::{Symbol, Term};
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PolicyDocs {
+    /// Map from each generic rule to its definitions, in the order they were loaded.
+    pub rules: BTreeMap<Symbol, Vec<RuleDocs>>,
+    /// Map from each rule name to its type declarations, e.g., `type allow(actor: User, _, _)`.
+    #[serde(default)]
+    pub types: BTreeMap<Symbol, Vec<RuleDocs>>,
+    /// Map from each constant defined in the policy to its definition.
+    pub constants: BTreeMap<Symbol, ConstantDocs>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConstantDocs {
+    /// The definition of the constant, e.g., `const ADMIN_ROLES = ["owner", "admin"]`.
+    pub definition: String,
+    pub doc: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RuleDocs {
+    /// Head of the rule, with its specializers, e.g., `allow(actor: User, "read", resource)`.
+    pub signature: String,
+    pub doc: Option<String>,
+    /// Annotations on the rule, e.g., `@owner("billing-team")`.
+    pub annotations: Vec<Annotation>,
+}
+
+impl RuleDocs {
+    fn new(rule: &Rule) -> Self {
+        let signature = format!(
+            "{}({})",
+            rule.name.to_polar(),
+            format_params(&rule.params, ", ")
+        );
+        Self {
+            signature,
+            doc: rule.doc.clone(),
+            annotations: rule.annotations.clone(),
+        }
+    }
+
+    /// The arguments of the rule's annotations named `name`, e.g., `["pii"]` for `@tag("pii")`.
+    pub fn annotation_args(&self, name: &str) -> Vec<&Term> {
+        self.annotations
+            .iter()
+            .filter(|annotation| annotation.name.0 == name)
+            .flat_map(|annotation| &annotation.args)
+            .collect()
+    }
+
+    /// The annotations and head of the rule, one per line.
+    fn head(&self) -> String {
+        let mut head = String::new();
+        for annotation in &self.annotations {
+            writeln!(head, "{}", annotation.to_polar()).unwrap();
+        }
+        head + &self.signature
+    }
+}
+
+impl PolicyDocs {
+    /// Document the rules, rule types and constants in a Polar source.
+    pub fn from_source(src: &str) -> PolarResult<Self> {
+        let mut docs = Self::default();
+        for line in parse_lines(0, src)? {
+            match line {
+                Line::Rule(rule) => docs.add_rule(&rule),
+                Line::RuleType(rule_type) => docs.add_rule_type(&rule_type),
+                Line::Constant(constant) => docs.add_constant(&constant),
+                _ => {}
+            }
+        }
+        Ok(docs)
+    }
+
+    /// Add a rule to the documentation. The rule should not have been rewritten, so that its
+    /// head is shown as written.
+    pub fn add_rule(&mut self, rule: &Rule) {
+        self.rules
+            .entry(rule.name.clone())
+            .or_default()
+            .push(RuleDocs::new(rule));
+    }
+
+    /// Add a rule type declaration to the documentation.
+    pub fn add_rule_type(&mut self, rule_type: &Rule) {
+        self.types
+            .entry(rule_type.name.clone())
+            .or_default()
+            .push(RuleDocs::new(rule_type));
+    }
+
+    /// Add a constant to the documentation.
+    pub fn add_constant(&mut self, constant: &Constant) {
+        let definition = format!(
+            "const {} = {}",
+            constant.name.to_polar(),
+            constant.value.to_polar()
+        );
+        self.constants.insert(
+            constant.name.value().as_symbol().unwrap().clone(),
+            ConstantDocs {
+                definition,
+                doc: constant.doc.clone(),
+            },
+        );
+    }
+
+    /// Add the rules, rule types and constants documented in `other`, after the ones already
+    /// documented.
+    pub fn extend(&mut self, other: PolicyDocs) {
+        for (name, definitions) in other.rules {
+            self.rules.entry(name).or_default().extend(definitions);
+        }
+        for (name, declarations) in other.types {
+            self.types.entry(name).or_default().extend(declarations);
+        }
+        self.constants.extend(other.constants);
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::from("# Rules\n");
+        for (name, definitions) in &self.rules {
+            write!(out, "\n## `{}`\n", name).unwrap();
+            for definition in definitions {
+                write!(out, "\n```polar\n{}\n```\n", definition.head()).unwrap();
+                if let Some(doc) = &definition.doc {
+                    write!(out, "\n{}\n", doc).unwrap();
+                }
+            }
+        }
+        if !self.types.is_empty() {
+            out.push_str("\n# Rule types\n");
+        }
+        for (name, declarations) in &self.types {
+            write!(out, "\n## `{}`\n", name).unwrap();
+            for declaration in declarations {
+                write!(out, "\n```polar\ntype {}\n```\n", declaration.head()).unwrap();
+                if let Some(doc) = &declaration.doc {
+                    write!(out, "\n{}\n", doc).unwrap();
+                }
+            }
+        }
+        if !self.constants.is_empty() {
+            out.push_str("\n# Constants\n");
+        }
+        for (name, constant) in &self.constants {
+            write!(out, "\n## `{}`\n", name).unwrap();
+            write!(out, "\n```polar\n{}\n```\n", constant.definition).unwrap();
+            if let Some(doc) = &constant.doc {
+                write!(out, "\n{}\n", doc).unwrap();
+            }
+        }
+        out
+    }
+
+    pub fn to_html(&self) -> String {
+        let mut out = String::from("<h1>Rules</h1>\n");
+        for (name, definitions) in &self.rules {
+            let name = escape_html(&name.0);
+            writeln!(out, "<h2 id=\"{}\"><code>{}</code></h2>", name, name).unwrap();
+            for definition in definitions {
+                writeln!(
+                    out,
+                    "<pre><code class=\"language-polar\">{}</code></pre>",
+                    escape_html(&definition.head())
+                )
+                .unwrap();
+                write_paragraphs(&mut out, &definition.doc);
+            }
+        }
+        if !self.types.is_empty() {
+            out.push_str("<h1>Rule types</h1>\n");
+        }
+        for (name, declarations) in &self.types {
+            let name = escape_html(&name.0);
+            writeln!(out, "<h2 id=\"type-{}\"><code>{}</code></h2>", name, name).unwrap();
+            for declaration in declarations {
+                writeln!(
+                    out,
+                    "<pre><code class=\"language-polar\">type {}</code></pre>",
+                    escape_html(&declaration.head())
+                )
+                .unwrap();
+                write_paragraphs(&mut out, &declaration.doc);
+            }
+        }
+        if !self.constants.is_empty() {
+            out.push_str("<h1>Constants</h1>\n");
+        }
+        for (name, constant) in &self.constants {
+            let name = escape_html(&name.0);
+            writeln!(out, "<h2 id=\"{}\"><code>{}</code></h2>", name, name).unwrap();
+            writeln!(
+                out,
+                "<pre><code class=\"language-polar\">{}</code></pre>",
+                escape_html(&constant.definition)
+            )
+            .unwrap();
+            write_paragraphs(&mut out, &constant.doc);
+        }
+        out
+    }
+}
+
+/// Write a doc comment as HTML paragraphs. Blank lines in a doc comment separate paragraphs.
+fn write_paragraphs(out: &mut String, doc: &Option<String>) {
+    let paragraphs = doc.iter().flat_map(|doc| doc.split("\n\n"));
+    for paragraph in paragraphs.filter(|p| !p.trim().is_empty()) {
+        writeln!(out, "<p>{}</p>", escape_html(paragraph.trim())).unwrap();
+    }
+}
+
+fn escape_html(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::polar::Polar;
+
+    const POLICY: &str = r#"## Anyone can read a public repository.
+allow(_actor, "read", repo: Repository) if repo.is_public;
+
+## Admins can do anything.
+##
+## Even delete <everything>.
+allow(actor: User{role: "admin"}, _action, _resource);
+
+### Section header, not a doc comment.
+is_public(repo) if repo.visibility = "public";
+"#;
+
+    #[test]
+    fn test_doc_comments() {
+        let docs = PolicyDocs::from_source(POLICY).unwrap();
+        assert_eq!(
+            docs.rules[&sym!("allow")],
+            vec![
+                RuleDocs {
+                    signature: "allow(_actor, \"read\", repo: Repository)".to_owned(),
+                    doc: Some("Anyone can read a public repository.".to_owned()),
+                    annotations: vec![],
+                },
+                RuleDocs {
+                    signature: "allow(actor: User{role: \"admin\"}, _action, _resource)".to_owned(),
+                    doc: Some("Admins can do anything.\n\nEven delete <everything>.".to_owned()),
+                    annotations: vec![],
+                }
+            ]
+        );
+        assert_eq!(docs.rules[&sym!("is_public")][0].doc, None);
+
+        // A blank line, or an ordinary comment, between a doc comment and a rule detaches it.
+        let docs =
+            PolicyDocs::from_source("## Detached\n\nf(1);\n## Detached\n# Comment\ng(1);").unwrap();
+        assert_eq!(docs.rules[&sym!("f")][0].doc, None);
+        assert_eq!(docs.rules[&sym!("g")][0].doc, None);
+    }
+
+    #[test]
+    fn test_loaded_docs() {
+        let polar = Polar::new();
+        polar.load_str(POLICY).unwrap();
+        polar
+            .load_str("## Also public.\nis_public(_repo: PublicRepository);")
+            .unwrap();
+        let docs = polar.docs();
+        let signatures = docs.rules[&sym!("is_public")]
+            .iter()
+            .map(|definition| definition.signature.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            signatures,
+            vec!["is_public(repo)", "is_public(_repo: PublicRepository)"]
+        );
+
+        let markdown = docs.to_markdown();
+        assert!(markdown.contains(
+            "## `allow`\n\n```polar\nallow(_actor, \"read\", repo: Repository)\n```\n\n\
+             Anyone can read a public repository.\n"
+        ));
+        let html = docs.to_html();
+        assert!(html.contains("<h2 id=\"allow\"><code>allow</code></h2>\n"));
+        assert!(html
+            .contains("<p>Admins can do anything.</p>\n<p>Even delete &lt;everything&gt;.</p>\n"));
+
+        polar.clear_rules();
+        assert!(polar.docs().rules.is_empty());
+    }
+
+    #[test]
+    fn test_constant_docs() {
+        let polar = Polar::new();
+        polar
+            .load_str("## Roles that can do anything.\nconst ADMIN_ROLES = [\"owner\", \"admin\"];")
+            .unwrap();
+        let docs = polar.docs();
+        assert_eq!(
+            docs.constants[&sym!("ADMIN_ROLES")],
+            ConstantDocs {
+                definition: "const ADMIN_ROLES = [\"owner\", \"admin\"]".to_owned(),
+                doc: Some("Roles that can do anything.".to_owned()),
+            }
+        );
+        assert!(docs.to_markdown().contains(
+            "# Constants\n\n## `ADMIN_ROLES`\n\n```polar\n\
+             const ADMIN_ROLES = [\"owner\", \"admin\"]\n```\n\nRoles that can do anything.\n"
+        ));
+        assert!(docs.to_html().contains(
+            "<h1>Constants</h1>\n<h2 id=\"ADMIN_ROLES\"><code>ADMIN_ROLES</code></h2>\n"
+        ));
+
+        polar.clear_rules();
+        assert!(polar.docs().constants.is_empty());
+    }
+
+    #[test]
+    fn test_rule_type_docs() {
+        let polar = Polar::new();
+        polar
+            .load_str(
+                "## Who can do what.\ntype allow(actor: User, action: String, resource);\n\
+                 allow(_actor: User, \"read\", _resource);",
+            )
+            .unwrap();
+        let docs = polar.docs();
+        assert_eq!(docs.rules[&sym!("allow")].len(), 1);
+        assert_eq!(
+            docs.types[&sym!("allow")],
+            vec![RuleDocs {
+                signature: "allow(actor: User, action: String, resource)".to_owned(),
+                doc: Some("Who can do what.".to_owned()),
+                annotations: vec![],
+            }]
+        );
+        assert!(docs.to_markdown().contains(
+            "# Rule types\n\n## `allow`\n\n```polar\n\
+             type allow(actor: User, action: String, resource)\n```\n\nWho can do what.\n"
+        ));
+        assert!(docs
+            .to_html()
+            .contains("<h1>Rule types</h1>\n<h2 id=\"type-allow\"><code>allow</code></h2>\n"));
+
+        polar.clear_rules();
+        assert!(polar.docs().types.is_empty());
+    }
+}
@@ -44,16 +44,27 @@ pub struct ErrorContext {
 
 impl PolarError {
     pub fn set_context(mut self, source: Option<&Source>, term: Option<&Term>) -> Self {
+        if let ErrorKind::Parse(ParseError::Multiple { errors }) = &mut self.kind {
+            for error in errors.iter_mut() {
+                *error = error.clone().set_context(source, term);
+            }
+            return self;
+        }
         match (&self.kind, source, term) {
             (ErrorKind::Parse(e), Some(source), _) => match e {
                 ParseError::IntegerOverflow { loc, .. }
                 | ParseError::InvalidTokenCharacter { loc, .. }
                 | ParseError::InvalidToken { loc, .. }
-                | ParseError::UnrecognizedEOF { loc }
+                | ParseError::UnrecognizedEOF { loc, .. }
                 | ParseError::UnrecognizedToken { loc, .. }
                 | ParseError::ExtraToken { loc, .. }
                 | ParseError::WrongValueType { loc, .. }
-                | ParseError::ReservedWord { loc, .. } => {
+                | ParseError::ReservedWord { loc, .. }
+                | ParseError::ResourceBlock { loc, .. }
+                | ParseError::Module { loc, .. }
+                | ParseError::Import { loc, .. }
+                | ParseError::Constant { loc, .. }
+                | ParseError::BuiltinRule { loc, .. } => {
                     let (row, column) = crate::lexer::loc_to_pos(&source.src, *loc);
                     self.context.replace(ErrorContext {
                         source: source.clone(),
@@ -149,10 +160,14 @@ pub enum ParseError {
     #[allow(clippy::upper_case_acronyms)]
     UnrecognizedEOF {
         loc: usize,
+        /// Tokens that the parser would have accepted, as named in the grammar.
+        expected: Vec<String>,
     },
     UnrecognizedToken {
         token: String,
         loc: usize,
+        /// Tokens that the parser would have accepted, as named in the grammar.
+        expected: Vec<String>,
     },
     ExtraToken {
         token: String,
@@ -171,6 +186,38 @@ pub enum ParseError {
         term: Term,
         expected: String,
     },
+    /// A resource block that is well formed, but declares or uses its roles, permissions or
+    /// relations incorrectly.
+    ResourceBlock {
+        loc: usize,
+        msg: String,
+    },
+    /// A module declaration or export that is misplaced, or a call to a rule that is private
+    /// to its module.
+    Module {
+        loc: usize,
+        msg: String,
+    },
+    /// An import of a file that has not been loaded, or in a source that is not a file.
+    Import {
+        loc: usize,
+        msg: String,
+    },
+    /// A constant whose value is not a literal, or that is already defined.
+    Constant {
+        loc: usize,
+        msg: String,
+    },
+    /// A rule or rule type with the name and arity of a builtin, e.g., `count(x, g, n)`, which
+    /// could never be called.
+    BuiltinRule {
+        loc: usize,
+        msg: String,
+    },
+    /// More than one error was found in the same source.
+    Multiple {
+        errors: Vec<PolarError>,
+    },
 }
 
 impl fmt::Display for ErrorContext {
@@ -196,15 +243,28 @@ impl fmt::Display for ParseError {
                 token.escape_debug()
             ),
             Self::InvalidToken { .. } => write!(f, "found an unexpected sequence of characters"),
-            Self::UnrecognizedEOF { .. } => write!(
-                f,
-                "hit the end of the file unexpectedly. Did you forget a semi-colon"
-            ),
-            Self::UnrecognizedToken { token, .. } => write!(
-                f,
-                "did not expect to find the token '{}'",
-                token.escape_debug()
-            ),
+            Self::UnrecognizedEOF { expected, .. } => {
+                write!(f, "hit the end of the file unexpectedly")?;
+                if expected.iter().any(|e| e == ";") {
+                    write!(f, ". Did you forget a semi-colon")
+                } else {
+                    write_expected(f, expected)
+                }
+            }
+            Self::UnrecognizedToken {
+                token, expected, ..
+            } => {
+                write!(
+                    f,
+                    "did not expect to find the token '{}'",
+                    token.escape_debug()
+                )?;
+                write_expected(f, expected)?;
+                if let Some(hint) = unrecognized_token_hint(token, expected) {
+                    write!(f, ". {}", hint)?;
+                }
+                Ok(())
+            }
             Self::ExtraToken { token, .. } => write!(
                 f,
                 "did not expect to find the token '{}'",
@@ -223,10 +283,85 @@ impl fmt::Display for ParseError {
             Self::WrongValueType { term, expected, .. } => {
                 write!(f, "Wrong value type: {}. Expected a {}", term, expected)
             }
+            Self::ResourceBlock { msg, .. }
+            | Self::Module { msg, .. }
+            | Self::Import { msg, .. }
+            | Self::Constant { msg, .. }
+            | Self::BuiltinRule { msg, .. } => write!(f, "{}", msg),
+            Self::Multiple { errors } => {
+                write!(f, "found {} syntax errors:", errors.len())?;
+                for error in errors {
+                    write!(f, "\n  {}", error)?;
+                }
+                Ok(())
+            }
         }
     }
 }
 
+/// Tokens that end or separate parts of a rule. When the parser would have accepted too many
+/// tokens to list, these are the ones worth mentioning.
+const STRUCTURAL_TOKENS: &[&str] = &[";", "if", "and", "or", ",", ")", "]", "}"];
+
+/// Most tokens the parser may expect before the list is summarized.
+const MAX_EXPECTED: usize = 4;
+
+fn describe_token(token: &str) -> String {
+    match token {
+        "Integer" => "an integer".to_owned(),
+        "Float" => "a float".to_owned(),
+        "String" => "a string".to_owned(),
+        "Boolean" => "a boolean".to_owned(),
+        "Symbol" => "a name".to_owned(),
+        _ => format!("`{}`", token),
+    }
+}
+
+/// Write e.g. ", expected `;` or `and` after rule body".
+fn write_expected(f: &mut fmt::Formatter, expected: &[String]) -> fmt::Result {
+    let expects = |token: &str| expected.iter().any(|e| e == token);
+    let shown: Vec<String> = if expected.len() <= MAX_EXPECTED {
+        expected.iter().map(|e| describe_token(e)).collect()
+    } else {
+        STRUCTURAL_TOKENS
+            .iter()
+            .filter(|token| expects(token))
+            .map(|token| describe_token(token))
+            .collect()
+    };
+    let (last, rest) = match shown.split_last() {
+        Some(split) => split,
+        None => return Ok(()),
+    };
+    write!(f, ", expected ")?;
+    if !rest.is_empty() {
+        write!(f, "{} or ", rest.join(", "))?;
+    }
+    write!(f, "{}", last)?;
+    if expects("if") && expects(";") {
+        write!(f, " after rule head")?;
+    } else if expects("and") && expects(";") {
+        write!(f, " after rule body")?;
+    }
+    Ok(())
+}
+
+/// Suggest a fix for common mistakes that lead to an unexpected token.
+fn unrecognized_token_hint(token: &str, expected: &[String]) -> Option<&'static str> {
+    let expects = |token: &str| expected.iter().any(|e| e == token);
+    match token {
+        "if" if expects("and") => {
+            Some("A rule has only one `if`; use `and` to add conditions to its body")
+        }
+        "==" if expects(")") || expects(",") => Some(
+            "Rule heads cannot contain comparisons; \
+             move the comparison into the rule body, e.g., `f(x) if x == 1;`",
+        ),
+        _ if expects(";") && expects("and") => Some("Did you forget a semi-colon"),
+        _ => None,
+    }
+}
+
 // @TODO: Information about the context of the error.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum RuntimeError {
@@ -262,6 +397,10 @@ pub enum RuntimeError {
     IncompatibleBindings {
         msg: String,
     },
+    InvalidRegex {
+        pattern: String,
+        msg: String,
+    },
 }
 
 impl RuntimeError {
@@ -297,6 +436,9 @@ impl fmt::Display for RuntimeError {
                 write!(f, "Application error: {}", msg)
             }
             Self::FileLoading { msg } => write!(f, "Problem loading file: {}", msg),
+            Self::InvalidRegex { pattern, msg } => {
+                write!(f, "Invalid regex {:?}: {}", pattern, msg)
+            }
             Self::IncompatibleBindings { msg } => {
                 write!(f, "Attempted binding was incompatible: {}", msg)
             }
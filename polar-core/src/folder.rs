@@ -78,6 +78,9 @@ pub trait Folder: Sized {
     fn fold_list(&mut self, l: TermList) -> TermList {
         fold_list(l, self)
     }
+    fn fold_set(&mut self, s: Set) -> Set {
+        fold_set(s, self)
+    }
     fn fold_operation(&mut self, o: Operation) -> Operation {
         fold_operation(o, self)
     }
@@ -86,11 +89,22 @@ pub trait Folder: Sized {
     }
 }
 
-pub fn fold_rule<T: Folder>(Rule { name, params, body }: Rule, fld: &mut T) -> Rule {
+pub fn fold_rule<T: Folder>(
+    Rule {
+        name,
+        params,
+        body,
+        doc,
+        annotations,
+    }: Rule,
+    fld: &mut T,
+) -> Rule {
     Rule {
         name: fld.fold_name(name),
         params: params.into_iter().map(|p| fld.fold_param(p)).collect(),
         body: fld.fold_term(body),
+        doc,
+        annotations,
     }
 }
 
@@ -108,6 +122,8 @@ pub fn fold_value<T: Folder>(v: Value, fld: &mut T) -> Value {
         Value::Pattern(p) => Value::Pattern(fld.fold_pattern(p)),
         Value::Call(c) => Value::Call(fld.fold_call(c)),
         Value::List(l) => Value::List(fld.fold_list(l)),
+        Value::Set(s) => Value::Set(fld.fold_set(s)),
+        Value::Timestamp(_) | Value::Duration(_) => v,
         Value::Variable(v) => Value::Variable(fld.fold_variable(v)),
         Value::RestVariable(r) => Value::RestVariable(fld.fold_rest_variable(r)),
         Value::Expression(o) => Value::Expression(fld.fold_operation(o)),
@@ -194,6 +210,15 @@ pub fn fold_list<T: Folder>(l: TermList, fld: &mut T) -> TermList {
         .collect::<TermList>()
 }
 
+pub fn fold_set<T: Folder>(s: Set, fld: &mut T) -> Set {
+    Set::new(
+        s.elements()
+            .iter()
+            .map(|t| fld.fold_term(t.clone()))
+            .collect(),
+    )
+}
+
 pub fn fold_operator<T: Folder>(o: Operator, _fld: &mut T) -> Operator {
     o
 }
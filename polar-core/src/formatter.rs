@@ -0,0 +1,559 @@
+//! Canonical formatting of Polar source files.
+//!
+//! `format_source` reprints a policy in one style, and formatting its output again leaves it
+//! unchanged:
+//!
+//! - Each rule or query starts on a new line. Blank lines between them are kept, but runs of
+//!   blank lines are collapsed into one.
+//! - A rule that fits in `LINE_WIDTH` columns is written on one line. Otherwise each condition of
+//!   its body goes on its own line, indented by four spaces, with `and` (or `or`) at the end of
+//!   the line. Nested conjunctions and disjunctions that still don't fit are broken the same way,
+//!   inside parentheses.
+//! - Comments are kept. A comment on its own line stays before the rule, or the condition, that
+//!   follows it, and a comment after a `;` stays at the end of that line. A comment anywhere
+//!   else inside a rule is moved to its own line, before the next condition (or after the rule,
+//!   if there is none).
+//! - Each item of a resource block goes on its own line, indented by four spaces.
+
+use std::iter::Peekable;
+
+use super::error::{OperationalError, PolarResult};
+use super::formatting::{format_args, format_params, to_polar_parens, ToPolarString};
+use super::lexer::Lexer;
+use super::parser::{parse_lines, Line, LineEnds};
+use super::rules::{BlockItem, ResourceBlock};
+use super::terms::*;
+
+pub const LINE_WIDTH: usize = 80;
+
+const INDENT: &str = "    ";
+
+struct Comment {
+    offset: usize,
+    /// The comment, from its `#` to the end of its line, without trailing whitespace, or from
+    /// its `/*` to its `*/`.
+    text: String,
+}
+
+/// A rule or inline query, and its location in the source: from its first token to the end of
+/// the `;` that ends it.
+struct Statement {
+    line: Line,
+    start: usize,
+    end: usize,
+}
+
+/// Format a Polar source in the canonical style.
+pub fn format_source(src: &str) -> PolarResult<String> {
+    let lines = parse_lines(0, src)?;
+    let comments = find_comments(src);
+    let comment_count = comments.len();
+
+    let mut printer = Printer {
+        src,
+        out: String::new(),
+        last_end: None,
+        blank_line: false,
+    };
+    let mut comments = comments.into_iter().peekable();
+    for statement in find_statements(src, lines.clone()) {
+        for comment in take_before(&mut comments, statement.start) {
+            printer.comment(&comment);
+        }
+        let inner = take_before(&mut comments, statement.end);
+        printer.statement(&statement, inner);
+    }
+    for comment in comments {
+        printer.comment(&comment);
+    }
+    let mut formatted = printer.out;
+    if !formatted.is_empty() {
+        formatted.push('\n');
+    }
+
+    // Formatting must not change the meaning of the policy, or lose any of its comments.
+    let unchanged = parse_lines(0, &formatted).map_or(false, |formatted| formatted == lines)
+        && find_comments(&formatted).len() == comment_count;
+    if !unchanged {
+        return Err(OperationalError::InvalidState(
+            "formatting changed the policy; please report this as a bug".to_owned(),
+        )
+        .into());
+    }
+    Ok(formatted)
+}
+
+fn take_before<I>(comments: &mut Peekable<I>, offset: usize) -> Vec<Comment>
+where
+    I: Iterator<Item = Comment>,
+{
+    let mut taken = vec![];
+    while let Some(comment) = comments.peek() {
+        if comment.offset >= offset {
+            break;
+        }
+        taken.extend(comments.next());
+    }
+    taken
+}
+
+/// Find the comments in a source. The lexer skips them, so they are found by scanning the
+/// source for `#`s and `/*`s outside of strings.
+fn find_comments(src: &str) -> Vec<Comment> {
+    let mut comments = vec![];
+    let mut in_string = false;
+    let mut skip_to = 0;
+    for (i, c) in src.char_indices() {
+        if i < skip_to {
+            continue;
+        }
+        match c {
+            '"' => in_string = !in_string,
+            '\\' if in_string => skip_to = i + 2,
+            '#' if !in_string => {
+                let end = src[i..].find('\n').map_or(src.len(), |n| i + n);
+                comments.push(Comment {
+                    offset: i,
+                    text: src[i..end].trim_end().to_owned(),
+                });
+                skip_to = end;
+            }
+            '/' if !in_string && src[i..].starts_with("/*") => {
+                let end = block_comment_end(src, i);
+                comments.push(Comment {
+                    offset: i,
+                    text: src[i..end].to_owned(),
+                });
+                skip_to = end;
+            }
+            _ => {}
+        }
+    }
+    comments
+}
+
+/// Return where the block comment starting at `start` ends, after its closing `*/`. Block
+/// comments nest.
+fn block_comment_end(src: &str, start: usize) -> usize {
+    let bytes = src.as_bytes();
+    let mut depth = 0;
+    let mut i = start;
+    while i + 1 < bytes.len() {
+        match &bytes[i..i + 2] {
+            b"/*" => {
+                depth += 1;
+                i += 2;
+            }
+            b"*/" => {
+                depth -= 1;
+                i += 2;
+                if depth == 0 {
+                    return i;
+                }
+            }
+            _ => i += 1,
+        }
+    }
+    src.len()
+}
+
+/// Pair each parsed line with its location in the source.
+fn find_statements(src: &str, lines: Vec<Line>) -> Vec<Statement> {
+    let mut bounds = vec![];
+    let mut start = None;
+    let mut line_ends = LineEnds::default();
+    // The source has already been parsed, so there are no lexer errors.
+    for (left, token, right) in Lexer::new(src).flatten() {
+        let first = *start.get_or_insert(left);
+        if line_ends.ends_line(&token) {
+            bounds.push((first, right));
+            start = None;
+        }
+    }
+    lines
+        .into_iter()
+        .zip(bounds)
+        .map(|(line, (start, end))| Statement { line, start, end })
+        .collect()
+}
+
+struct Printer<'a> {
+    src: &'a str,
+    out: String,
+    /// Where the last statement or comment printed ended in the source.
+    last_end: Option<usize>,
+    /// Whether the next line must be preceded by a blank line.
+    blank_line: bool,
+}
+
+impl Printer<'_> {
+    /// Start a new line for something found at `offset` in the source. If there was a blank
+    /// line before it, keep one.
+    fn newline(&mut self, offset: usize) {
+        if let Some(end) = self.last_end {
+            self.out.push('\n');
+            if self.blank_line || self.src[end..offset].matches('\n').count() > 1 {
+                self.out.push('\n');
+            }
+            self.blank_line = false;
+        }
+    }
+
+    fn comment(&mut self, comment: &Comment) {
+        match self.last_end {
+            // The comment is at the end of the last statement's line.
+            Some(end) if !self.src[end..comment.offset].contains('\n') => self.out.push(' '),
+            _ => self.newline(comment.offset),
+        }
+        self.out.push_str(&comment.text);
+        self.last_end = Some(comment.offset + comment.text.len());
+    }
+
+    /// Print a statement, and the comments found inside it.
+    fn statement(&mut self, statement: &Statement, comments: Vec<Comment>) {
+        self.newline(statement.start);
+        if let Line::ResourceBlock(block) = &statement.line {
+            self.resource_block(block, comments);
+            self.last_end = Some(statement.end);
+            return;
+        }
+        // Module declarations, exports and constants have no body.
+        let no_body = Term::new_temporary(Value::Expression(Operation {
+            operator: Operator::And,
+            args: vec![],
+        }));
+        let (prefix, body) = match &statement.line {
+            Line::Rule(rule) => (
+                format!(
+                    "{}({})",
+                    rule.name.to_polar(),
+                    format_params(&rule.params, ", ")
+                ),
+                &rule.body,
+            ),
+            Line::RuleType(rule) => (
+                format!(
+                    "type {}({})",
+                    rule.name.to_polar(),
+                    format_params(&rule.params, ", ")
+                ),
+                &rule.body,
+            ),
+            Line::Query(query) => ("?=".to_owned(), query),
+            Line::Module(name) => (format!("module {}", name.to_polar()), &no_body),
+            Line::Export(names) => (
+                format!("export {}", format_args(Operator::And, names, ", ")),
+                &no_body,
+            ),
+            Line::Import(path) => (format!("import {}", path.to_polar()), &no_body),
+            Line::Constant(constant) => (
+                format!(
+                    "const {} = {}",
+                    constant.name.to_polar(),
+                    constant.value.to_polar()
+                ),
+                &no_body,
+            ),
+            Line::ResourceBlock(_) => unreachable!("resource blocks are printed by resource_block"),
+        };
+        // Each annotation goes on its own line before the rule.
+        if let Line::Rule(rule) = &statement.line {
+            for annotation in &rule.annotations {
+                self.out.push_str(&annotation.to_polar());
+                self.out.push('\n');
+            }
+        }
+        let (op, goals) = conditions(body);
+        let mut comments = comments.into_iter().peekable();
+        if goals.is_empty() {
+            self.out.push_str(&prefix);
+            self.out.push(';');
+        } else {
+            let keyword = if let Line::Rule(_) = statement.line {
+                " if"
+            } else {
+                ""
+            };
+            let separator = format!(" {} ", op.to_polar());
+            let flat = format!(
+                "{}{} {};",
+                prefix,
+                keyword,
+                format_args(op, goals, &separator)
+            );
+            // Comments before the last condition keep the statement from fitting on one line.
+            let last_left = goals
+                .last()
+                .and_then(Term::span)
+                .map_or(0, |(left, _)| left);
+            let commented = comments.peek().map_or(false, |c| c.offset < last_left);
+            if !commented && flat.len() <= LINE_WIDTH {
+                self.out.push_str(&flat);
+            } else {
+                self.out.push_str(&prefix);
+                self.out.push_str(keyword);
+                for (i, goal) in goals.iter().enumerate() {
+                    if let Some((left, _)) = goal.span() {
+                        for comment in take_before(&mut comments, left) {
+                            self.out.push('\n');
+                            self.out.push_str(INDENT);
+                            self.out.push_str(&comment.text);
+                        }
+                    }
+                    self.out.push('\n');
+                    self.out.push_str(&format_goal(goal, op, 1));
+                    if i + 1 < goals.len() {
+                        self.out.push(' ');
+                        self.out.push_str(&op.to_polar());
+                    } else {
+                        self.out.push(';');
+                    }
+                }
+            }
+        }
+        // Comments after the last condition go on their own lines after the statement, where
+        // they stay when it is formatted again. Separate them from the next statement, so that a
+        // `##` comment does not become its doc comment.
+        for comment in comments {
+            self.out.push('\n');
+            self.out.push_str(&comment.text);
+            self.blank_line = comment.text.starts_with("##");
+        }
+        self.last_end = Some(statement.end);
+    }
+
+    /// Print a resource block with each of its items on its own line, and the comments found
+    /// inside it. Blank lines between items are kept.
+    fn resource_block(&mut self, block: &ResourceBlock, comments: Vec<Comment>) {
+        self.out
+            .push_str(&format!("resource {} {{", block.resource.to_polar()));
+        let mut comments = comments.into_iter().peekable();
+        // Where the last item, or the block's name, ended in the source.
+        let mut last_end = block.resource.span().map_or(0, |(_, right)| right);
+        for (i, item) in block.items.iter().enumerate() {
+            let (text, terms) = match item {
+                BlockItem::Declaration { name, value } => (
+                    format!("{} = {};", name.to_polar(), value.to_polar()),
+                    vec![name, value],
+                ),
+                BlockItem::ShorthandRule {
+                    head,
+                    implier,
+                    relation,
+                } => {
+                    let mut text = format!("{} if {}", head.to_polar(), implier.to_polar());
+                    if let Some(relation) = relation {
+                        text.push_str(&format!(" on {}", relation.to_polar()));
+                    }
+                    text.push(';');
+                    (
+                        text,
+                        vec![head, implier].into_iter().chain(relation).collect(),
+                    )
+                }
+            };
+            let left = terms[0].span().map_or(last_end, |(left, _)| left);
+            let first_left = comments.peek().map_or(left, |c| c.offset.min(left));
+            if i > 0 && self.src[last_end..first_left].matches('\n').count() > 1 {
+                self.out.push('\n');
+            }
+            for comment in take_before(&mut comments, left) {
+                self.block_comment(&comment, last_end, i > 0);
+            }
+            self.out.push('\n');
+            self.out.push_str(INDENT);
+            self.out.push_str(&text);
+            let right = terms
+                .iter()
+                .filter_map(|term| term.span())
+                .map(|(_, right)| right)
+                .max()
+                .unwrap_or(left);
+            last_end = self.src[right..].find(';').map_or(right, |n| right + n + 1);
+        }
+        for comment in comments {
+            self.block_comment(&comment, last_end, !block.items.is_empty());
+        }
+        if !block.items.is_empty() {
+            self.out.push('\n');
+        }
+        self.out.push('}');
+    }
+
+    /// Print a comment inside a resource block: at the end of the line of the item before it,
+    /// if it was there in the source, and on its own line otherwise.
+    fn block_comment(&mut self, comment: &Comment, last_end: usize, after_item: bool) {
+        if after_item && !self.src[last_end..comment.offset].contains('\n') {
+            self.out.push(' ');
+        } else {
+            self.out.push('\n');
+            self.out.push_str(INDENT);
+        }
+        self.out.push_str(&comment.text);
+    }
+}
+
+/// Split a rule body or query into the conditions that go on their own lines when it does not
+/// fit on one, and the operator that joins them.
+fn conditions(body: &Term) -> (Operator, &[Term]) {
+    match body.value() {
+        Value::Expression(Operation {
+            operator: Operator::And,
+            args,
+        }) => match args.as_slice() {
+            [goal] => match goal.value() {
+                Value::Expression(Operation {
+                    operator: Operator::Or,
+                    args,
+                }) if !args.is_empty() => (Operator::Or, args),
+                _ => (Operator::And, args),
+            },
+            _ => (Operator::And, args),
+        },
+        Value::Expression(Operation {
+            operator: Operator::Or,
+            args,
+        }) if !args.is_empty() => (Operator::Or, args),
+        _ => (Operator::And, std::slice::from_ref(body)),
+    }
+}
+
+/// Format a condition joined to its siblings by `parent`, indented `depth` levels.
+fn format_goal(goal: &Term, parent: Operator, depth: usize) -> String {
+    let indent = INDENT.repeat(depth);
+    let flat = match goal.value() {
+        // Make the grouping explicit when mixing `and` and `or` over several lines.
+        Value::Expression(Operation {
+            operator: Operator::And,
+            ..
+        })
+        | Value::Expression(Operation {
+            operator: Operator::Or,
+            ..
+        }) => format!("({})", goal.to_polar()),
+        _ => to_polar_parens(parent, goal),
+    };
+    // Leave room for the ` and` or ` or` that may follow.
+    if indent.len() + flat.len() + 4 <= LINE_WIDTH {
+        return indent + &flat;
+    }
+    match goal.value() {
+        Value::Expression(Operation {
+            operator: operator @ Operator::And,
+            args,
+        })
+        | Value::Expression(Operation {
+            operator: operator @ Operator::Or,
+            args,
+        }) if args.len() > 1 => {
+            let separator = format!(" {}\n", operator.to_polar());
+            let lines = args
+                .iter()
+                .map(|arg| format_goal(arg, *operator, depth + 1))
+                .collect::<Vec<_>>();
+            format!("{}(\n{}\n{})", indent, lines.join(&separator), indent)
+        }
+        _ => indent + &flat,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_formats(src: &str, expected: &str) {
+        let formatted = format_source(src).unwrap();
+        assert_eq!(formatted, expected);
+        assert_eq!(format_source(&formatted).unwrap(), formatted);
+    }
+
+    #[test]
+    fn test_format_spacing() {
+        assert_formats(
+            "f(x,y)if x=y   and g( x );\n\n\n\ng(1);h(x:Foo,y:(Bar),\"a\\\"b\");",
+            "f(x, y) if x = y and g(x);\n\ng(1);\nh(x: Foo, y: (Bar), \"a\\\"b\");\n",
+        );
+        assert_formats("", "");
+        assert_formats(
+            "?= f(1.0) and x.(\"a-b\") = a - (b - c);",
+            "?= f(1.0) and x.(\"a-b\") = a - (b - c);\n",
+        );
+        assert_formats(
+            "type  allow(actor:User,action:String,resource);",
+            "type allow(actor: User, action: String, resource);\n",
+        );
+        assert_formats(
+            "module  billing;\nexport is_member,has_plan ;",
+            "module billing;\nexport is_member, has_plan;\n",
+        );
+        assert_formats(
+            "const  ADMIN_ROLES=[\"owner\",\"admin\"] ;",
+            "const ADMIN_ROLES = [\"owner\", \"admin\"];\n",
+        );
+        assert_formats(
+            "@tag( \"pii\" )@deprecated allow(x)if x=1;",
+            "@tag(\"pii\")\n@deprecated\nallow(x) if x = 1;\n",
+        );
+    }
+
+    #[test]
+    fn test_format_resource_blocks() {
+        assert_formats(
+            "resource Repo{roles=[\"reader\",\"maintainer\"];# Roles\n\
+             permissions = [\"read\"];\n\n\n\
+             # Shorthand rules\n\
+             \"read\"   if \"reader\"; \"reader\" if \"member\" on \"parent\";}\n\
+             resource Org {}",
+            "resource Repo {\n    \
+             roles = [\"reader\", \"maintainer\"]; # Roles\n    \
+             permissions = [\"read\"];\n\n    \
+             # Shorthand rules\n    \
+             \"read\" if \"reader\";\n    \
+             \"reader\" if \"member\" on \"parent\";\n\
+             }\n\
+             resource Org {}\n",
+        );
+    }
+
+    #[test]
+    fn test_format_long_rules() {
+        assert_formats(
+            "allow(actor: User, \"read\", resource: Repository) if resource.is_public or \
+             actor.is_admin or (actor.name = resource.owner and resource.owner.active);",
+            "allow(actor: User, \"read\", resource: Repository) if\n    \
+             resource.is_public or\n    \
+             actor.is_admin or\n    \
+             (actor.name = resource.owner and resource.owner.active);\n",
+        );
+        assert_formats(
+            "f(x) if g(x) and (some_very_long_condition_name(x, 1) or some_other_long_condition(x, 2) \
+             or yet_another_condition(x, 3));",
+            "f(x) if\n    \
+             g(x) and\n    \
+             (\n        \
+             some_very_long_condition_name(x, 1) or\n        \
+             some_other_long_condition(x, 2) or\n        \
+             yet_another_condition(x, 3)\n    \
+             );\n",
+        );
+    }
+
+    #[test]
+    fn test_format_comments() {
+        assert_formats(
+            "# Leading comment\nf(x) if # head\n  g(x) and # after g\n  h(x); # trailing\n\n\
+             # Before i\ni(x) if j(x) # inside\n;\n# The end",
+            "# Leading comment\nf(x) if\n    # head\n    g(x) and\n    # after g\n    h(x); # trailing\n\n\
+             # Before i\ni(x) if j(x);\n# inside\n# The end\n",
+        );
+        assert_formats(
+            "## Doc for f.\nf(x) if g(x) ## not a doc comment\n;\ng(1);",
+            "## Doc for f.\nf(x) if g(x);\n## not a doc comment\n\ng(1);\n",
+        );
+        assert_formats("f(\"# not a comment\");", "f(\"# not a comment\");\n");
+        assert_formats(
+            "/* f(1);\n /* nested */ g(1); */\nf(x) if /* why */ g(x);",
+            "/* f(1);\n /* nested */ g(1); */\nf(x) if\n    /* why */\n    g(x);\n",
+        );
+    }
+}
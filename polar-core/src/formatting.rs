@@ -125,6 +125,20 @@ fn precedence(o: &Operator) -> i32 {
         Operator::New => 10,
         Operator::Cut => 10,
         Operator::ForAll => 10,
+        Operator::FindAll => 10,
+        Operator::Count => 10,
+        Operator::Sum => 10,
+        Operator::Min => 10,
+        Operator::Max => 10,
+        Operator::RegexMatch => 10,
+        Operator::Format => 10,
+        Operator::Union => 10,
+        Operator::Intersection => 10,
+        Operator::Difference => 10,
+        Operator::Subset => 10,
+        Operator::Timestamp => 10,
+        Operator::Duration => 10,
+        Operator::Now => 10,
         Operator::Dot => 9,
         Operator::In => 8,
         Operator::Isa => 8,
@@ -145,6 +159,7 @@ fn precedence(o: &Operator) -> i32 {
         Operator::Not => 3,
         Operator::Or => 2,
         Operator::And => 1,
+        Operator::If => 0,
     }
 }
 
@@ -167,6 +182,17 @@ pub fn to_polar_parens(op: Operator, t: &Term) -> String {
     }
 }
 
+/// Format the right operand of a binary operator. Binary operators associate to the left, so an
+/// operand with the same precedence needs parentheses too, as in `a - (b - c)`.
+pub fn to_polar_parens_right(op: Operator, t: &Term) -> String {
+    match t.value() {
+        Value::Expression(Operation {
+            operator: other, ..
+        }) if precedence(&op) == precedence(other) => format!("({})", t.to_polar()),
+        _ => to_polar_parens(op, t),
+    }
+}
+
 pub mod display {
     use crate::formatting::{format_args, format_params};
     use std::fmt;
@@ -174,7 +200,7 @@ pub mod display {
 
     use super::ToPolarString;
     use crate::bindings::Binding;
-    use crate::numerics::Numeric;
+    use crate::numerics::{Decimal, Numeric};
     use crate::rules::Rule;
     use crate::terms::{Operation, Operator, Symbol, Term, Value};
     use crate::vm::*;
@@ -273,6 +299,10 @@ pub mod display {
                 Goal::PopQuery { term } => write!(fmt, "PopQuery({})", term.to_polar()),
                 Goal::Query { term } => write!(fmt, "Query({})", term.to_polar()),
                 Goal::Run { .. } => write!(fmt, "Run(...)"),
+                Goal::Aggregate { term, .. } => write!(fmt, "Aggregate({})", term.to_polar()),
+                Goal::CompleteTable { call, .. } => {
+                    write!(fmt, "CompleteTable({})", call.to_polar())
+                }
                 Goal::FilterRules {
                     applicable_rules,
                     unfiltered_rules,
@@ -341,16 +371,64 @@ pub mod display {
             match self {
                 Self::Integer(i) => write!(f, "{}", i),
                 Self::Float(float) => write!(f, "{}", float),
+                Self::Decimal(d) => write!(f, "{}", d),
+            }
+        }
+    }
+
+    impl fmt::Display for Decimal {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            let sign = if self.coefficient() < 0 { "-" } else { "" };
+            let digits = self.coefficient().unsigned_abs().to_string();
+            let scale = self.scale() as usize;
+            if scale == 0 {
+                write!(f, "{}{}", sign, digits)
+            } else {
+                // Pad with zeros so there is at least one digit before the point.
+                let digits = format!("{:0>width$}", digits, width = scale + 1);
+                let (integral, fraction) = digits.split_at(digits.len() - scale);
+                write!(f, "{}{}.{}", sign, integral, fraction)
             }
         }
     }
 }
 
 pub mod to_polar {
-    use crate::formatting::{format_args, format_params, to_polar_parens};
+    use crate::formatting::{format_args, format_params, to_polar_parens, to_polar_parens_right};
+    use crate::numerics::Numeric;
     use crate::rules::*;
     use crate::terms::*;
 
+    /// Escape a string so that the lexer reads it back unchanged.
+    fn escape_string(s: &str) -> String {
+        let mut escaped = String::with_capacity(s.len());
+        for c in s.chars() {
+            match c {
+                '"' => escaped.push_str("\\\""),
+                '\\' => escaped.push_str("\\\\"),
+                '\n' => escaped.push_str("\\n"),
+                '\r' => escaped.push_str("\\r"),
+                '\t' => escaped.push_str("\\t"),
+                '\0' => escaped.push_str("\\0"),
+                c if c.is_control() => escaped.push_str(&format!("\\u{{{:x}}}", c as u32)),
+                c => escaped.push(c),
+            }
+        }
+        escaped
+    }
+
+    /// Return true if `s` can be written as a bare field name after a `.`.
+    fn is_identifier(s: &str) -> bool {
+        const KEYWORDS: &[&str] = &[
+            "and", "cut", "debug", "false", "forall", "if", "in", "matches", "mod", "new", "not",
+            "or", "print", "rem", "true",
+        ];
+        let mut chars = s.chars();
+        matches!(chars.next(), Some(c) if c.is_alphabetic() || c == '_')
+            && chars.all(|c| c.is_alphanumeric() || c == '_')
+            && !KEYWORDS.contains(&s)
+    }
+
     /// Effectively works as a reverse-parser. Allows types to be turned
     /// back into polar-parseable strings.
     pub trait ToPolarString {
@@ -404,6 +482,7 @@ pub mod to_polar {
                 Lt => "<",
                 Or => "or",
                 And => "and",
+                If => "if",
                 New => "new",
                 Dot => ".",
                 Unify => "=",
@@ -411,6 +490,20 @@ pub mod to_polar {
                 In => "in",
                 Cut => "cut",
                 ForAll => "forall",
+                FindAll => "findall",
+                Count => "count",
+                Sum => "sum",
+                Min => "min",
+                Max => "max",
+                RegexMatch => "regex_match",
+                Format => "format",
+                Union => "union",
+                Intersection => "intersection",
+                Difference => "difference",
+                Subset => "subset",
+                Timestamp => "timestamp",
+                Duration => "duration",
+                Now => "now",
                 Debug => "debug",
                 Print => "print",
                 Isa => "matches",
@@ -433,6 +526,43 @@ pub mod to_polar {
                     self.args[0].to_polar(),
                     self.args[1].to_polar()
                 ),
+                FindAll | Count | Sum | Min | Max | RegexMatch | Union | Intersection
+                | Difference | Subset => format!(
+                    "{}({})",
+                    self.operator.to_polar(),
+                    self.args
+                        .iter()
+                        .map(|arg| arg.to_polar())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+                // The format arguments are in a list.
+                Format => {
+                    let mut args = vec![self.args[0].clone()];
+                    if let Value::List(values) = self.args[1].value() {
+                        args.extend(values.iter().cloned());
+                    }
+                    let args = args.iter().map(|arg| arg.to_polar()).collect::<Vec<_>>();
+                    let call = format!("format({})", args.join(", "));
+                    match self.args.get(2) {
+                        Some(result) => format!("{} = {}", call, result.to_polar()),
+                        None => call,
+                    }
+                }
+                // Rewritten with a result argument, e.g., `now() = _now_1`.
+                Timestamp | Duration | Now => {
+                    let arity = if self.operator == Now { 0 } else { 1 };
+                    let args = self.args.iter().take(arity).map(|arg| arg.to_polar());
+                    let call = format!(
+                        "{}({})",
+                        self.operator.to_polar(),
+                        args.collect::<Vec<_>>().join(", ")
+                    );
+                    match self.args.get(arity) {
+                        Some(result) => format!("{} = {}", call, result.to_polar()),
+                        None => call,
+                    }
+                }
                 New => {
                     if self.args.len() == 1 {
                         format!("new {}", to_polar_parens(self.operator, &self.args[0]))
@@ -447,7 +577,11 @@ pub mod to_polar {
                 // Lookup operator
                 Dot => {
                     let call_term = if let Value::String(s) = self.args[1].value() {
-                        s.to_string()
+                        if is_identifier(s) {
+                            s.to_string()
+                        } else {
+                            format!("({})", self.args[1].to_polar())
+                        }
                     } else {
                         self.args[1].to_polar()
                     };
@@ -463,6 +597,12 @@ pub mod to_polar {
                         _ => format!(".({})", format_args(self.operator, &self.args, ", ")),
                     }
                 }
+                If => format!(
+                    "if {} then {} else {}",
+                    self.args[0].to_polar(),
+                    self.args[1].to_polar(),
+                    self.args[2].to_polar()
+                ),
                 // Unary operators
                 Not => format!(
                     "{} {}",
@@ -476,7 +616,7 @@ pub mod to_polar {
                         "{} {} {}",
                         to_polar_parens(self.operator, &self.args[0]),
                         self.operator.to_polar(),
-                        to_polar_parens(self.operator, &self.args[1]),
+                        to_polar_parens_right(self.operator, &self.args[1]),
                     ),
                     3 => format!(
                         "{} {} {} = {}",
@@ -513,9 +653,23 @@ pub mod to_polar {
         fn to_polar(&self) -> String {
             match &self.specializer {
                 None => self.parameter.to_polar(),
-                Some(specializer) => {
-                    format!("{}: {}", self.parameter.to_polar(), specializer.to_polar())
+                // A parenthesized specializer is matched by value, not as a class name.
+                Some(specializer) if matches!(specializer.value(), Value::Variable(_)) => {
+                    format!(
+                        "{}: ({})",
+                        self.parameter.to_polar(),
+                        specializer.to_polar()
+                    )
                 }
+                // A bare class name is parsed as an instance pattern with no fields.
+                Some(specializer) => match specializer.value() {
+                    Value::Pattern(Pattern::Instance(InstanceLiteral { tag, fields }))
+                        if fields.fields.is_empty() =>
+                    {
+                        format!("{}: {}", self.parameter.to_polar(), tag.to_polar())
+                    }
+                    _ => format!("{}: {}", self.parameter.to_polar(), specializer.to_polar()),
+                },
             }
         }
     }
@@ -542,9 +696,23 @@ pub mod to_polar {
         }
     }
 
+    impl ToPolarString for Annotation {
+        fn to_polar(&self) -> String {
+            if self.args.is_empty() {
+                format!("@{}", self.name.to_polar())
+            } else {
+                format!(
+                    "@{}({})",
+                    self.name.to_polar(),
+                    format_args(Operator::And, &self.args, ", ")
+                )
+            }
+        }
+    }
+
     impl ToPolarString for Rule {
         fn to_polar(&self) -> String {
-            match &self.body.value() {
+            let rule = match &self.body.value() {
                 Value::Expression(Operation {
                     operator: Operator::And,
                     args,
@@ -565,7 +733,13 @@ pub mod to_polar {
                     }
                 }
                 _ => panic!("Not any sorta rule I parsed"),
-            }
+            };
+            let annotations = self
+                .annotations
+                .iter()
+                .map(|annotation| annotation.to_polar() + " ")
+                .collect::<String>();
+            annotations + &rule
         }
     }
 
@@ -593,8 +767,11 @@ pub mod to_polar {
     impl ToPolarString for Value {
         fn to_polar(&self) -> String {
             match self {
+                // Floats keep their decimal point (or exponent), so they parse as floats again.
+                Value::Number(Numeric::Float(f)) => format!("{:?}", f),
+                Value::Number(Numeric::Decimal(d)) => format!("{}d", d),
                 Value::Number(i) => format!("{}", i),
-                Value::String(s) => format!("\"{}\"", s),
+                Value::String(s) => format!("\"{}\"", escape_string(s)),
                 Value::Boolean(b) => {
                     if *b {
                         "true".to_string()
@@ -607,6 +784,11 @@ pub mod to_polar {
                 Value::ExternalInstance(i) => i.to_polar(),
                 Value::Call(c) => c.to_polar(),
                 Value::List(l) => format!("[{}]", format_args(Operator::And, l, ", "),),
+                // `{}` is an empty dictionary.
+                Value::Set(s) if s.is_empty() => "set()".to_owned(),
+                Value::Set(s) => format!("{{{}}}", format_args(Operator::And, s.elements(), ", ")),
+                Value::Timestamp(t) => format!("timestamp(\"{}\")", t),
+                Value::Duration(d) => format!("duration(\"{}\")", d),
                 Value::Variable(s) => s.to_polar(),
                 Value::RestVariable(s) => format!("*{}", s.to_polar()),
                 Value::Expression(e) => e.to_polar(),
@@ -1,7 +1,13 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+
+use regex::Regex;
 
 pub use super::bindings::Bindings;
 use super::counter::Counter;
+use super::docs::PolicyDocs;
+use super::modules::Module;
+use super::rule_types::RuleTypes;
 use super::rules::*;
 use super::sources::*;
 use super::terms::*;
@@ -12,27 +18,59 @@ use super::terms::*;
 #[derive(Default)]
 pub struct KnowledgeBase {
     pub constants: Bindings,
+    /// The constants defined by the loaded policies, rather than registered by the host.
+    pub policy_constants: HashSet<Symbol>,
     pub rules: HashMap<Symbol, GenericRule>,
     pub sources: Sources,
+    /// Documentation of the rules, as written before they were rewritten.
+    pub docs: PolicyDocs,
+    /// Map from each module to the rules it exports.
+    pub modules: HashMap<Symbol, Module>,
+    /// Types declared for rules, which their definitions and calls must match.
+    pub rule_types: RuleTypes,
     /// For symbols returned from gensym.
     gensym_counter: Counter,
     /// For call IDs, instance IDs, symbols, etc.
     id_counter: Counter,
     pub inline_queries: Vec<Term>,
+    /// Compiled regular expressions, by pattern.
+    regexes: Mutex<HashMap<String, Regex>>,
 }
 
+/// The most compiled regular expressions to keep; the cache is emptied when it is full.
+const MAX_CACHED_REGEXES: usize = 1000;
+
 impl KnowledgeBase {
     pub fn new() -> Self {
         Self {
             constants: HashMap::new(),
+            policy_constants: HashSet::new(),
             rules: HashMap::new(),
             sources: Sources::default(),
+            docs: PolicyDocs::default(),
+            modules: HashMap::new(),
+            rule_types: RuleTypes::default(),
             id_counter: Counter::default(),
             gensym_counter: Counter::default(),
             inline_queries: vec![],
+            regexes: Mutex::new(HashMap::new()),
         }
     }
 
+    /// Compile `pattern`, or return the cached regular expression for it.
+    pub fn regex(&self, pattern: &str) -> Result<Regex, regex::Error> {
+        let mut regexes = self.regexes.lock().unwrap();
+        if let Some(regex) = regexes.get(pattern) {
+            return Ok(regex.clone());
+        }
+        let regex = Regex::new(pattern)?;
+        if regexes.len() >= MAX_CACHED_REGEXES {
+            regexes.clear();
+        }
+        regexes.insert(pattern.to_owned(), regex.clone());
+        Ok(regex)
+    }
+
     /// Return a monotonically increasing integer ID.
     ///
     /// Wraps around at 52 bits of precision so that it can be safely
@@ -72,4 +110,25 @@ impl KnowledgeBase {
     pub fn is_constant(&self, name: &Symbol) -> bool {
         self.constants.contains_key(name)
     }
+
+    /// Define a constant in a policy. It is removed when the rules are cleared.
+    pub fn policy_constant(&mut self, name: Symbol, value: Term) {
+        self.policy_constants.insert(name.clone());
+        self.constant(name, value);
+    }
+
+    /// Map from each constant defined in a policy to its value.
+    pub fn policy_constant_values(&self) -> HashMap<Symbol, Term> {
+        self.policy_constants
+            .iter()
+            .map(|name| (name.clone(), self.constants[name].clone()))
+            .collect()
+    }
+
+    /// Remove the constants defined in policies.
+    pub fn clear_policy_constants(&mut self) {
+        for name in self.policy_constants.drain() {
+            self.constants.remove(&name);
+        }
+    }
 }
@@ -1,35 +1,47 @@
 #![allow(clippy::upper_case_acronyms)]
 
 use super::error::ParseError;
+use super::numerics::Decimal;
 use super::terms::Symbol;
+use std::collections::VecDeque;
 use std::iter::Peekable;
 use std::str::{CharIndices, FromStr};
 
 pub type SrcPos = (usize, usize);
 
-// Take a location in a string and return the row and column.
+// Take a byte offset into a string, as used by the lexer, and return the row and the column,
+// counted in characters.
 pub fn loc_to_pos(src: &str, loc: usize) -> SrcPos {
-    let mut row = 0;
-    let mut col = 0;
-    let mut chars = src.chars();
-    for _ in 0..loc {
-        let c = chars.next();
-        match c {
-            Some('\n') => {
-                row += 1;
-                col = 0;
-            }
-            Some(_) => col += 1,
-            None => panic!("loc is longer than the string."),
-        }
+    assert!(loc <= src.len(), "loc is longer than the string.");
+    let before = &src[..loc];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    (
+        before.matches('\n').count(),
+        before[line_start..].chars().count(),
+    )
+}
+
+fn invalid_character(token: &str, c: char, loc: usize) -> ParseError {
+    ParseError::InvalidTokenCharacter {
+        token: token.to_owned(),
+        c,
+        loc,
     }
-    (row, col)
 }
 
 pub struct Lexer<'input> {
+    input: &'input str,
     c: Option<(usize, char)>,
     chars: Peekable<CharIndices<'input>>,
+    /// Where to stop lexing; the end of the input except in an interpolated string.
+    end: usize,
+    /// Tokens scanned ahead, e.g., for an interpolated string.
+    pending: VecDeque<Spanned<Token, usize, ParseError>>,
     buf: String,
+    /// Lines of the doc comment right before the last token, without their `##`.
+    doc: Vec<String>,
+    /// True until something other than whitespace is found on the current line.
+    line_start: bool,
 }
 
 impl<'input> Lexer<'input> {
@@ -37,7 +49,29 @@ impl<'input> Lexer<'input> {
         let mut chars = input.char_indices().peekable();
         let c = chars.next();
         let buf = String::new();
-        Lexer { c, chars, buf }
+        Lexer {
+            input,
+            c,
+            chars,
+            end: input.len(),
+            pending: VecDeque::new(),
+            buf,
+            doc: vec![],
+            line_start: true,
+        }
+    }
+
+    /// Take the doc comment right before the last token returned, if there was one.
+    ///
+    /// A doc comment is a run of lines starting with `##`, with no blank lines or other
+    /// comments between it and the token. Lines starting with three or more `#`s are ordinary
+    /// comments, so that they can still be used as section headers.
+    pub fn take_doc(&mut self) -> Option<String> {
+        if self.doc.is_empty() {
+            None
+        } else {
+            Some(self.doc.drain(..).collect::<Vec<_>>().join("\n"))
+        }
     }
 }
 
@@ -47,6 +81,7 @@ pub type Spanned<Tok, Loc, Error> = Result<(Loc, Tok, Loc), Error>;
 pub enum Token {
     Integer(i64),
     Float(f64),
+    Decimal(Decimal),
     String(String),
     Boolean(bool),
     Symbol(Symbol),
@@ -85,10 +120,13 @@ pub enum Token {
     Isa,       // isa
     ForAll,    // forall
     If,        // if
+    Then,      // then
+    Else,      // else
     And,       // and
     Or,        // or
     Not,       // not
     Matches,   // matches
+    At,        // @
 }
 
 impl ToString for Token {
@@ -96,6 +134,7 @@ impl ToString for Token {
         match self {
             Token::Integer(i) => i.to_string(),
             Token::Float(f) => f.to_string(),
+            Token::Decimal(d) => format!("{}d", d),
             Token::String(s) => s.clone(),
             Token::Boolean(b) => b.to_string(),
             Token::Symbol(sym) => sym.0.clone(),
@@ -134,38 +173,101 @@ impl ToString for Token {
             Token::Isa => "isa".to_owned(),         // isa
             Token::ForAll => "forall".to_owned(),   // forall
             Token::If => "if".to_owned(),           // if
+            Token::Then => "then".to_owned(),       // then
+            Token::Else => "else".to_owned(),       // else
             Token::And => "and".to_owned(),         // and
             Token::Or => "or".to_owned(),           // or
             Token::Not => "not".to_owned(),         // not
             Token::Matches => "matches".to_owned(), // matches
+            Token::At => "@".to_owned(),            // @
         }
     }
 }
 
 impl<'input> Lexer<'input> {
+    /// Skip whitespace and comments. Fails if a block comment is not closed.
     #[inline]
-    fn skip_whitespace(&mut self) {
+    fn skip_whitespace(&mut self) -> Result<(), ParseError> {
+        self.doc.clear();
         loop {
             match self.c {
-                Some((_, ' ')) | Some((_, '\n')) | Some((_, '\r')) | Some((_, '\t')) => {
+                Some((_, ' ')) | Some((_, '\r')) | Some((_, '\t')) => {
                     self.c = self.chars.next();
                 }
-                Some((_, '#')) => {
+                Some((_, '\n')) => {
+                    // A blank line ends a doc comment.
+                    if self.line_start {
+                        self.doc.clear();
+                    }
+                    self.line_start = true;
                     self.c = self.chars.next();
+                }
+                Some((_, '#')) => {
+                    let mut hashes = 0;
+                    while let Some((_, '#')) = self.c {
+                        hashes += 1;
+                        self.c = self.chars.next();
+                    }
+                    self.buf.clear();
                     loop {
                         match self.c {
                             None | Some((_, '\r')) | Some((_, '\n')) => {
                                 break;
                             }
-                            _ => {
+                            Some((_, c)) => {
+                                self.buf.push(c);
                                 self.c = self.chars.next();
                             }
                         }
                     }
+                    if self.line_start && hashes == 2 {
+                        let line = self.buf.strip_prefix(' ').unwrap_or(&self.buf);
+                        self.doc.push(line.trim_end().to_owned());
+                    } else {
+                        self.doc.clear();
+                    }
+                    self.line_start = false;
+                }
+                Some((i, '/')) if matches!(self.chars.peek(), Some((_, '*'))) => {
+                    self.skip_block_comment(i)?;
+                    self.doc.clear();
+                    self.line_start = false;
                 }
                 _ => break,
             };
         }
+        self.line_start = false;
+        Ok(())
+    }
+
+    /// Skip a `/* ... */` comment starting at `start`. Block comments nest, so that a block
+    /// of rules that already contains one can be commented out.
+    fn skip_block_comment(&mut self, start: usize) -> Result<(), ParseError> {
+        let mut depth = 0;
+        loop {
+            match (self.c, self.chars.peek()) {
+                (Some((_, '/')), Some((_, '*'))) => {
+                    depth += 1;
+                    self.chars.next();
+                }
+                (Some((_, '*')), Some((_, '/'))) => {
+                    depth -= 1;
+                    self.chars.next();
+                    if depth == 0 {
+                        self.c = self.chars.next();
+                        return Ok(());
+                    }
+                }
+                (None, _) => {
+                    return Err(ParseError::UnrecognizedEOF {
+                        loc: start,
+                        expected: vec!["*/".to_owned()],
+                    })
+                }
+                _ => {}
+            }
+            self.c = self.chars.next();
+        }
     }
 
     #[inline]
@@ -237,6 +339,10 @@ impl<'input> Lexer<'input> {
             Some(Ok((start, Token::ForAll, last + 1)))
         } else if &self.buf == "if" {
             Some(Ok((start, Token::If, last + 1)))
+        } else if &self.buf == "then" {
+            Some(Ok((start, Token::Then, last + 1)))
+        } else if &self.buf == "else" {
+            Some(Ok((start, Token::Else, last + 1)))
         } else if &self.buf == "and" {
             Some(Ok((start, Token::And, last + 1)))
         } else if &self.buf == "or" {
@@ -249,6 +355,8 @@ impl<'input> Lexer<'input> {
             Some(Ok((start, Token::Mod, last + 1)))
         } else if &self.buf == "rem" {
             Some(Ok((start, Token::Rem, last + 1)))
+        } else if &self.buf == "f" && matches!(self.c, Some((_, '"'))) {
+            self.scan_fstring(start)
         } else {
             Some(Ok((start, Token::Symbol(Symbol::new(&self.buf)), last + 1)))
         }
@@ -276,26 +384,10 @@ impl<'input> Lexer<'input> {
                         last = i;
                         break;
                     }
-                    '\\' => {
-                        self.c = self.chars.next();
-                        if let Some((_, char)) = self.c {
-                            let escaped_char = match char {
-                                'n' => '\n',
-                                'r' => '\r',
-                                't' => '\t',
-                                '0' => '\0',
-                                c => c,
-                            };
-                            self.buf.push(escaped_char);
-                        } else {
-                            return Some(Err(ParseError::InvalidTokenCharacter {
-                                token: self.buf.clone(),
-                                c: '\0',
-                                loc: i,
-                            }));
-                        }
-                        self.c = self.chars.next();
-                    }
+                    '\\' => match self.scan_escape(i) {
+                        Ok(escaped_char) => self.buf.push(escaped_char),
+                        Err(e) => return Some(Err(e)),
+                    },
                     _ => {
                         self.buf.push(char);
                         self.c = self.chars.next();
@@ -312,6 +404,155 @@ impl<'input> Lexer<'input> {
         Some(Ok((start, Token::String(self.buf.clone()), last + 1)))
     }
 
+    /// Scan an interpolated string, e.g., `f"tenant/{t.id}"`, starting at the `f` at `start`.
+    /// It is lexed as the tokens of the equivalent call to `format`, e.g.,
+    /// `format("tenant/{}", (t.id))`, with the locations of the interpolated expressions.
+    fn scan_fstring(&mut self, start: usize) -> Option<Spanned<Token, usize, ParseError>> {
+        let mut holes = vec![];
+        self.buf.clear();
+        self.c = self.chars.next();
+        let last = loop {
+            match self.c {
+                None => return Some(Err(invalid_character(&self.buf, '\0', start))),
+                Some((i, '\n')) => return Some(Err(invalid_character(&self.buf, '\n', i))),
+                Some((i, '"')) => {
+                    self.c = self.chars.next();
+                    break i;
+                }
+                Some((i, '\\')) => match self.scan_escape(i) {
+                    Ok(c) => {
+                        // Braces in the template of `format` are doubled.
+                        if c == '{' || c == '}' {
+                            self.buf.push(c);
+                        }
+                        self.buf.push(c);
+                    }
+                    Err(e) => return Some(Err(e)),
+                },
+                Some((i, c)) if c == '{' || c == '}' => {
+                    self.c = self.chars.next();
+                    match self.c {
+                        Some((_, next)) if next == c => {
+                            self.buf.push(c);
+                            self.buf.push(c);
+                            self.c = self.chars.next();
+                        }
+                        _ if c == '{' => match self.scan_hole(i + 1) {
+                            Ok(hole) => {
+                                holes.push(hole);
+                                self.buf.push_str("{}");
+                            }
+                            Err(e) => return Some(Err(e)),
+                        },
+                        _ => return Some(Err(invalid_character(&self.buf, c, i))),
+                    }
+                }
+                Some((_, c)) => self.push_char(c),
+            }
+        };
+
+        self.pending.extend(vec![
+            Ok((start, Token::Symbol(Symbol::new("format")), start + 1)),
+            Ok((start, Token::LP, start + 1)),
+            Ok((start, Token::String(self.buf.clone()), last + 1)),
+        ]);
+        for (hole_start, hole_end) in holes {
+            self.pending
+                .push_back(Ok((hole_start - 1, Token::Comma, hole_start)));
+            self.pending
+                .push_back(Ok((hole_start - 1, Token::LP, hole_start)));
+            let tokens = self.sub_lexer(hole_start, hole_end).collect::<Vec<_>>();
+            self.pending.extend(tokens);
+            self.pending
+                .push_back(Ok((hole_end, Token::RP, hole_end + 1)));
+        }
+        self.pending.push_back(Ok((last, Token::RP, last + 1)));
+        self.pending.pop_front()
+    }
+
+    /// Scan the expression in a `{...}` of an interpolated string, starting right after the `{`
+    /// at `start`, and return where it starts and ends. The expression may contain `}` in
+    /// brackets or strings.
+    fn scan_hole(&mut self, start: usize) -> Result<(usize, usize), ParseError> {
+        let mut depth = 0;
+        let mut in_string = false;
+        loop {
+            match self.c {
+                None => return Err(invalid_character(&self.buf, '\0', start)),
+                Some((i, '\n')) => return Err(invalid_character(&self.buf, '\n', i)),
+                Some((i, '}')) if depth == 0 && !in_string => {
+                    self.c = self.chars.next();
+                    return Ok((start, i));
+                }
+                Some((_, '"')) => in_string = !in_string,
+                // Skip the escaped character.
+                Some((_, '\\')) if in_string => self.c = self.chars.next(),
+                Some((_, '{')) | Some((_, '(')) | Some((_, '[')) if !in_string => depth += 1,
+                Some((_, '}')) | Some((_, ')')) | Some((_, ']')) if !in_string => depth -= 1,
+                _ => {}
+            }
+            self.c = self.chars.next();
+        }
+    }
+
+    /// A lexer for the input from `start` to `end`, with the same locations as this one.
+    fn sub_lexer(&self, start: usize, end: usize) -> Lexer<'input> {
+        let mut lexer = Lexer::new(self.input);
+        while matches!(lexer.c, Some((i, _)) if i < start) {
+            lexer.c = lexer.chars.next();
+        }
+        lexer.end = end;
+        lexer
+    }
+
+    /// Scan an escape sequence in a string, starting at the `\` at `i`. Bad escapes are
+    /// reported at the first character that makes them invalid.
+    fn scan_escape(&mut self, i: usize) -> Result<char, ParseError> {
+        self.c = self.chars.next();
+        let escaped_char = match self.c {
+            Some((_, 'n')) => '\n',
+            Some((_, 'r')) => '\r',
+            Some((_, 't')) => '\t',
+            Some((_, '0')) => '\0',
+            Some((_, '"')) => '"',
+            Some((_, '\\')) => '\\',
+            Some((_, 'u')) => return self.scan_unicode_escape(i),
+            Some((j, c)) => return Err(invalid_character(&self.buf, c, j)),
+            None => return Err(invalid_character(&self.buf, '\0', i)),
+        };
+        self.c = self.chars.next();
+        Ok(escaped_char)
+    }
+
+    /// Scan the `{...}` of a `\u{...}` escape starting at `i`: one to six hex digits, the code
+    /// point of a Unicode scalar value.
+    fn scan_unicode_escape(&mut self, i: usize) -> Result<char, ParseError> {
+        self.c = self.chars.next();
+        match self.c {
+            Some((_, '{')) => self.c = self.chars.next(),
+            Some((j, c)) => return Err(invalid_character(&self.buf, c, j)),
+            None => return Err(invalid_character(&self.buf, '\0', i)),
+        }
+        let mut digits = String::new();
+        let digits_start = self.c.map(|(j, _)| j);
+        loop {
+            match self.c {
+                Some((_, '}')) if !digits.is_empty() => break,
+                Some((_, c)) if c.is_ascii_hexdigit() && digits.len() < 6 => digits.push(c),
+                Some((j, c)) => return Err(invalid_character(&self.buf, c, j)),
+                None => return Err(invalid_character(&self.buf, '\0', i)),
+            }
+            self.c = self.chars.next();
+        }
+        self.c = self.chars.next();
+        let code = u32::from_str_radix(&digits, 16).expect("hex digits");
+        // Surrogates and code points past U+10FFFF are not characters.
+        std::char::from_u32(code).ok_or_else(|| {
+            let loc = digits_start.expect("at least one digit");
+            invalid_character(&self.buf, digits.chars().next().unwrap(), loc)
+        })
+    }
+
     #[inline]
     fn push_char(&mut self, c: char) {
         self.buf.push(c);
@@ -352,6 +593,19 @@ impl<'input> Lexer<'input> {
             last = self.match_digits(last);
         }
 
+        // A `d` suffix makes an exact decimal, e.g., `19.99d`.
+        if let Some((i, 'd')) = self.c {
+            self.c = self.chars.next();
+            return if let Ok(d) = Decimal::from_str(&self.buf) {
+                Some(Ok((start, Token::Decimal(d), i + 1)))
+            } else {
+                Some(Err(ParseError::IntegerOverflow {
+                    token: format!("{}d", self.buf),
+                    loc: start,
+                }))
+            };
+        }
+
         if let Some((i, char)) = self.c {
             match char {
                 'e' | 'E' => {
@@ -455,13 +709,33 @@ impl<'input> Lexer<'input> {
     }
 }
 
+impl<'input> Lexer<'input> {
+    /// Skip the rest of the current line, up to and including the next `;`. Used to resume
+    /// lexing after an error.
+    pub fn skip_line(&mut self) {
+        self.pending.clear();
+        while let Some((_, c)) = self.c {
+            self.c = self.chars.next();
+            if c == ';' {
+                break;
+            }
+        }
+    }
+}
+
 impl<'input> Iterator for Lexer<'input> {
     type Item = Spanned<Token, usize, ParseError>; // @TODO: Error, not String
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.skip_whitespace();
+        if let Some(token) = self.pending.pop_front() {
+            return Some(token);
+        }
+        if let Err(e) = self.skip_whitespace() {
+            return Some(Err(e));
+        }
         match self.c {
             None => None,
+            Some((i, _)) if i >= self.end => None,
             Some((i, char)) => match char {
                 x if x == '_' || (!x.is_ascii_punctuation() && !x.is_ascii_digit()) => {
                     self.scan_symbol(i, char)
@@ -488,6 +762,7 @@ impl<'input> Iterator for Lexer<'input> {
                 '*' => self.scan_1c_op(i, Token::Mul),
                 '/' => self.scan_1c_op(i, Token::Div),
                 ';' => self.scan_1c_op(i, Token::SemiColon),
+                '@' => self.scan_1c_op(i, Token::At),
                 _ => Some(Err(ParseError::InvalidTokenCharacter {
                     token: "".to_owned(),
                     c: char,
@@ -508,6 +783,52 @@ mod tests {
         assert_eq!(loc_to_pos(src, 6), (1, 0));
         assert_eq!(loc_to_pos(src, 13), (2, 0));
         assert_eq!(loc_to_pos(src, 18), (2, 5));
+
+        // Locations are byte offsets; columns are counted in characters.
+        let src = "\"é\" = 💯\nx";
+        assert_eq!(loc_to_pos(src, 7), (0, 6));
+        assert_eq!(loc_to_pos(src, 12), (1, 0));
+    }
+
+    #[test]
+    fn test_block_comments() {
+        let f = "foo /* a /* nested */ comment */ bar /* * / */\n/**/baz/ 2";
+        let mut lexer = Lexer::new(f);
+        assert!(matches!(lexer.next(), Some(Ok((0, Token::Symbol(_), 3)))));
+        assert!(matches!(lexer.next(), Some(Ok((33, Token::Symbol(_), 36)))));
+        assert!(matches!(lexer.next(), Some(Ok((51, Token::Symbol(_), 54)))));
+        assert!(matches!(lexer.next(), Some(Ok((54, Token::Div, 55)))));
+        assert!(matches!(lexer.next(), Some(Ok((_, Token::Integer(2), _)))));
+        assert!(matches!(lexer.next(), None));
+
+        let mut lexer = Lexer::new("foo /* a /* b */ c");
+        lexer.next();
+        assert!(matches!(
+            lexer.next(),
+            Some(Err(ParseError::UnrecognizedEOF { loc: 4, expected })) if expected == vec!["*/"]
+        ));
+    }
+
+    #[test]
+    fn test_unicode_escapes() {
+        let s = r#""caf\u{e9} \u{1F600}\t\r\\""#;
+        let mut lexer = Lexer::new(s);
+        assert!(
+            matches!(lexer.next(), Some(Ok((0, Token::String(s), 27))) if s == "café 😀\t\r\\")
+        );
+
+        let bad_escape = |s: &str| match Lexer::new(s).next() {
+            Some(Err(ParseError::InvalidTokenCharacter { token, c, loc })) => (token, c, loc),
+            t => panic!("expected an invalid character, got {:?}", t),
+        };
+        assert_eq!(bad_escape(r#""ab\q""#), ("ab".to_owned(), 'q', 4));
+        assert_eq!(bad_escape(r#""é\u00e9""#), ("é".to_owned(), '0', 5));
+        assert_eq!(bad_escape(r#""\u{}""#), ("".to_owned(), '}', 4));
+        assert_eq!(bad_escape(r#""\u{12g}""#), ("".to_owned(), 'g', 6));
+        assert_eq!(bad_escape(r#""\u{1234567}""#), ("".to_owned(), '7', 10));
+        assert_eq!(bad_escape(r#""\u{D800}""#), ("".to_owned(), 'D', 4));
+        assert_eq!(bad_escape(r#""\u{110000}""#), ("".to_owned(), '1', 4));
+        assert_eq!(bad_escape(r#""x\u{41"#), ("x".to_owned(), '\0', 2));
     }
 
     #[test]
@@ -541,6 +862,18 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_doc_comments() {
+        let f = "## Doc\r\n##  indented\nfoo ## not a doc\n#### header\nbar\n## Detached\n\nbaz";
+        let mut lexer = Lexer::new(f);
+        assert!(matches!(lexer.next(), Some(Ok((_, Token::Symbol(_), _)))));
+        assert_eq!(lexer.take_doc(), Some("Doc\n indented".to_owned()));
+        assert!(matches!(lexer.next(), Some(Ok((_, Token::Symbol(_), _)))));
+        assert_eq!(lexer.take_doc(), None);
+        assert!(matches!(lexer.next(), Some(Ok((_, Token::Symbol(_), _)))));
+        assert_eq!(lexer.take_doc(), None);
+    }
+
     #[test]
     fn test_escapes() {
         let s = r#"
@@ -713,5 +1046,65 @@ mod tests {
         let f = "1.1e-1";
         let mut lexer = Lexer::new(&f);
         assert!(matches!(lexer.next(), Some(Ok((_, Token::Float(f), _))) if f == 1.1e-1));
+
+        let f = "19.90d";
+        let mut lexer = Lexer::new(f);
+        assert!(matches!(
+            lexer.next(),
+            Some(Ok((0, Token::Decimal(d), 6))) if (d.coefficient(), d.scale()) == (1990, 2)
+        ));
+        assert!(lexer.next().is_none());
+
+        let f = "1.00000000000000000000000000001d";
+        let mut lexer = Lexer::new(f);
+        assert!(matches!(
+            lexer.next(),
+            Some(Err(ParseError::IntegerOverflow { .. }))
+        ));
+    }
+
+    #[test]
+    fn test_fstrings() {
+        let f = r#"f"a/{x.y}{{}}" + 1"#;
+        let mut lexer = Lexer::new(&f);
+        assert!(
+            matches!(lexer.next(), Some(Ok((0, Token::Symbol(format), 1))) if format == Symbol::new("format"))
+        );
+        assert!(matches!(lexer.next(), Some(Ok((0, Token::LP, 1)))));
+        assert!(
+            matches!(lexer.next(), Some(Ok((0, Token::String(template), 14))) if &template == "a/{}{{}}")
+        );
+        assert!(matches!(lexer.next(), Some(Ok((4, Token::Comma, 5)))));
+        assert!(matches!(lexer.next(), Some(Ok((4, Token::LP, 5)))));
+        assert!(
+            matches!(lexer.next(), Some(Ok((5, Token::Symbol(x), 6))) if x == Symbol::new("x"))
+        );
+        assert!(matches!(lexer.next(), Some(Ok((6, Token::Dot, 7)))));
+        assert!(
+            matches!(lexer.next(), Some(Ok((7, Token::Symbol(y), 8))) if y == Symbol::new("y"))
+        );
+        assert!(matches!(lexer.next(), Some(Ok((8, Token::RP, 9)))));
+        assert!(matches!(lexer.next(), Some(Ok((13, Token::RP, 14)))));
+        assert!(matches!(lexer.next(), Some(Ok((15, Token::Add, 16)))));
+        assert!(matches!(
+            lexer.next(),
+            Some(Ok((17, Token::Integer(1), 18)))
+        ));
+        assert!(lexer.next().is_none());
+
+        // Braces and quotes inside an interpolated expression.
+        let f = r#"f"{ {"a": "}"}.a }""#;
+        let tokens: Vec<_> = Lexer::new(f).map(|t| t.unwrap().1).collect();
+        assert_eq!(tokens.len(), 14);
+        assert!(matches!(&tokens[8], Token::String(s) if s == "}"));
+
+        assert!(matches!(
+            Lexer::new(r#"f"{x""#).next(),
+            Some(Err(ParseError::InvalidTokenCharacter { .. }))
+        ));
+        assert!(matches!(
+            Lexer::new(r#"f"}""#).next(),
+            Some(Err(ParseError::InvalidTokenCharacter { .. }))
+        ));
     }
 }
@@ -7,27 +7,36 @@ extern crate maplit;
 #[macro_use]
 pub mod macros;
 
+mod aggregates;
 mod bindings;
+pub mod call_graph;
 mod counter;
+mod datetime;
 mod debugger;
+pub mod docs;
 pub mod error;
 pub mod events;
 mod folder;
+pub mod formatter;
 pub mod formatting;
 mod inverter;
 pub mod kb;
 mod lexer;
 pub mod messages;
+mod methods;
+mod modules;
 mod numerics;
 pub mod parser;
 mod partial;
 pub mod polar;
 mod rewrites;
+mod rule_types;
 pub mod rules;
 mod runnable;
 mod sources;
+mod tabling;
 pub mod terms;
 pub mod traces;
 mod visitor;
 mod vm;
-mod warnings;
+pub mod warnings;
@@ -186,6 +186,8 @@ macro_rules! rule {
             name: sym!($name),
             params,
             body: term!(op!(And, $(term!($body)),+)),
+            doc: None,
+            annotations: vec![],
         }}
     };
     ($name:expr, [$($args:tt)*]) => {{
@@ -195,6 +197,8 @@ macro_rules! rule {
             name: sym!($name),
             params,
             body: term!(op!(And)),
+            doc: None,
+            annotations: vec![],
         }
     }};
 }
@@ -304,6 +308,11 @@ impl From<TermList> for TestHelper<Value> {
         Self(Value::List(other))
     }
 }
+impl From<Set> for TestHelper<Value> {
+    fn from(other: Set) -> Self {
+        Self(Value::Set(other))
+    }
+}
 impl From<Symbol> for TestHelper<Value> {
     fn from(other: Symbol) -> Self {
         Self(Value::Variable(other))
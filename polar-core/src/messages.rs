@@ -2,16 +2,61 @@ use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
 use std::sync::{Arc, Mutex};
 
+use super::lexer::loc_to_pos;
+use super::sources::Source;
+use super::warnings::LintCode;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum MessageKind {
     Print,
     Warning,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Severity {
+    Info,
+    Warning,
+}
+
+/// A position in a source. Both fields are zero-based.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Position {
+    pub row: usize,
+    pub column: usize,
+}
+
+/// The part of a source a message refers to, from `start` up to (but not including) `end`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourceRange {
+    pub filename: Option<String>,
+    pub start: Position,
+    pub end: Position,
+}
+
+impl SourceRange {
+    /// Return the range covering the characters from offset `left` up to `right` of `source`.
+    pub fn from_offsets(source: &Source, left: usize, right: usize) -> Self {
+        let position = |loc| {
+            let (row, column) = loc_to_pos(&source.src, loc);
+            Position { row, column }
+        };
+        Self {
+            filename: source.filename.clone(),
+            start: position(left),
+            end: position(right),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Message {
     pub kind: MessageKind,
     pub msg: String,
+    /// The lint that produced a warning.
+    pub code: Option<LintCode>,
+    pub severity: Option<Severity>,
+    /// Where the problem a warning is about was found.
+    pub range: Option<SourceRange>,
 }
 
 #[derive(Clone, Debug)]
@@ -36,7 +81,13 @@ impl MessageQueue {
 
     pub fn push(&self, kind: MessageKind, msg: String) {
         let mut messages = self.messages.lock().unwrap();
-        messages.push_back(Message { kind, msg });
+        messages.push_back(Message {
+            kind,
+            msg,
+            code: None,
+            severity: None,
+            range: None,
+        });
     }
 
     pub fn extend<T: IntoIterator<Item = Message>>(&self, iter: T) {
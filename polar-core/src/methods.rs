@@ -0,0 +1,122 @@
+//! Builtin methods on Polar strings, lists, sets and dictionaries, e.g.,
+//! `name.starts_with("a")`.
+//!
+//! They are evaluated by the VM instead of being looked up in the host, so they behave the
+//! same in every host language. Other methods are still looked up in the host.
+
+use crate::formatting::ToPolarString;
+use crate::numerics::Numeric;
+use crate::terms::{Call, Term, Value};
+
+/// Call the builtin method `call` on `object`. Return `None` if there is no such builtin
+/// method, or an error message if the arguments are wrong.
+pub fn call_method(object: &Term, call: &Call) -> Option<Result<Value, String>> {
+    if matches!(&call.kwargs, Some(kwargs) if !kwargs.is_empty()) {
+        return None;
+    }
+    let name = call.name.0.as_str();
+    let args = &call.args[..];
+    let result =
+        match (object.value(), name) {
+            (Value::String(s), "len") => arity(name, args, 0, 0)
+                .map(|_| Value::Number(Numeric::Integer(s.chars().count() as i64))),
+            (Value::String(s), "contains") => {
+                string_arg(name, args).map(|pat| Value::Boolean(s.contains(pat)))
+            }
+            (Value::String(s), "starts_with") => {
+                string_arg(name, args).map(|pat| Value::Boolean(s.starts_with(pat)))
+            }
+            (Value::String(s), "ends_with") => {
+                string_arg(name, args).map(|pat| Value::Boolean(s.ends_with(pat)))
+            }
+            (Value::String(s), "lower") => {
+                arity(name, args, 0, 0).map(|_| Value::String(s.to_lowercase()))
+            }
+            (Value::String(s), "upper") => {
+                arity(name, args, 0, 0).map(|_| Value::String(s.to_uppercase()))
+            }
+            (Value::String(s), "slice") => {
+                let chars = s.chars().collect::<Vec<_>>();
+                slice(name, args, chars.len())
+                    .map(|(start, end)| Value::String(chars[start..end].iter().collect()))
+            }
+            (Value::List(list), "len") => {
+                arity(name, args, 0, 0).map(|_| Value::Number(Numeric::Integer(list.len() as i64)))
+            }
+            (Value::List(list), "contains") => arity(name, args, 1, 1)
+                .map(|_| Value::Boolean(list.iter().any(|x| x.value() == args[0].value()))),
+            (Value::List(list), "slice") => slice(name, args, list.len())
+                .map(|(start, end)| Value::List(list[start..end].to_vec())),
+            (Value::Set(set), "len") => {
+                arity(name, args, 0, 0).map(|_| Value::Number(Numeric::Integer(set.len() as i64)))
+            }
+            (Value::Set(set), "contains") => {
+                arity(name, args, 1, 1).map(|_| Value::Boolean(set.contains(&args[0])))
+            }
+            (Value::Dictionary(dict), "len") => arity(name, args, 0, 0)
+                .map(|_| Value::Number(Numeric::Integer(dict.fields.len() as i64))),
+            (Value::Dictionary(dict), "keys") => arity(name, args, 0, 0).map(|_| {
+                Value::List(
+                    dict.fields
+                        .keys()
+                        .map(|k| object.clone_with_value(Value::String(k.0.clone())))
+                        .collect(),
+                )
+            }),
+            (Value::Dictionary(dict), "values") => arity(name, args, 0, 0)
+                .map(|_| Value::List(dict.fields.values().cloned().collect())),
+            _ => return None,
+        };
+    Some(result)
+}
+
+/// Check that there are between `min` and `max` arguments.
+fn arity(name: &str, args: &[Term], min: usize, max: usize) -> Result<(), String> {
+    if args.len() < min || args.len() > max {
+        let expected = if min == max {
+            min.to_string()
+        } else {
+            format!("{} to {}", min, max)
+        };
+        return Err(format!(
+            "{}() takes {} arguments, got {}",
+            name,
+            expected,
+            args.len()
+        ));
+    }
+    Ok(())
+}
+
+/// The single string argument of a method.
+fn string_arg<'a>(name: &str, args: &'a [Term]) -> Result<&'a str, String> {
+    arity(name, args, 1, 1)?;
+    match args[0].value() {
+        Value::String(s) => Ok(s),
+        _ => Err(format!(
+            "{}() expects a string, got {}",
+            name,
+            args[0].to_polar()
+        )),
+    }
+}
+
+/// The bounds of `slice(start)` or `slice(start, end)` for a sequence of length `len`.
+/// Negative indices count from the end, and indices out of range are clamped.
+fn slice(name: &str, args: &[Term], len: usize) -> Result<(usize, usize), String> {
+    arity(name, args, 1, 2)?;
+    let index = |arg: &Term| match arg.value() {
+        Value::Number(Numeric::Integer(i)) if *i < 0 => {
+            Ok(len.saturating_sub(i.unsigned_abs() as usize))
+        }
+        Value::Number(Numeric::Integer(i)) => Ok((*i as usize).min(len)),
+        _ => Err(format!(
+            "{}() expects integer indices, got {}",
+            name,
+            arg.to_polar()
+        )),
+    };
+    let start = index(&args[0])?;
+    let end = args.get(1).map_or(Ok(len), index)?;
+    Ok((start, end.max(start)))
+}
@@ -0,0 +1,421 @@
+//! Policy modules, e.g., `module billing;`.
+//!
+//! The rules defined in a source that starts with a module declaration belong to the module:
+//! `is_member` defined in module `billing` is the rule `billing::is_member`. Unqualified calls
+//! in the module are to global rules if the module does not define them but a global rule
+//! does, and to the module's own rules otherwise, whatever order the sources are loaded in.
+//! The rules of a module are private to it
+//! unless it exports them, e.g., `export is_member;`, so that other sources can call them as
+//! `billing::is_member(...)`.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use super::error::{ParseError, PolarError, PolarResult};
+use super::kb::KnowledgeBase;
+use super::messages::Message;
+use super::parser::Line;
+use super::rules::{GenericRule, Rule};
+use super::terms::*;
+use super::warnings::{goal_calls, warning, LintCode};
+
+#[derive(Clone, Debug, Default)]
+pub struct Module {
+    /// The rules that other sources may call, without the module prefix.
+    pub exports: HashSet<Symbol>,
+}
+
+/// The lines of a source, with the names of its module's rules qualified.
+pub struct ModuleSource {
+    pub lines: Vec<Line>,
+    /// The module the source belongs to, and the rules it exports.
+    pub module: Option<(Symbol, HashSet<Symbol>)>,
+}
+
+fn qualify(module: &Symbol, name: &Symbol) -> Symbol {
+    Symbol(format!("{}::{}", module.0, name.0))
+}
+
+/// Split a qualified rule name into its module and unqualified name.
+fn split(name: &Symbol) -> Option<(Symbol, Symbol)> {
+    let (module, name) = name.0.split_once("::")?;
+    Some((Symbol::new(module), Symbol::new(name)))
+}
+
+fn module_error(kb: &KnowledgeBase, term: &Term, msg: String) -> PolarError {
+    let source = term
+        .get_source_id()
+        .and_then(|id| kb.sources.get_source(id));
+    PolarError::from(ParseError::Module {
+        loc: term.offset(),
+        msg,
+    })
+    .set_context(source.as_ref(), None)
+}
+
+/// Rename the rules called in goal position in `term`.
+fn rename_calls<F>(term: &Term, rename: &mut F) -> PolarResult<Term>
+where
+    F: FnMut(&Term, &Symbol) -> PolarResult<Option<Symbol>>,
+{
+    match term.value() {
+        Value::Call(call) => Ok(match rename(term, &call.name)? {
+            Some(name) => term.clone_with_value(Value::Call(Call {
+                name,
+                ..call.clone()
+            })),
+            None => term.clone(),
+        }),
+        Value::Expression(Operation {
+            operator:
+                operator @ (Operator::And
+                | Operator::Or
+                | Operator::Not
+                | Operator::If
+                | Operator::ForAll),
+            args,
+        }) => {
+            let args = args
+                .iter()
+                .map(|arg| rename_calls(arg, rename))
+                .collect::<PolarResult<Vec<_>>>()?;
+            Ok(term.clone_with_value(Value::Expression(Operation {
+                operator: *operator,
+                args,
+            })))
+        }
+        // The second argument of an aggregate is a goal.
+        Value::Expression(Operation { operator, args }) if operator.is_aggregate() => {
+            let mut args = args.clone();
+            args[1] = rename_calls(&args[1], rename)?;
+            Ok(term.clone_with_value(Value::Expression(Operation {
+                operator: *operator,
+                args,
+            })))
+        }
+        _ => Ok(term.clone()),
+    }
+}
+
+/// Check that a qualified call from outside `module`'s module is to a rule it exports. Calls to
+/// modules that have not been loaded are left to the undefined rule lint.
+fn check_access(
+    kb: &KnowledgeBase,
+    module: Option<&Symbol>,
+    exports: &HashMap<&Symbol, &HashSet<Symbol>>,
+    term: &Term,
+    name: &Symbol,
+) -> PolarResult<()> {
+    let (callee_module, callee) = match split(name) {
+        Some(split) => split,
+        None => return Ok(()),
+    };
+    if module == Some(&callee_module) {
+        return Ok(());
+    }
+    match exports.get(&callee_module) {
+        Some(exported) if !exported.contains(&callee) => {
+            let msg = format!(
+                "Rule {} is private to module {}; export {} from it to call it here",
+                name, callee_module, callee
+            );
+            Err(module_error(kb, term, msg))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Qualify the names of the rules defined in, and called from, a source that declares a
+/// module, and check that the source only calls the rules other modules export.
+pub fn resolve_modules(kb: &KnowledgeBase, lines: Vec<Line>) -> PolarResult<ModuleSource> {
+    let mut module = None;
+    let mut exports = vec![];
+    for (i, line) in lines.iter().enumerate() {
+        match line {
+            Line::Module(term) if i == 0 => {
+                let name = term.value().as_symbol()?;
+                if name.is_namespaced_var() {
+                    let msg = format!("Module name {} cannot contain ::", name);
+                    return Err(module_error(kb, term, msg));
+                }
+                module = Some(name.clone());
+            }
+            Line::Module(name) => {
+                let msg = "A module declaration must be the first line of its source".to_owned();
+                return Err(module_error(kb, name, msg));
+            }
+            Line::Export(names) => exports.extend(names.iter().cloned()),
+            _ => {}
+        }
+    }
+
+    // The rules of each module that may be called from outside it.
+    let mut new_exports = HashSet::new();
+    let mut all_exports = kb
+        .modules
+        .iter()
+        .map(|(name, module)| (name, &module.exports))
+        .collect::<HashMap<_, _>>();
+
+    // The rules the module defines, in this source or in the ones already loaded.
+    let mut local = HashSet::new();
+    for line in &lines {
+        if let Line::Rule(rule) | Line::RuleType(rule) = line {
+            if let Some((rule_module, _)) = split(&rule.name) {
+                let msg = format!(
+                    "Rule {} can only be defined in module {}",
+                    rule.name, rule_module
+                );
+                return Err(module_error(kb, rule_term(rule), msg));
+            }
+            local.insert(rule.name.clone());
+        }
+    }
+    if let Some(module) = &module {
+        local.extend(
+            kb.rules
+                .keys()
+                .filter_map(split)
+                .filter(|(rule_module, _)| rule_module == module)
+                .map(|(_, name)| name),
+        );
+        for export in &exports {
+            let name = export.value().as_symbol().unwrap();
+            if !local.contains(name) {
+                let msg = format!("Module {} exports undefined rule {}", module, name);
+                return Err(module_error(kb, export, msg));
+            }
+            new_exports.insert(name.clone());
+        }
+    } else if let Some(export) = exports.first() {
+        let msg = "Only a module can export rules; declare one with `module <name>;`".to_owned();
+        return Err(module_error(kb, export, msg));
+    }
+    let module_exports = module.as_ref().map(|module| {
+        let mut exports = new_exports.clone();
+        if let Some(loaded) = kb.modules.get(module) {
+            exports.extend(loaded.exports.iter().cloned());
+        }
+        exports
+    });
+    if let (Some(module), Some(exports)) = (&module, &module_exports) {
+        all_exports.insert(module, exports);
+    }
+
+    let mut rename = |term: &Term, name: &Symbol| -> PolarResult<Option<Symbol>> {
+        check_access(kb, module.as_ref(), &all_exports, term, name)?;
+        match &module {
+            // Calls to rules that are not defined yet are assumed to be to the module's own,
+            // until `link_module_calls` finds otherwise.
+            Some(module)
+                if !name.is_namespaced_var()
+                    && (local.contains(name) || !kb.rules.contains_key(name)) =>
+            {
+                Ok(Some(qualify(module, name)))
+            }
+            _ => Ok(None),
+        }
+    };
+    let mut resolved = vec![];
+    for line in lines {
+        resolved.push(match line {
+            Line::Rule(rule) => Line::Rule(resolve_rule(rule, module.as_ref(), &mut rename)?),
+            Line::RuleType(rule) => {
+                Line::RuleType(resolve_rule(rule, module.as_ref(), &mut rename)?)
+            }
+            Line::Query(query) => Line::Query(rename_calls(&query, &mut rename)?),
+            line => line,
+        });
+    }
+
+    // Rules already loaded may call the rules of the module that it does not export.
+    if let Some(module) = &module {
+        for generic_rule in kb.rules.values() {
+            let caller_module = split(&generic_rule.name).map(|(caller, _)| caller);
+            for rule in generic_rule.rules() {
+                for call in goal_calls(&rule.body) {
+                    let name = &call.value().as_call().unwrap().name;
+                    if matches!(split(name), Some((callee, _)) if &callee == module) {
+                        check_access(kb, caller_module.as_ref(), &all_exports, &call, name)?;
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(ModuleSource {
+        lines: resolved,
+        module: module.map(|module| (module, new_exports)),
+    })
+}
+
+/// The rule an unqualified call to `name` from `module` is to: the module's own if it defines
+/// one, and otherwise the global rule if there is one.
+fn resolve_call(kb: &KnowledgeBase, module: &Symbol, name: &Symbol) -> Symbol {
+    let qualified = qualify(module, name);
+    let defined = |name: &Symbol| kb.rules.contains_key(name) || kb.rule_types.is_declared(name);
+    if defined(&qualified) || !defined(name) {
+        qualified
+    } else {
+        name.clone()
+    }
+}
+
+/// The unqualified name of a call from `module` to `name`, if it may be to either the module's
+/// own rule or a global rule.
+fn unqualified_call(module: &Symbol, name: &Symbol) -> Option<Symbol> {
+    match split(name) {
+        Some((callee_module, callee)) if &callee_module == module => Some(callee),
+        Some(_) => None,
+        None => Some(name.clone()),
+    }
+}
+
+/// Point the unqualified calls in the rules of every module at the rule `resolve_call` picks.
+/// A source can define a rule that a module loaded before it calls, so calls are linked again
+/// after every load, and end up the same whatever order the sources are loaded in.
+pub fn link_module_calls(kb: &mut KnowledgeBase) -> PolarResult<()> {
+    let mut relinked = vec![];
+    for (name, generic_rule) in &kb.rules {
+        let module = match split(name) {
+            Some((module, _)) => module,
+            None => continue,
+        };
+        let mut rename = |_: &Term, name: &Symbol| -> PolarResult<Option<Symbol>> {
+            Ok(unqualified_call(&module, name)
+                .map(|callee| resolve_call(kb, &module, &callee))
+                .filter(|callee| callee != name))
+        };
+        let rules = generic_rule.rules();
+        let mut changed = false;
+        let mut linked = vec![];
+        for rule in rules {
+            let body = rename_calls(&rule.body, &mut rename)?;
+            if body == rule.body {
+                linked.push(rule);
+            } else {
+                changed = true;
+                linked.push(Arc::new(Rule {
+                    body,
+                    ..rule.as_ref().clone()
+                }));
+            }
+        }
+        if changed {
+            relinked.push(GenericRule::new(name.clone(), linked));
+        }
+    }
+    for generic_rule in relinked {
+        kb.rules.insert(generic_rule.name.clone(), generic_rule);
+    }
+    Ok(())
+}
+
+/// Warn about calls in a module's rule to a rule that both the module and the global scope
+/// define.
+pub fn check_ambiguous_calls(rule: &Rule, kb: &KnowledgeBase) -> Vec<Message> {
+    let module = match split(&rule.name) {
+        Some((module, _)) => module,
+        None => return vec![],
+    };
+    goal_calls(&rule.body)
+        .iter()
+        .filter_map(|term| {
+            let name = &term.value().as_call().unwrap().name;
+            let callee = unqualified_call(&module, name)?;
+            if !kb.rules.contains_key(&callee) || &qualify(&module, &callee) != name {
+                return None;
+            }
+            let msg = format!(
+                "Call to {} in module {} is ambiguous: it calls {}, not the global rule {}",
+                callee, module, name, callee
+            );
+            Some(warning(kb, LintCode::AmbiguousCall, msg, Some(term)))
+        })
+        .collect()
+}
+
+fn resolve_rule<F>(rule: Rule, module: Option<&Symbol>, rename: &mut F) -> PolarResult<Rule>
+where
+    F: FnMut(&Term, &Symbol) -> PolarResult<Option<Symbol>>,
+{
+    let name = match module {
+        Some(module) => qualify(module, &rule.name),
+        None => rule.name,
+    };
+    let body = rename_calls(&rule.body, rename)?;
+    Ok(Rule { name, body, ..rule })
+}
+
+/// A term to point at in errors about a rule: its first parameter, or its body.
+fn rule_term(rule: &Rule) -> &Term {
+    rule.params
+        .first()
+        .map_or(&rule.body, |param| &param.parameter)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::formatting::ToPolarString;
+    use crate::polar::Polar;
+    use crate::terms::Symbol;
+
+    fn load_error(polar: &Polar, src: &str) -> String {
+        polar.load_str(src).expect_err("load error").to_string()
+    }
+
+    #[test]
+    fn test_module_rules() {
+        let polar = Polar::new();
+        polar
+            .load_str("module billing;\nexport has_plan;\nhas_plan(_x) if is_member(_x);")
+            .unwrap();
+        // A module can span several sources.
+        polar.load_str("module billing;\nis_member(_x);").unwrap();
+        let kb = polar.kb.read().unwrap();
+        let mut names = kb
+            .rules
+            .keys()
+            .map(|name| name.0.as_str())
+            .collect::<Vec<_>>();
+        names.sort_unstable();
+        assert_eq!(names, vec!["billing::has_plan", "billing::is_member"]);
+        let has_plan = kb.rules[&sym!("billing::has_plan")].rules();
+        assert_eq!(has_plan[0].body.to_polar(), "billing::is_member(_x)");
+    }
+
+    #[test]
+    fn test_module_errors() {
+        let polar = Polar::new();
+        assert_eq!(
+            load_error(&polar, "f(1);\nmodule billing;"),
+            "A module declaration must be the first line of its source at line 2, column 8"
+        );
+        assert_eq!(
+            load_error(&polar, "export f;\nf(1);"),
+            "Only a module can export rules; declare one with `module <name>;` at line 1, \
+             column 8"
+        );
+        assert_eq!(
+            load_error(&polar, "module billing;\nexport g;\nf(1);"),
+            "Module billing exports undefined rule g at line 2, column 8"
+        );
+        assert_eq!(
+            load_error(&polar, "billing::f(1);"),
+            "Rule billing::f can only be defined in module billing at line 1, column 12"
+        );
+        assert_eq!(
+            load_error(&polar, "module billing, payments;"),
+            "did not expect to find the token 'payments', expected `;` at line 1, column 17"
+        );
+
+        // Rules loaded before a module may not call its private rules either.
+        polar.load_str("g(x) if billing::f(x);").unwrap();
+        assert_eq!(
+            load_error(&polar, "module billing;\nf(1);"),
+            "Rule billing::f is private to module billing; export f from it to call it here at \
+             line 1, column 9"
+        );
+        polar.load_str("module billing;\nexport f;\nf(1);").unwrap();
+    }
+}
@@ -1,11 +1,13 @@
 use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 
 use std::cmp::Ordering;
+use std::convert::TryFrom;
 use std::fmt;
 use std::hash::{Hash, Hasher};
 use std::mem::discriminant;
 use std::num::FpCategory;
-use std::ops::{Add, Div, Mul, Rem, Sub};
+use std::ops::{Add, Div, Mul, Neg, Rem, Sub};
+use std::str::FromStr;
 
 #[derive(Copy, Clone, Debug, Deserialize, Serialize)]
 pub enum Numeric {
@@ -16,6 +18,8 @@ pub enum Numeric {
         deserialize_with = "deserialize_float"
     )]
     Float(f64),
+
+    Decimal(Decimal),
 }
 
 /// Since JSON does not support ±∞ or NaN (RFC 8259 §6),
@@ -86,6 +90,305 @@ where
     deserializer.deserialize_any(FloatVisitor)
 }
 
+/// The most digits a decimal may have after its decimal point.
+pub const MAX_DECIMAL_SCALE: u32 = 28;
+
+/// Decimal coefficients have at most 38 digits, so they always fit in an `i128`
+/// and can always be negated.
+const MAX_DECIMAL_COEFFICIENT: i128 = 10i128.pow(38) - 1;
+
+/// An exact decimal number `coefficient × 10^-scale`, written `1.50d` in a policy.
+///
+/// Decimals keep the scale they were written with, so `1.50d == 1.5d` but the former
+/// still displays as `1.50`. Arithmetic on decimals and integers is exact; operations
+/// whose result does not fit return `None`, like integer overflow.
+#[derive(Copy, Clone, Debug)]
+pub struct Decimal {
+    coefficient: i128,
+    scale: u32,
+}
+
+impl Decimal {
+    /// Make a decimal, or `None` if the coefficient or scale are out of range.
+    pub fn new(coefficient: i128, scale: u32) -> Option<Self> {
+        if scale <= MAX_DECIMAL_SCALE && coefficient.abs() <= MAX_DECIMAL_COEFFICIENT {
+            Some(Self { coefficient, scale })
+        } else {
+            None
+        }
+    }
+
+    /// Like `new`, but first drop trailing zeros that push the scale out of range.
+    fn reduce(mut coefficient: i128, mut scale: u32) -> Option<Self> {
+        while scale > MAX_DECIMAL_SCALE && coefficient % 10 == 0 {
+            coefficient /= 10;
+            scale -= 1;
+        }
+        Self::new(coefficient, scale)
+    }
+
+    pub fn coefficient(self) -> i128 {
+        self.coefficient
+    }
+
+    pub fn scale(self) -> u32 {
+        self.scale
+    }
+
+    /// The coefficient and scale without trailing zeros, so that
+    /// equal decimals have equal normal forms.
+    fn normalized(self) -> (i128, u32) {
+        let (mut coefficient, mut scale) = (self.coefficient, self.scale);
+        while scale > 0 && coefficient % 10 == 0 {
+            coefficient /= 10;
+            scale -= 1;
+        }
+        (coefficient, scale)
+    }
+
+    /// The coefficient of this decimal at a scale no smaller than its own.
+    fn rescale(self, scale: u32) -> Option<i128> {
+        10i128
+            .checked_pow(scale - self.scale)
+            .and_then(|factor| self.coefficient.checked_mul(factor))
+    }
+
+    /// The nearest float to this decimal.
+    pub fn to_f64(self) -> f64 {
+        self.to_string().parse().unwrap_or(f64::NAN)
+    }
+
+    /// This decimal as a float, if the conversion is exact.
+    fn exact_f64(self) -> Option<f64> {
+        let (coefficient, scale) = self.normalized();
+        // c × 10^-s = (c / 5^s) × 2^-s, which is a float iff 5^s divides c and
+        // the odd part of the quotient fits in the 53 bits of a float's mantissa.
+        let power = 5i128.pow(scale);
+        if coefficient % power != 0 {
+            return None;
+        }
+        let quotient = coefficient / power;
+        let odd = quotient >> quotient.trailing_zeros().min(127);
+        if odd.unsigned_abs() <= MOST_POSITIVE_EXACT_FLOAT as u128 {
+            Some(quotient as f64 / 2f64.powi(scale as i32))
+        } else {
+            None
+        }
+    }
+
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        let scale = self.scale.max(other.scale);
+        let sum = self.rescale(scale)?.checked_add(other.rescale(scale)?)?;
+        Self::new(sum, scale)
+    }
+
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        self.checked_add(-other)
+    }
+
+    pub fn checked_mul(self, other: Self) -> Option<Self> {
+        let product = self.coefficient.checked_mul(other.coefficient)?;
+        Self::reduce(product, self.scale + other.scale)
+    }
+
+    /// Divide to as many places as fit, at most `MAX_DECIMAL_SCALE`, rounding half to even.
+    /// Trailing zeros beyond the scale of the dividend are dropped, so `10.00d / 4 = 2.50d`.
+    pub fn checked_div(self, other: Self) -> Option<Self> {
+        if other.coefficient == 0 {
+            return None;
+        }
+        let negative = (self.coefficient < 0) != (other.coefficient < 0);
+        let divisor = other.coefficient.unsigned_abs();
+        let mut quotient = self.coefficient.unsigned_abs() / divisor;
+        let mut remainder = self.coefficient.unsigned_abs() % divisor;
+
+        // The next digit of the quotient, without overflowing `remainder * 10`.
+        let next_digit = |remainder: &mut u128| {
+            let (mut digit, ten_times) = (0, *remainder);
+            *remainder = 0;
+            for _ in 0..10 {
+                *remainder += ten_times;
+                if *remainder >= divisor {
+                    *remainder -= divisor;
+                    digit += 1;
+                }
+            }
+            digit
+        };
+
+        // `quotient` is at scale `self.scale - other.scale`; it must reach scale 0.
+        let mut scale = self.scale as i64 - other.scale as i64;
+        while scale < MAX_DECIMAL_SCALE as i64 {
+            let mut next = remainder;
+            let digit = next_digit(&mut next);
+            match quotient.checked_mul(10).map(|q| q + digit) {
+                Some(q) if q <= MAX_DECIMAL_COEFFICIENT as u128 => {
+                    quotient = q;
+                    remainder = next;
+                    scale += 1;
+                }
+                _ if scale < 0 => return None,
+                _ => break,
+            }
+        }
+
+        let digit = next_digit(&mut remainder);
+        if digit > 5 || (digit == 5 && (remainder > 0 || quotient % 2 == 1)) {
+            quotient += 1;
+        }
+        let mut coefficient = quotient as i128;
+        let mut scale = scale as u32;
+        while scale > self.scale && coefficient % 10 == 0 {
+            coefficient /= 10;
+            scale -= 1;
+        }
+        Self::new(if negative { -coefficient } else { coefficient }, scale)
+    }
+
+    pub fn checked_rem(self, other: Self) -> Option<Self> {
+        let scale = self.scale.max(other.scale);
+        let remainder = self.rescale(scale)?.checked_rem(other.rescale(scale)?)?;
+        Self::new(remainder, scale)
+    }
+
+    pub fn checked_modulo(self, modulus: Self) -> Option<Self> {
+        let scale = self.scale.max(modulus.scale);
+        let modulus = modulus.rescale(scale)?;
+        let remainder = self.rescale(scale)?.checked_rem(modulus)?;
+        Self::new(remainder.checked_add(modulus)? % modulus, scale)
+    }
+}
+
+impl Neg for Decimal {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self {
+            coefficient: -self.coefficient,
+            scale: self.scale,
+        }
+    }
+}
+
+impl From<i64> for Decimal {
+    fn from(other: i64) -> Self {
+        Self {
+            coefficient: other.into(),
+            scale: 0,
+        }
+    }
+}
+
+/// Parse a plain decimal like `-12.50`, with no exponent or suffix.
+impl FromStr for Decimal {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (negative, digits) = match s.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, s),
+        };
+        let (integral, fraction) = match digits.find('.') {
+            Some(i) => (&digits[..i], &digits[i + 1..]),
+            None => (digits, ""),
+        };
+        let all_digits = |s: &str| s.chars().all(|c| c.is_ascii_digit());
+        if integral.is_empty() || !all_digits(integral) || !all_digits(fraction) {
+            return Err(format!("invalid decimal {:?}", s));
+        }
+        let mut coefficient = 0i128;
+        for c in integral.chars().chain(fraction.chars()) {
+            coefficient = coefficient
+                .checked_mul(10)
+                .and_then(|x| x.checked_add((c as u8 - b'0').into()))
+                .ok_or_else(|| format!("decimal {:?} has too many digits", s))?;
+        }
+        let coefficient = if negative { -coefficient } else { coefficient };
+        Self::new(coefficient, fraction.len() as u32)
+            .ok_or_else(|| format!("decimal {:?} has too many digits", s))
+    }
+}
+
+/// Decimals are encoded as strings, so that they cross the FFI without losing precision.
+impl Serialize for Decimal {
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        s.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for Decimal {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
+
+/// Compare two decimals exactly.
+fn cmp_decimals(left: Decimal, right: Decimal) -> Ordering {
+    let scale = left.scale.max(right.scale);
+    match (left.rescale(scale), right.rescale(scale)) {
+        (Some(l), Some(r)) => l.cmp(&r),
+        // Only the side with the smaller scale is scaled up, so if that overflows
+        // its magnitude is greater than the other side's.
+        (None, _) => left.coefficient.cmp(&0),
+        (_, None) => 0.cmp(&right.coefficient),
+    }
+}
+
+/// Compare a decimal with a float exactly.
+fn cmp_decimal_float(d: Decimal, f: f64) -> Option<Ordering> {
+    if f.is_nan() {
+        return None;
+    } else if f.is_infinite() {
+        return Some(if f > 0.0 {
+            Ordering::Less
+        } else {
+            Ordering::Greater
+        });
+    }
+
+    // Decompose the float into `mantissa × 2^exponent`.
+    let bits = f.to_bits();
+    let biased = ((bits >> 52) & 0x7ff) as i64;
+    let fraction = (bits & 0xf_ffff_ffff_ffff) as i128;
+    let mantissa = if biased == 0 {
+        fraction << 1
+    } else {
+        fraction | 0x10_0000_0000_0000
+    };
+    let mantissa = if bits >> 63 == 0 { mantissa } else { -mantissa };
+    let exponent = biased - 1075;
+
+    // c × 10^-s ≶ m × 2^e  ⟺  c ≶ m × 5^s × 2^(e + s). The first factor is less than
+    // 2^119, and |c| < 10^38 < 2^127; check the shift against those bounds first.
+    let significant_bits = |x: i128| 128 - x.unsigned_abs().leading_zeros() as i64;
+    let m = mantissa * 5i128.pow(d.scale);
+    let c = d.coefficient;
+    let shift = exponent + d.scale as i64;
+    Some(if shift >= 0 {
+        if m == 0 {
+            c.cmp(&0)
+        } else if significant_bits(m) + shift >= 127 {
+            0.cmp(&m)
+        } else {
+            c.cmp(&(m << shift))
+        }
+    } else if c == 0 {
+        0.cmp(&m)
+    } else if significant_bits(c) - shift >= 127 {
+        c.cmp(&0)
+    } else {
+        (c << -shift).cmp(&m)
+    })
+}
+
 impl Add for Numeric {
     type Output = Option<Self>;
 
@@ -95,6 +398,13 @@ impl Add for Numeric {
             (Numeric::Integer(a), Numeric::Float(b)) => Some(Numeric::Float(a as f64 + b)),
             (Numeric::Float(a), Numeric::Integer(b)) => Some(Numeric::Float(a + b as f64)),
             (Numeric::Float(a), Numeric::Float(b)) => Some(Numeric::Float(a + b)),
+            (Numeric::Float(_), _) | (_, Numeric::Float(_)) => {
+                Some(Numeric::Float(self.to_f64() + other.to_f64()))
+            }
+            _ => self
+                .to_decimal()?
+                .checked_add(other.to_decimal()?)
+                .map(Numeric::Decimal),
         }
     }
 }
@@ -108,6 +418,13 @@ impl Sub for Numeric {
             (Numeric::Integer(a), Numeric::Float(b)) => Some(Numeric::Float(a as f64 - b)),
             (Numeric::Float(a), Numeric::Integer(b)) => Some(Numeric::Float(a - b as f64)),
             (Numeric::Float(a), Numeric::Float(b)) => Some(Numeric::Float(a - b)),
+            (Numeric::Float(_), _) | (_, Numeric::Float(_)) => {
+                Some(Numeric::Float(self.to_f64() - other.to_f64()))
+            }
+            _ => self
+                .to_decimal()?
+                .checked_sub(other.to_decimal()?)
+                .map(Numeric::Decimal),
         }
     }
 }
@@ -125,6 +442,31 @@ impl Numeric {
             (Numeric::Integer(a), Numeric::Float(b)) => Some(Numeric::Float(modulo(a as f64, b))),
             (Numeric::Float(a), Numeric::Integer(b)) => Some(Numeric::Float(modulo(a, b as f64))),
             (Numeric::Float(a), Numeric::Float(b)) => Some(Numeric::Float(modulo(a, b))),
+            (Numeric::Float(_), _) | (_, Numeric::Float(_)) => {
+                Some(Numeric::Float(modulo(self.to_f64(), modulus.to_f64())))
+            }
+            _ => self
+                .to_decimal()?
+                .checked_modulo(modulus.to_decimal()?)
+                .map(Numeric::Decimal),
+        }
+    }
+
+    /// This number as a float, possibly losing precision.
+    pub fn to_f64(self) -> f64 {
+        match self {
+            Numeric::Integer(i) => i as f64,
+            Numeric::Float(f) => f,
+            Numeric::Decimal(d) => d.to_f64(),
+        }
+    }
+
+    /// This number as a decimal, unless it is a float.
+    fn to_decimal(self) -> Option<Decimal> {
+        match self {
+            Numeric::Integer(i) => Some(i.into()),
+            Numeric::Float(_) => None,
+            Numeric::Decimal(d) => Some(d),
         }
     }
 }
@@ -138,6 +480,13 @@ impl Rem for Numeric {
             (Numeric::Integer(a), Numeric::Float(b)) => Some(Numeric::Float((a as f64) % b)),
             (Numeric::Float(a), Numeric::Integer(b)) => Some(Numeric::Float(a % (b as f64))),
             (Numeric::Float(a), Numeric::Float(b)) => Some(Numeric::Float(a % b)),
+            (Numeric::Float(_), _) | (_, Numeric::Float(_)) => {
+                Some(Numeric::Float(self.to_f64() % other.to_f64()))
+            }
+            _ => self
+                .to_decimal()?
+                .checked_rem(other.to_decimal()?)
+                .map(Numeric::Decimal),
         }
     }
 }
@@ -151,6 +500,13 @@ impl Mul for Numeric {
             (Numeric::Integer(a), Numeric::Float(b)) => Some(Numeric::Float(a as f64 * b)),
             (Numeric::Float(a), Numeric::Integer(b)) => Some(Numeric::Float(a * b as f64)),
             (Numeric::Float(a), Numeric::Float(b)) => Some(Numeric::Float(a * b)),
+            (Numeric::Float(_), _) | (_, Numeric::Float(_)) => {
+                Some(Numeric::Float(self.to_f64() * other.to_f64()))
+            }
+            _ => self
+                .to_decimal()?
+                .checked_mul(other.to_decimal()?)
+                .map(Numeric::Decimal),
         }
     }
 }
@@ -164,6 +520,13 @@ impl Div for Numeric {
             (Numeric::Integer(a), Numeric::Float(b)) => Some(Numeric::Float(a as f64 / b)),
             (Numeric::Float(a), Numeric::Integer(b)) => Some(Numeric::Float(a / b as f64)),
             (Numeric::Float(a), Numeric::Float(b)) => Some(Numeric::Float(a / b)),
+            (Numeric::Float(_), _) | (_, Numeric::Float(_)) => {
+                Some(Numeric::Float(self.to_f64() / other.to_f64()))
+            }
+            _ => self
+                .to_decimal()?
+                .checked_div(other.to_decimal()?)
+                .map(Numeric::Decimal),
         }
     }
 }
@@ -224,6 +587,24 @@ impl Hash for Numeric {
                     }
                 }
             },
+            Numeric::Decimal(d) => {
+                let (coefficient, scale) = d.normalized();
+                if let (0, Ok(i)) = (scale, i64::try_from(coefficient)) {
+                    // Hash integral decimals the same as numerically equal integers.
+                    discriminant(&Numeric::Integer(0)).hash(state);
+                    i as u64
+                } else if let Some(f) = d.exact_f64() {
+                    // And decimals that are exactly floats the same as those floats.
+                    // Integral floats in the range of an i64 were handled above.
+                    discriminant(&Numeric::Float(0.0)).hash(state);
+                    f.to_bits()
+                } else {
+                    // No other number is equal to this one.
+                    discriminant(self).hash(state);
+                    scale.hash(state);
+                    coefficient as u64 ^ (coefficient >> 64) as u64
+                }
+            }
         }
         .hash(state)
     }
@@ -256,6 +637,11 @@ impl PartialOrd for Numeric {
             (Self::Integer(i), Self::Float(f)) => partial_cmp(i, f),
             (Self::Float(f), Self::Integer(i)) => partial_cmp(i, f).map(Ordering::reverse),
             (Self::Float(left), Self::Float(right)) => left.partial_cmp(&right),
+            (Self::Decimal(d), Self::Float(f)) => cmp_decimal_float(d, f),
+            (Self::Float(f), Self::Decimal(d)) => cmp_decimal_float(d, f).map(Ordering::reverse),
+            (Self::Decimal(left), Self::Decimal(right)) => Some(cmp_decimals(left, right)),
+            (Self::Decimal(d), Self::Integer(i)) => Some(cmp_decimals(d, i.into())),
+            (Self::Integer(i), Self::Decimal(d)) => Some(cmp_decimals(i.into(), d)),
         }
     }
 }
@@ -441,6 +827,117 @@ mod tests {
         );
     }
 
+    fn dec(s: &str) -> Numeric {
+        Numeric::Decimal(s.parse().unwrap())
+    }
+
+    #[test]
+    fn decimal_arithmetic() {
+        assert_eq!((dec("0.1") + dec("0.2")).unwrap(), dec("0.3"));
+        assert_eq!((dec("0.1") + dec("0.2")).unwrap().to_string(), "0.3");
+        assert_eq!(
+            (dec("19.99") * Numeric::Integer(3)).unwrap().to_string(),
+            "59.97"
+        );
+        assert_eq!((dec("10.00") - dec("0.005")).unwrap().to_string(), "9.995");
+        assert_eq!(
+            (dec("10.00") / Numeric::Integer(4)).unwrap().to_string(),
+            "2.50"
+        );
+        assert_eq!((dec("1") / dec("8")).unwrap().to_string(), "0.125");
+        assert_eq!(
+            (dec("1") / Numeric::Integer(3)).unwrap().to_string(),
+            "0.3333333333333333333333333333"
+        );
+        assert_eq!(
+            (dec("2") / Numeric::Integer(3)).unwrap().to_string(),
+            "0.6666666666666666666666666667"
+        );
+        assert_eq!((dec("-7.5") / dec("0.25")).unwrap().to_string(), "-30.0");
+        assert_eq!(
+            (dec("5.5") % Numeric::Integer(2)).unwrap().to_string(),
+            "1.5"
+        );
+        assert_eq!(
+            dec("-5.5").modulo(Numeric::Integer(2)).unwrap().to_string(),
+            "0.5"
+        );
+        assert!(matches!(dec("0.5") + Numeric::Float(0.25), Some(Numeric::Float(f)) if f == 0.75));
+
+        // Out of range results are errors, like integer overflow.
+        assert!((dec("1") / dec("0")).is_none());
+        assert!((dec("1") % dec("0.0")).is_none());
+        let big = dec("99999999999999999999999999999999999999");
+        assert!((big + dec("1")).is_none());
+        assert!((big * dec("10")).is_none());
+        assert!((dec("0.000000000000001") * dec("0.000000000000001")).is_none());
+        assert_eq!(
+            (dec("0.000000000000010") * dec("0.000000000000010"))
+                .unwrap()
+                .to_string(),
+            "0.0000000000000000000000000001"
+        );
+    }
+
+    #[test]
+    #[allow(clippy::neg_cmp_op_on_partial_ord)]
+    fn decimal_comparison() {
+        assert!(dec("1.50") == dec("1.5"));
+        assert!(dec("-1.50") < dec("-1.499"));
+        assert!(
+            dec("99999999999999999999999999999999999999") > dec("0.0000000000000000000000000001")
+        );
+        assert!(
+            dec("-99999999999999999999999999999999999999") < dec("0.0000000000000000000000000001")
+        );
+
+        assert!(dec("2.00") == Numeric::Integer(2));
+        assert!(dec("2.01") > Numeric::Integer(2));
+        assert!(Numeric::Integer(i64::MIN) < dec("-9223372036854775807.5"));
+        assert!(Numeric::Integer(i64::MAX) > dec("9223372036854775806.5"));
+
+        // Comparison with floats is exact.
+        assert!(dec("0.5") == Numeric::Float(0.5));
+        assert!(dec("0.1") != Numeric::Float(0.1));
+        assert!(dec("0.1") < Numeric::Float(0.1));
+        // 0.1 is 0.1000000000000000055511151231257827... as a float.
+        assert!(dec("0.1000000000000000055511151232") > Numeric::Float(0.1));
+        assert!(dec("0.1000000000000000055511151231") < Numeric::Float(0.1));
+        assert!(dec("1000000000000000019884624838656") == Numeric::Float(1e30));
+        assert!(dec("-0.0") == Numeric::Float(-0.0));
+        assert!(dec("0") > Numeric::Float(-f64::MIN_POSITIVE));
+        assert!(dec("0.0000000000000000000000000001") > Numeric::Float(f64::MIN_POSITIVE));
+        assert!(dec("99999999999999999999999999999999999999") < Numeric::Float(1e38));
+        assert!(dec("-99999999999999999999999999999999999999") > Numeric::Float(-1e300));
+        assert!(dec("1") < Numeric::Float(f64::INFINITY));
+        assert!(dec("1") > Numeric::Float(f64::NEG_INFINITY));
+        assert!(dec("1") != Numeric::Float(f64::NAN));
+        assert!(!(dec("1") < Numeric::Float(f64::NAN)));
+        assert!(!(dec("1") > Numeric::Float(f64::NAN)));
+    }
+
+    #[test]
+    fn decimal_hash() {
+        assert_eq!(hash(&dec("1.50")), hash(&dec("1.5")));
+        assert_ne!(hash(&dec("1.50")), hash(&dec("1.51")));
+        assert_eq!(hash(&dec("2.00")), hash(&Numeric::Integer(2)));
+        assert_eq!(hash(&dec("-0.0")), hash(&Numeric::Float(0.0)));
+        assert_eq!(hash(&dec("0.375")), hash(&Numeric::Float(0.375)));
+        assert_eq!(
+            hash(&dec("-9223372036854775808")),
+            hash(&Numeric::Integer(i64::MIN))
+        );
+        assert_eq!(
+            hash(&dec("9223372036854775808")),
+            hash(&Numeric::Float(MOST_POSITIVE_I64_FLOAT))
+        );
+        assert_eq!(
+            hash(&dec("1000000000000000019884624838656")),
+            hash(&Numeric::Float(1e30))
+        );
+        assert_ne!(hash(&dec("0.1")), hash(&Numeric::Float(0.1)));
+    }
+
     #[test]
     fn json_serialization() {
         assert_eq!(to_json(&Numeric::Integer(0)).unwrap(), r#"{"Integer":0}"#);
@@ -475,6 +972,8 @@ mod tests {
             to_json(&Numeric::Float(f64::NAN)).unwrap(),
             r#"{"Float":"NaN"}"#
         );
+        assert_eq!(to_json(&dec("1.50")).unwrap(), r#"{"Decimal":"1.50"}"#);
+        assert_eq!(to_json(&dec("-0.001")).unwrap(), r#"{"Decimal":"-0.001"}"#);
     }
 
     #[test]
@@ -534,5 +1033,13 @@ mod tests {
             Numeric::Float(f) => f.is_nan(),
             _ => panic!("expected a float"),
         });
+
+        // Decimals.
+        assert!(matches!(
+            from_json::<Numeric>(r#"{"Decimal":"1.50"}"#).unwrap(),
+            Numeric::Decimal(d) if (d.coefficient(), d.scale()) == (150, 2)
+        ));
+        assert!(from_json::<Numeric>(r#"{"Decimal":"1e5"}"#).is_err());
+        assert!(from_json::<Numeric>(r#"{"Decimal":1.5}"#).is_err());
     }
 }
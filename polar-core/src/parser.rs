@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use crate::lexer::Token;
 use lalrpop_util::{lalrpop_mod, ParseError};
 
@@ -8,12 +10,171 @@ pub enum ValueOrLogical {
     Either(Term),
 }
 
+/// Build a call from its positional arguments and their locations. A call to `findall`,
+/// `count`, `sum`, `min` or `max` with three arguments is an aggregate, e.g.,
+/// `count(x, f(x), n)`, whose second argument is a goal. A call to `regex_match` with two or
+/// three arguments is a regular expression match, and calls to `union`, `intersection` and
+/// `difference` with three arguments or `subset` with two are set operations. Aggregates,
+/// matches and set operations are logical expressions. A call to `format` is a string value,
+/// e.g., `format("{}/{}", a, b)`, whose format arguments are kept in a list, and `set()` is the
+/// empty set. Calls to `timestamp` or `duration` with one argument and `now()` are time values.
+/// All other arguments must be values, and other calls may be either values or logical
+/// expressions.
+pub(crate) fn predicate_call(
+    src_id: u64,
+    start: usize,
+    end: usize,
+    name: Symbol,
+    args: Vec<(usize, ValueOrLogical)>,
+    kwargs: Option<BTreeMap<Symbol, Term>>,
+) -> Result<ValueOrLogical, error::ParseError> {
+    let builtin = builtin_operator(&name, args.len()).filter(|_| kwargs.is_none());
+    let aggregate = matches!(builtin, Some(operator) if operator.is_aggregate());
+    let args = args
+        .into_iter()
+        .enumerate()
+        .map(|(i, (loc, arg))| match arg {
+            ValueOrLogical::Logical(term) if !(aggregate && i == 1) => {
+                Err(error::ParseError::WrongValueType {
+                    loc,
+                    term,
+                    expected: "value".to_owned(),
+                })
+            }
+            ValueOrLogical::Value(term)
+            | ValueOrLogical::Logical(term)
+            | ValueOrLogical::Either(term) => Ok(term),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let term = |value| Term::new_from_parser(src_id, start, end, value);
+    if name.0 == "set" && args.is_empty() && kwargs.is_none() {
+        // The empty set, since `{}` is an empty dictionary.
+        return Ok(ValueOrLogical::Value(term(Value::Set(Set::default()))));
+    }
+    Ok(match builtin {
+        Some(Operator::Format) => {
+            let mut args = args.into_iter();
+            let template = args.next().unwrap();
+            let values = Term::new_temporary(Value::List(args.collect()));
+            ValueOrLogical::Value(term(Value::Expression(Operation {
+                operator: Operator::Format,
+                args: vec![template, values],
+            })))
+        }
+        Some(operator @ Operator::Timestamp)
+        | Some(operator @ Operator::Duration)
+        | Some(operator @ Operator::Now) => {
+            ValueOrLogical::Value(term(Value::Expression(Operation { operator, args })))
+        }
+        Some(operator) => {
+            ValueOrLogical::Logical(term(Value::Expression(Operation { operator, args })))
+        }
+        None => ValueOrLogical::Either(term(Value::Call(Call { name, args, kwargs }))),
+    })
+}
+
+/// The builtin that a call to `name` with `arity` positional arguments is, if any. See
+/// `predicate_call`.
+fn builtin_operator(name: &Symbol, arity: usize) -> Option<Operator> {
+    match (name.0.as_str(), arity) {
+        ("findall", 3) => Some(Operator::FindAll),
+        ("count", 3) => Some(Operator::Count),
+        ("sum", 3) => Some(Operator::Sum),
+        ("min", 3) => Some(Operator::Min),
+        ("max", 3) => Some(Operator::Max),
+        ("regex_match", 2) | ("regex_match", 3) => Some(Operator::RegexMatch),
+        ("format", n) if n > 0 => Some(Operator::Format),
+        ("union", 3) => Some(Operator::Union),
+        ("intersection", 3) => Some(Operator::Intersection),
+        ("difference", 3) => Some(Operator::Difference),
+        ("subset", 2) => Some(Operator::Subset),
+        ("timestamp", 1) => Some(Operator::Timestamp),
+        ("duration", 1) => Some(Operator::Duration),
+        ("now", 0) => Some(Operator::Now),
+        _ => None,
+    }
+}
+
+/// Check that a rule head, or the head of a rule type, is not the name and arity of a builtin.
+/// A call to it would be the builtin, so the rule could never be called.
+pub(crate) fn check_rule_head(
+    loc: usize,
+    name: &Symbol,
+    arity: usize,
+) -> Result<(), error::ParseError> {
+    let kind = match builtin_operator(name, arity) {
+        Some(operator) if operator.is_aggregate() => "aggregate",
+        Some(Operator::RegexMatch) => "regular expression match",
+        Some(Operator::Format) => "string format",
+        Some(Operator::Union)
+        | Some(Operator::Intersection)
+        | Some(Operator::Difference)
+        | Some(Operator::Subset) => "set operation",
+        Some(Operator::Timestamp) | Some(Operator::Duration) | Some(Operator::Now) => "time value",
+        None if name.0 == "set" && arity == 0 => "empty set",
+        _ => return Ok(()),
+    };
+    Err(error::ParseError::BuiltinRule {
+        loc,
+        msg: format!(
+            "{}/{} is a builtin {} and cannot be defined as a rule",
+            name, arity, kind
+        ),
+    })
+}
+
+/// Check that the value of a constant is a literal: a number, string or boolean, or a list,
+/// dictionary or set of literals. Timestamps and durations are evaluated, so that the value of
+/// `const GRACE = duration("7d");` is a duration.
+pub(crate) fn constant_value(term: &Term) -> Result<Term, error::ParseError> {
+    let error = |msg| error::ParseError::Constant {
+        loc: term.offset(),
+        msg,
+    };
+    let value = match term.value() {
+        Value::Number(_) | Value::String(_) | Value::Boolean(_) => return Ok(term.clone()),
+        Value::List(terms) => {
+            Value::List(terms.iter().map(constant_value).collect::<Result<_, _>>()?)
+        }
+        Value::Set(set) => Value::Set(Set::new(
+            set.elements()
+                .iter()
+                .map(constant_value)
+                .collect::<Result<_, _>>()?,
+        )),
+        Value::Dictionary(Dictionary { fields }) => Value::Dictionary(Dictionary {
+            fields: fields
+                .iter()
+                .map(|(key, value)| Ok((key.clone(), constant_value(value)?)))
+                .collect::<Result<_, _>>()?,
+        }),
+        Value::Expression(Operation {
+            operator: operator @ (Operator::Timestamp | Operator::Duration),
+            args,
+        }) => match args[0].value() {
+            Value::String(s) if *operator == Operator::Timestamp => {
+                Value::Timestamp(Timestamp::parse(s).map_err(error)?)
+            }
+            Value::String(s) => Value::Duration(Duration::parse(s).map_err(error)?),
+            _ => return Err(error(format!("{} takes a string", operator.to_polar()))),
+        },
+        _ => {
+            return Err(error(format!(
+                "The value of a constant must be a literal, not {}",
+                term.to_polar()
+            )))
+        }
+    };
+    Ok(term.clone_with_value(value))
+}
+
 lalrpop_mod!(
     #[allow(clippy::all, dead_code, unused_imports, unused_mut)]
     polar
 );
 
 use super::error::{self, PolarResult};
+use super::formatting::ToPolarString;
 use super::lexer::{self, Lexer};
 use super::rules::*;
 use super::terms::*;
@@ -21,17 +182,88 @@ use super::terms::*;
 #[derive(Clone, Debug, PartialEq)]
 pub enum Line {
     Rule(Rule),
+    RuleType(Rule),
+    ResourceBlock(ResourceBlock),
+    /// A module declaration, with the module name as a variable.
+    Module(Term),
+    /// The rules a module exports, as variables.
+    Export(Vec<Term>),
+    /// An import of another policy file, with its path as a string. The host resolves it.
+    Import(Term),
+    Constant(Constant),
     Query(Term),
 }
 
-fn to_parse_error(e: ParseError<usize, lexer::Token, error::ParseError>) -> error::ParseError {
+/// Finds where lines end in a stream of tokens: at a `;`, or at the `}` that closes a resource
+/// block. The `;`s inside a resource block end its items, not the line.
+#[derive(Default)]
+pub(crate) struct LineEnds {
+    /// Tokens seen so far in the current line.
+    count: usize,
+    /// Whether the line starts with `resource` and a name, as in `resource Repo {`. A call like
+    /// `resource({x: 1})` is an ordinary rule.
+    resource: bool,
+    /// How deeply nested in braces the current token is, if the line is a resource block.
+    block_depth: Option<usize>,
+}
+
+impl LineEnds {
+    /// Return whether `token` ends the current line.
+    pub(crate) fn ends_line(&mut self, token: &Token) -> bool {
+        let position = self.count;
+        self.count += 1;
+        let ends = match (token, self.block_depth) {
+            (Token::Symbol(name), None) if position == 0 => {
+                self.resource = name.0 == "resource";
+                false
+            }
+            (Token::Symbol(_), None) if position == 1 => false,
+            (Token::SemiColon, None) => true,
+            (_, None) if position == 1 => {
+                self.resource = false;
+                false
+            }
+            (Token::LCB, None) if position == 2 && self.resource => {
+                self.block_depth = Some(1);
+                false
+            }
+            (Token::LCB, Some(depth)) => {
+                self.block_depth = Some(depth + 1);
+                false
+            }
+            (Token::RCB, Some(depth)) => {
+                self.block_depth = Some(depth - 1);
+                depth == 1
+            }
+            _ => false,
+        };
+        if ends {
+            self.reset();
+        }
+        ends
+    }
+
+    pub(crate) fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+fn to_parse_error(
+    src: &str,
+    e: ParseError<usize, lexer::Token, error::ParseError>,
+) -> error::ParseError {
     match e {
         ParseError::InvalidToken { location: loc } => error::ParseError::InvalidToken { loc },
-        ParseError::UnrecognizedEOF { location: loc, .. } => {
-            error::ParseError::UnrecognizedEOF { loc }
-        }
+        ParseError::UnrecognizedEOF {
+            location: loc,
+            expected,
+        } => error::ParseError::UnrecognizedEOF {
+            loc,
+            expected: expected_tokens(src, loc, expected),
+        },
         ParseError::UnrecognizedToken {
-            token: (loc, t, _), ..
+            token: (loc, t, _),
+            expected,
         } => match t {
             Token::Debug | Token::Cut | Token::In | Token::New => error::ParseError::ReservedWord {
                 token: t.to_string(),
@@ -40,6 +272,7 @@ fn to_parse_error(e: ParseError<usize, lexer::Token, error::ParseError>) -> erro
             _ => error::ParseError::UnrecognizedToken {
                 token: t.to_string(),
                 loc,
+                expected: expected_tokens(src, loc, expected),
             },
         },
         ParseError::ExtraToken { token: (loc, t, _) } => error::ParseError::ExtraToken {
@@ -50,29 +283,120 @@ fn to_parse_error(e: ParseError<usize, lexer::Token, error::ParseError>) -> erro
     }
 }
 
+/// lalrpop names the terminals it expected as they are written in the grammar, e.g., `"\";\""`
+/// or `Symbol`. Strip the quotes from the literal ones.
+///
+/// The list is a superset of what is valid at `loc`, because the parser shares states between
+/// contexts: it may expect a `)` at the top level, or a `;` inside a list. Drop the closing
+/// brackets and separators that cannot appear given the brackets open at `loc`.
+fn expected_tokens(src: &str, loc: usize, expected: Vec<String>) -> Vec<String> {
+    let mut open = vec![];
+    for token in Lexer::new(src) {
+        match token {
+            Ok((start, _, _)) if start >= loc => break,
+            Ok((_, Token::LP, _)) => open.push(")"),
+            Ok((_, Token::LB, _)) => open.push("]"),
+            Ok((_, Token::LCB, _)) => open.push("}"),
+            Ok((_, Token::RP, _)) | Ok((_, Token::RB, _)) | Ok((_, Token::RCB, _)) => {
+                open.pop();
+            }
+            Ok((_, Token::SemiColon, _)) => open.clear(),
+            Ok(_) => {}
+            Err(_) => break,
+        }
+    }
+    let innermost = open.last();
+    expected
+        .into_iter()
+        .map(|e| e.trim_matches('"').to_owned())
+        .filter(|e| match (e.as_str(), innermost) {
+            (")", _) | ("]", _) | ("}", _) => innermost == Some(&e.as_str()),
+            (",", None) => false,
+            ("|", Some(&close)) => close == "]",
+            (";", Some(_)) | ("if", Some(_)) => false,
+            _ => true,
+        })
+        .collect()
+}
+
 pub fn parse_term(src: &str) -> PolarResult<Term> {
     polar::TermParser::new()
         .parse(0, Lexer::new(src))
-        .map_err(|e| to_parse_error(e).into())
+        .map_err(|e| to_parse_error(src, e).into())
 }
 
+/// Parse a source line by line. After an error, parsing resumes after the `;` (or the `}` of a
+/// resource block) that ends the line, so that every syntax error in the source is reported. If
+/// there is more than one, they are returned together as a `ParseError::Multiple`.
 pub fn parse_lines(src_id: u64, src: &str) -> PolarResult<Vec<Line>> {
-    polar::LinesParser::new()
-        .parse(src_id, Lexer::new(src))
-        .map_err(|e| to_parse_error(e).into())
+    let parser = polar::LineParser::new();
+    let mut lexer = Lexer::new(src);
+    let mut lines = vec![];
+    let mut errors = vec![];
+    let mut tokens = vec![];
+    let mut line_ends = LineEnds::default();
+    // The doc comment before the first token of the line.
+    let mut doc = None;
+    let parse_line = |tokens: &mut Vec<_>, doc: Option<String>| {
+        parser
+            .parse(src_id, tokens.drain(..))
+            .map(|line| match line {
+                Line::Rule(rule) => Line::Rule(Rule { doc, ..rule }),
+                Line::RuleType(rule) => Line::RuleType(Rule { doc, ..rule }),
+                Line::Constant(constant) => Line::Constant(Constant { doc, ..constant }),
+                query => query,
+            })
+            .map_err(|e| to_parse_error(src, e))
+    };
+    while let Some(token) = lexer.next() {
+        if tokens.is_empty() {
+            doc = lexer.take_doc();
+        }
+        match token {
+            Ok((_, ref t, _)) if line_ends.ends_line(t) => {
+                tokens.push(token);
+                match parse_line(&mut tokens, doc.take()) {
+                    Ok(line) => lines.push(line),
+                    Err(e) => errors.push(e),
+                }
+            }
+            Ok(_) => tokens.push(token),
+            Err(e) => {
+                errors.push(e);
+                tokens.clear();
+                line_ends.reset();
+                lexer.skip_line();
+            }
+        }
+    }
+    if !tokens.is_empty() {
+        match parse_line(&mut tokens, doc) {
+            Ok(line) => lines.push(line),
+            Err(e) => errors.push(e),
+        }
+    }
+
+    match errors.len() {
+        0 => Ok(lines),
+        1 => Err(errors.pop().unwrap().into()),
+        _ => Err(error::ParseError::Multiple {
+            errors: errors.into_iter().map(error::PolarError::from).collect(),
+        }
+        .into()),
+    }
 }
 
 pub fn parse_query(src_id: u64, src: &str) -> PolarResult<Term> {
     polar::TermExpParser::new()
         .parse(src_id, Lexer::new(src))
-        .map_err(|e| to_parse_error(e).into())
+        .map_err(|e| to_parse_error(src, e).into())
 }
 
 #[cfg(test)]
 pub fn parse_rules(src_id: u64, src: &str) -> PolarResult<Vec<Rule>> {
     polar::RulesParser::new()
         .parse(src_id, Lexer::new(src))
-        .map_err(|e| to_parse_error(e).into())
+        .map_err(|e| to_parse_error(src, e).into())
 }
 
 #[cfg(test)]
@@ -242,6 +566,82 @@ mod tests {
         assert_eq!(line[0], Line::Query(term!(call!("f", [1]))));
     }
 
+    #[test]
+    fn test_parse_annotations() {
+        let rules = parse_rules(
+            0,
+            "## Doc\n@tag(\"pii\", 2) @deprecated\nallow(x) if x = 1;\nf(1);",
+        )
+        .unwrap();
+        assert_eq!(
+            rules[0].to_polar(),
+            "@tag(\"pii\", 2) @deprecated allow(x) if x = 1;"
+        );
+        assert_eq!(rules[0].annotations[0].name, sym!("tag"));
+        assert!(rules[1].annotations.is_empty());
+
+        let lines = parse_lines("## Doc\n@owner(\"billing\")\nf(1);");
+        match &lines[0] {
+            Line::Rule(rule) => assert_eq!(rule.doc.as_deref(), Some("Doc")),
+            line => panic!("expected a rule, found {:?}", line),
+        }
+
+        // Arguments must be literals.
+        assert!(parse_rules(0, "@owner(x) f(1);").is_err());
+        assert!(parse_rules(0, "@tag(\"pii\");").is_err());
+    }
+
+    #[test]
+    fn test_parse_resource_block() {
+        let src = r#"resource Repo {
+            roles = ["reader"];
+            relations = {parent: Org};
+            "read" if "reader";
+            "reader" if "member" on "parent";
+        }
+        f(x) if x = {a: 1};"#;
+        let lines = parse_lines(src);
+        assert_eq!(lines.len(), 2);
+        let block = match &lines[0] {
+            Line::ResourceBlock(block) => block,
+            line => panic!("expected a resource block, found {:?}", line),
+        };
+        assert_eq!(block.resource, term!(sym!("Repo")));
+        assert_eq!(
+            block.items[1],
+            BlockItem::Declaration {
+                name: term!(sym!("relations")),
+                value: term!(btreemap! {sym!("parent") => term!(sym!("Org"))}),
+            }
+        );
+        assert_eq!(
+            block.items[3],
+            BlockItem::ShorthandRule {
+                head: term!("reader"),
+                implier: term!("member"),
+                relation: Some(term!("parent")),
+            }
+        );
+
+        // The `;`s inside a block do not end it, so its errors are reported once.
+        let err = super::parse_lines(0, "resource Repo { \"read\" if \"reader\" with \"x\"; }")
+            .expect_err("parse error");
+        assert_eq!(
+            err.to_string(),
+            "did not expect to find the token 'with', expected `;` or `on`"
+        );
+
+        // A rule named `resource` is not a block.
+        let lines = parse_lines("resource({x: 1});\nresource({x: 1}) if true;\nf(1);");
+        assert_eq!(lines.len(), 3);
+        for line in &lines[..2] {
+            match line {
+                Line::Rule(rule) => assert_eq!(rule.name, sym!("resource")),
+                line => panic!("expected a rule, found {:?}", line),
+            }
+        }
+    }
+
     #[test]
     fn test_parse_new() {
         let f = r#"a(x) if x = new Foo(a: 1);"#;
@@ -285,6 +685,80 @@ mod tests {
         parse_rules(0, f).expect_err("parse error");
     }
 
+    #[test]
+    fn test_parse_aggregates() {
+        let term = parse_query("findall(x, f(x) and x > 1, xs)");
+        assert_eq!(term.to_polar(), "findall(x, f(x) and x > 1, xs)");
+        assert!(matches!(
+            term.value(),
+            Value::Expression(Operation {
+                operator: Operator::FindAll,
+                ..
+            })
+        ));
+        let term = parse_query("count(x, x in [1, 2], 2) and max(x, x = 1 or x = 2, y)");
+        assert_eq!(
+            term.to_polar(),
+            "count(x, x in [1, 2], 2) and max(x, x = 1 or x = 2, y)"
+        );
+
+        // Calls with other arities are ordinary rule calls.
+        let term = parse_query("count(x, y)");
+        assert!(matches!(term.value(), Value::Call(_)));
+
+        let term = parse_query(r#"regex_match("^a(b*)$", x, [_, bs])"#);
+        assert_eq!(term.to_polar(), r#"regex_match("^a(b*)$", x, [_, bs])"#);
+        assert!(matches!(
+            term.value(),
+            Value::Expression(Operation {
+                operator: Operator::RegexMatch,
+                ..
+            })
+        ));
+
+        // Interpolated strings are calls to `format`.
+        let term = parse_query(r#"x = format("{}/{}", a, b)"#);
+        assert_eq!(term.to_polar(), r#"x = format("{}/{}", a, b)"#);
+        let term = parse_query(r#"x = f"{a}/{b.c}""#);
+        assert_eq!(term.to_polar(), r#"x = format("{}/{}", a, b.c)"#);
+    }
+
+    #[test]
+    fn test_parse_sets() {
+        let term = parse_query(r#"x = {"b", 1, x.y, 1}"#);
+        assert_eq!(term.to_polar(), r#"x = {1, "b", x.y}"#);
+        assert!(matches!(
+            term.value().as_expression().unwrap().args[1].value(),
+            Value::Set(_)
+        ));
+
+        // `{}` is an empty dictionary, and `set()` is the empty set.
+        let term = parse_query("x = {} and y = set() and z = {b: 1}");
+        assert_eq!(term.to_polar(), "x = {} and y = set() and z = {b: 1}");
+
+        let term = parse_query("intersection(a, b, c) and subset(c, {1})");
+        assert_eq!(term.to_polar(), "intersection(a, b, c) and subset(c, {1})");
+    }
+
+    #[test]
+    fn test_parse_times() {
+        let q = r#"t = timestamp("2021-06-01T09:00:00Z") and now() < t + duration("1h")"#;
+        let term = parse_query(q);
+        assert_eq!(term.to_polar(), q);
+        assert!(matches!(
+            term.value().as_expression().unwrap().args[0]
+                .value()
+                .as_expression()
+                .unwrap()
+                .args[1]
+                .value(),
+            Value::Expression(Operation {
+                operator: Operator::Timestamp,
+                ..
+            })
+        ));
+    }
+
     #[test]
     fn test_parse_matches() {
         let term = parse_query("{} matches {}");
@@ -292,6 +766,24 @@ mod tests {
         let _term = parse_query("{x: 1} matches {}");
     }
 
+    #[test]
+    fn test_parse_if_then_else() {
+        let q = "if x.public then y = 1 else y = 2 and z = 3";
+        let term = parse_query(q);
+        assert_eq!(term.to_polar(), q);
+        assert!(matches!(
+            term.value(),
+            Value::Expression(Operation {
+                operator: Operator::If,
+                ..
+            })
+        ));
+
+        // Inside `and`, `or` & `not`, an if-then-else must be parenthesized.
+        let q = "f(x) and (if x = 1 then g(x) else h(x)) or not (if a then b else c)";
+        assert_eq!(parse_query(q).to_polar(), q);
+    }
+
     #[test]
     fn test_parse_rest_vars() {
         let q = "[1, 2, *x] = [*rest]";
@@ -366,6 +858,15 @@ mod tests {
             "(1 or 2) = x",
             "x = (not x)",
             "y matches z = x",
+            "count(x = 1, x = 1, n)",
+            "count(x, f(x), n = 1)",
+            "findall(x, x = 1, xs, 1)",
+            "f(x, x = 1, n)",
+            "x = count(x, x = 1, n)",
+            "regex_match(x = 1, y)",
+            "if 1 then x = 1 else x = 2",
+            "if x then 1 else 2",
+            "x = regex_match(x, y)",
         ] {
             assert!(matches!(
                 super::parse_query(0, bad_query).expect_err("parse error"),
@@ -376,4 +877,73 @@ mod tests {
             ));
         }
     }
+
+    #[test]
+    fn test_parse_error_recovery() {
+        let src = "f(x) if x = ;\ng(x) if x = 1;\nh($);\ni(x) if x and;\nj(1);";
+        let errors = match super::parse_lines(0, src).expect_err("parse error").kind {
+            error::ErrorKind::Parse(error::ParseError::Multiple { errors }) => errors,
+            kind => panic!("unexpected error {:?}", kind),
+        };
+        assert_eq!(errors.len(), 3);
+        assert!(matches!(
+            errors[0].kind,
+            error::ErrorKind::Parse(error::ParseError::UnrecognizedToken { loc: 12, .. })
+        ));
+        assert!(matches!(
+            errors[1].kind,
+            error::ErrorKind::Parse(error::ParseError::InvalidTokenCharacter { c: '$', .. })
+        ));
+        assert!(matches!(
+            errors[2].kind,
+            error::ErrorKind::Parse(error::ParseError::UnrecognizedToken { .. })
+        ));
+
+        // A single error is reported on its own.
+        assert!(matches!(
+            super::parse_lines(0, "f(x);\ng(")
+                .expect_err("parse error")
+                .kind,
+            error::ErrorKind::Parse(error::ParseError::UnrecognizedEOF { .. })
+        ));
+        assert_eq!(parse_lines("f(1); g(2);").len(), 2);
+    }
+
+    #[test]
+    fn test_expected_tokens() {
+        let message = |src: &str| {
+            super::parse_lines(0, src)
+                .expect_err("parse error")
+                .to_string()
+        };
+        assert_eq!(
+            message("f(x) if x = 1\ng(x);"),
+            "did not expect to find the token 'g', expected `;`, `and` or `or` after rule body. \
+             Did you forget a semi-colon"
+        );
+        assert_eq!(
+            message("f(x) if x = 1 if x = 2;"),
+            "did not expect to find the token 'if', expected `;`, `and` or `or` after rule body. \
+             A rule has only one `if`; use `and` to add conditions to its body"
+        );
+        assert_eq!(
+            message("f(x == 1);"),
+            "did not expect to find the token '==', expected `,` or `)`. \
+             Rule heads cannot contain comparisons; move the comparison into the rule body, \
+             e.g., `f(x) if x == 1;`"
+        );
+        assert_eq!(
+            message("f(x) g(x);"),
+            "did not expect to find the token 'g', expected `;` or `if` after rule head"
+        );
+        // Closing brackets are only expected inside the matching bracket.
+        assert_eq!(
+            message("f(x) if x = [1 2];"),
+            "did not expect to find the token '2', expected `and`, `or`, `,` or `]`"
+        );
+        assert_eq!(
+            message("f(x"),
+            "hit the end of the file unexpectedly, expected `and`, `or`, `,` or `)`"
+        );
+    }
 }
@@ -943,6 +943,50 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_partial_in_string_formatting() -> TestResult {
+        let p = Polar::new();
+        p.load_str(
+            r#"f(x, y) if y = "org:" + x;
+               g(x, y) if y = f"org:{x}";"#,
+        )?;
+        let mut q = p.new_query_from_term(term!(call!("f", [sym!("x"), "org:1"])), false);
+        assert_partial_expression!(next_binding(&mut q)?, "x", "\"org:\" + _this = \"org:1\"");
+        assert_query_done!(q);
+        let mut q = p.new_query_from_term(term!(call!("g", [sym!("x"), "org:1"])), false);
+        assert_partial_expression!(
+            next_binding(&mut q)?,
+            "x",
+            "format(\"org:{}\", _this) = \"org:1\""
+        );
+        assert_query_done!(q);
+        Ok(())
+    }
+
+    #[test]
+    fn test_partial_in_set_operation() -> TestResult {
+        let p = Polar::new();
+        p.load_str("f(x) if subset(x, {1, 2});")?;
+        let mut q = p.new_query_from_term(term!(call!("f", [sym!("x")])), false);
+        assert_partial_expression!(next_binding(&mut q)?, "x", "subset(_this, {1, 2})");
+        assert_query_done!(q);
+        Ok(())
+    }
+
+    #[test]
+    fn test_partial_time_comparison() -> TestResult {
+        let p = Polar::new();
+        p.load_str(r#"f(x) if x.expiry > timestamp("2021-06-01T11:00:00+02:00");"#)?;
+        let mut q = p.new_query_from_term(term!(call!("f", [sym!("x")])), false);
+        assert_partial_expression!(
+            next_binding(&mut q)?,
+            "x",
+            r#"_this.expiry > timestamp("2021-06-01T09:00:00Z")"#
+        );
+        assert_query_done!(q);
+        Ok(())
+    }
+
     #[test]
     fn test_method_call_on_partial() -> TestResult {
         let p = Polar::new();
@@ -1597,6 +1641,40 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_that_aggregate_with_partial_errors() -> TestResult {
+        let p = Polar::new();
+        p.load_str("f(x, n) if count(y, y in x, n);")?;
+        p.register_constant(sym!("x"), op!(And).into_term());
+        let mut q = p.new_query_from_term(term!(call!("f", [sym!("x"), sym!("n")])), false);
+        let error = q.next_event().unwrap_err();
+        assert!(matches!(
+            error,
+            PolarError {
+                kind: ErrorKind::Runtime(RuntimeError::Unsupported { msg }),
+                ..
+            } if msg == "cannot use count with partial evaluation"
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_that_tabling_with_partial_errors() -> TestResult {
+        let p = Polar::new();
+        p.load_str("@table\nf(x) if x = 1;")?;
+        p.register_constant(sym!("x"), op!(And).into_term());
+        let mut q = p.new_query_from_term(term!(call!("f", [sym!("x")])), false);
+        let error = q.next_event().unwrap_err();
+        assert!(matches!(
+            error,
+            PolarError {
+                kind: ErrorKind::Runtime(RuntimeError::Unsupported { .. }),
+                ..
+            }
+        ));
+        Ok(())
+    }
+
     #[test]
     fn test_cut_with_partial() -> TestResult {
         let p = Polar::new();
@@ -1630,6 +1708,44 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_if_then_else_with_partial() -> TestResult {
+        let p = Polar::new();
+        p.load_str(
+            r#"f(x, y) if if x.public = true then y = "read" else y = "none";
+               g(x) if if x.public = true then x.owner = 1 else x.owner = 2;
+               role(1, "admin");
+               role(1, "member");
+               h(x, user, level) if
+                   (if role(user, r) then level = r else level = "guest") and
+                   x.owner = user;"#,
+        )?;
+        p.register_constant(sym!("x"), op!(And).into_term());
+        let mut q = p.new_query_from_term(term!(call!("f", [sym!("x"), sym!("y")])), false);
+        let next = next_binding(&mut q)?;
+        assert_partial_expression!(next, "x", "true = _this.public");
+        assert_eq!(next[&sym!("y")], term!("read"));
+        let next = next_binding(&mut q)?;
+        assert_partial_expression!(next, "x", "true != _this.public");
+        assert_eq!(next[&sym!("y")], term!("none"));
+        assert_query_done!(q);
+
+        let mut q = p.new_query_from_term(term!(call!("g", [sym!("x")])), false);
+        let next = next_binding(&mut q)?;
+        assert_partial_expression!(next, "x", "true = _this.public and 1 = _this.owner");
+        let next = next_binding(&mut q)?;
+        assert_partial_expression!(next, "x", "true != _this.public and 2 = _this.owner");
+        assert_query_done!(q);
+
+        // A condition without partials still commits to its first answer.
+        let mut q = p.new_query_from_term(term!(call!("h", [sym!("x"), 1, sym!("level")])), false);
+        let next = next_binding(&mut q)?;
+        assert_partial_expression!(next, "x", "_this.owner = 1");
+        assert_eq!(next[&sym!("level")], term!("admin"));
+        assert_query_done!(q);
+        Ok(())
+    }
+
     #[test]
     fn test_method_sorting_with_cut_and_partial() -> TestResult {
         let p = Polar::new();
@@ -2,7 +2,7 @@ use std::collections::{HashMap, HashSet, VecDeque};
 
 use crate::bindings::Bindings;
 use crate::folder::{fold_term, Folder};
-use crate::terms::{Operation, Operator, Symbol, Term, Value};
+use crate::terms::{Operation, Operator, Set, Symbol, Term, Value};
 
 use super::partial::{invert_operation, FALSE, TRUE};
 
@@ -392,7 +392,11 @@ impl Simplifier {
         *term = self.deref(term);
         if matches!(
             term.value(),
-            Value::Dictionary(_) | Value::Call(_) | Value::List(_) | Value::Expression(_)
+            Value::Dictionary(_)
+                | Value::Call(_)
+                | Value::List(_)
+                | Value::Set(_)
+                | Value::Expression(_)
         ) {
             let value = term.mut_value();
             match value {
@@ -416,6 +420,13 @@ impl Simplifier {
                         self.simplify_term(elem);
                     }
                 }
+                Value::Set(set) => {
+                    let mut elements = set.elements().clone();
+                    for elem in elements.iter_mut() {
+                        self.simplify_term(elem);
+                    }
+                    *set = Set::new(elements);
+                }
                 Value::Expression(operation) => {
                     self.simplify_operation(operation);
                 }
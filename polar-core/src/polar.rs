@@ -1,17 +1,26 @@
-use super::error::PolarResult;
+use super::call_graph::CallGraph;
+use super::docs::PolicyDocs;
+use super::error::{ParseError, PolarError, PolarResult};
 use super::events::*;
+use super::folder::Folder;
 use super::kb::*;
 use super::messages::*;
+use super::modules::{link_module_calls, resolve_modules, ModuleSource};
 use super::parser;
 use super::rewrites::*;
+use super::rule_types::RuleTypes;
 use super::rules::*;
 use super::runnable::Runnable;
 use super::sources::*;
 use super::terms::*;
+use super::traces::TraceFilter;
 use super::vm::*;
-use super::warnings::check_singletons;
+use super::warnings::{
+    check_comparisons, check_knowledge_base, check_singletons, check_source, LintCode,
+};
 
 use std::collections::{HashMap, HashSet};
+use std::path::{Component, Path, PathBuf};
 use std::sync::{Arc, RwLock};
 
 pub struct Query {
@@ -108,6 +117,18 @@ impl Query {
     pub fn bind(&mut self, name: Symbol, value: Term) -> PolarResult<()> {
         self.vm.bind(&name, value)
     }
+
+    /// Pin the time returned by `now()` for this query. By default, it is the time the query
+    /// starts running.
+    pub fn set_now(&mut self, now: Timestamp) {
+        self.vm.set_now(now)
+    }
+
+    /// Leave the nodes and bindings excluded by `filter` out of the `tree` and `dot` of the
+    /// traces of results.
+    pub fn set_trace_filter(&mut self, filter: TraceFilter) {
+        self.vm.set_trace_filter(filter)
+    }
 }
 
 // Query as an iterator returns `None` after the first time `Done` is seen
@@ -126,6 +147,31 @@ impl Iterator for Query {
     }
 }
 
+/// Resolve the path of a file imported by `importer` relative to the directory it is in, and
+/// normalize it so that each file has one name however it is imported.
+fn resolve_import(importer: &str, path: &Term) -> String {
+    let path = path.value().as_string().unwrap();
+    let dir = Path::new(importer)
+        .parent()
+        .unwrap_or_else(|| Path::new(""));
+    let mut resolved = PathBuf::new();
+    for component in dir.join(path).components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir
+                if matches!(
+                    resolved.components().next_back(),
+                    Some(Component::Normal(_))
+                ) =>
+            {
+                resolved.pop();
+            }
+            component => resolved.push(component),
+        }
+    }
+    resolved.to_string_lossy().into_owned()
+}
+
 pub struct Polar {
     pub kb: Arc<RwLock<KnowledgeBase>>,
     messages: MessageQueue,
@@ -133,6 +179,12 @@ pub struct Polar {
     loaded_files: Arc<RwLock<HashSet<String>>>,
     /// Map from source code loaded to the filename it was loaded as
     loaded_content: Arc<RwLock<HashMap<String, String>>>,
+    /// Warnings that should not be reported
+    ignored_warnings: Arc<RwLock<HashSet<LintCode>>>,
+    /// Whether sources have been loaded since the knowledge base was last checked
+    unchecked: Arc<RwLock<bool>>,
+    /// Warnings from checks of the whole knowledge base that have already been reported
+    reported_warnings: Arc<RwLock<HashSet<String>>>,
 }
 
 impl Default for Polar {
@@ -148,6 +200,9 @@ impl Polar {
             messages: MessageQueue::new(),
             loaded_content: Arc::new(RwLock::new(HashMap::new())), // file content -> file name
             loaded_files: Arc::new(RwLock::new(HashSet::new())),   // set of file names
+            ignored_warnings: Arc::new(RwLock::new(HashSet::new())),
+            unchecked: Arc::new(RwLock::new(false)),
+            reported_warnings: Arc::new(RwLock::new(HashSet::new())),
         }
     }
 
@@ -194,26 +249,129 @@ impl Polar {
         Ok(())
     }
 
-    pub fn load(&self, src: &str, filename: Option<String>) -> PolarResult<()> {
-        if let Some(ref filename) = filename {
-            self.check_file(src, filename)?;
+    /// Whether a file with this name has been loaded.
+    pub fn is_loaded(&self, filename: &str) -> bool {
+        self.loaded_files.read().unwrap().contains(filename)
+    }
+
+    /// The files that the source of `filename` imports, resolved relative to it, in the order
+    /// they are imported. The host must load them before it loads the source.
+    pub fn imports(&self, src: &str, filename: &str) -> PolarResult<Vec<String>> {
+        let source = Source {
+            filename: Some(filename.to_owned()),
+            src: src.to_owned(),
+        };
+        let lines = parser::parse_lines(0, src).map_err(|e| e.set_context(Some(&source), None))?;
+        Ok(lines
+            .iter()
+            .filter_map(|line| match line {
+                parser::Line::Import(path) => Some(resolve_import(filename, path)),
+                _ => None,
+            })
+            .collect())
+    }
+
+    /// Check that the files a source imports have been loaded.
+    fn check_imports(&self, source: &Source, lines: &[parser::Line]) -> PolarResult<()> {
+        for line in lines {
+            if let parser::Line::Import(path) = line {
+                let msg = match &source.filename {
+                    None => "Only a policy file can import other files".to_owned(),
+                    Some(filename) => {
+                        let import = resolve_import(filename, path);
+                        if self.is_loaded(&import) {
+                            continue;
+                        }
+                        format!(
+                            "Imported file {} has not been loaded; load it before {}",
+                            import, filename
+                        )
+                    }
+                };
+                let error = ParseError::Import {
+                    loc: path.offset(),
+                    msg,
+                };
+                return Err(PolarError::from(error).set_context(Some(source), None));
+            }
         }
+        Ok(())
+    }
+
+    pub fn load(&self, src: &str, filename: Option<String>) -> PolarResult<()> {
         let source = Source {
             filename,
             src: src.to_owned(),
         };
         let mut kb = self.kb.write().unwrap();
         let src_id = kb.new_id();
-        let mut lines =
-            parser::parse_lines(src_id, src).map_err(|e| e.set_context(Some(&source), None))?;
-        lines.reverse();
+        let lines = parser::parse_lines(src_id, src)
+            .and_then(rewrite_resource_blocks)
+            .map_err(|e| e.set_context(Some(&source), None))?;
+        self.check_imports(&source, &lines)?;
+        if let Some(filename) = &source.filename {
+            self.check_file(src, filename)?;
+        }
         kb.sources.add_source(source, src_id);
+        let ModuleSource { mut lines, module } = resolve_modules(&kb, lines)?;
+
+        // The constants defined in policies, including this one, which the rules may name in
+        // their specializers and patterns.
+        let mut constants = kb.policy_constant_values();
+        let mut new_constants = vec![];
+        for line in &lines {
+            if let parser::Line::Constant(Constant { name, value, .. }) = line {
+                let symbol = name.value().as_symbol()?;
+                if kb.is_constant(symbol) || constants.contains_key(symbol) {
+                    let error = ParseError::Constant {
+                        loc: name.offset(),
+                        msg: format!("Constant {} is already defined", symbol),
+                    };
+                    let source = kb.sources.get_source(src_id);
+                    return Err(PolarError::from(error).set_context(source.as_ref(), None));
+                }
+                constants.insert(symbol.clone(), value.clone());
+                new_constants.push((symbol.clone(), value.clone()));
+            }
+        }
+        let mut constant_patterns = ConstantPatterns::new(&constants);
+
+        // Check the new rules against the declared types, and the rules already loaded against
+        // the new types, before adding anything to the knowledge base.
+        let mut rule_types = kb.rule_types.clone();
+        for line in &lines {
+            if let parser::Line::RuleType(rule_type) = line {
+                rule_types.add(&kb, rule_type.clone())?;
+            }
+        }
+        let checked_lines = lines
+            .iter()
+            .map(|line| match line {
+                parser::Line::Rule(rule) => {
+                    parser::Line::Rule(constant_patterns.fold_rule(rule.clone()))
+                }
+                line => line.clone(),
+            })
+            .collect::<Vec<_>>();
+        rule_types.check_lines(&kb, &checked_lines)?;
+        kb.rule_types = rule_types;
+        if let Some((name, exports)) = module {
+            kb.modules.entry(name).or_default().exports.extend(exports);
+        }
+        for (name, value) in new_constants {
+            kb.policy_constant(name, value);
+        }
+
+        lines.reverse();
+
         let mut warnings = vec![];
         while let Some(line) = lines.pop() {
             match line {
                 parser::Line::Rule(rule) => {
-                    let mut rule_warnings = check_singletons(&rule, &kb);
-                    warnings.append(&mut rule_warnings);
+                    kb.docs.add_rule(&rule);
+                    let rule = constant_patterns.fold_rule(rule);
+                    warnings.append(&mut check_singletons(&rule, &kb));
+                    warnings.append(&mut check_comparisons(&rule, &kb));
                     let rule = rewrite_rule(rule, &mut kb);
 
                     let name = rule.name.clone();
@@ -223,19 +381,48 @@ impl Polar {
                         .or_insert_with(|| GenericRule::new(name, vec![]));
                     generic_rule.add_rule(Arc::new(rule));
                 }
+                parser::Line::RuleType(rule_type) => kb.docs.add_rule_type(&rule_type),
+                parser::Line::Constant(constant) => kb.docs.add_constant(&constant),
+                parser::Line::ResourceBlock(_) => unreachable!("resource blocks are desugared"),
+                parser::Line::Module(_) | parser::Line::Export(_) | parser::Line::Import(_) => {}
                 parser::Line::Query(term) => {
-                    kb.inline_queries.push(term);
+                    kb.inline_queries.push(constant_patterns.fold_term(term));
                 }
             }
         }
-        self.messages.extend(warnings.iter().map(|m| Message {
-            kind: MessageKind::Warning,
-            msg: m.to_owned(),
-        }));
+        link_module_calls(&mut kb)?;
+        warnings.append(&mut check_source(&kb, src_id));
+        self.report_warnings(warnings);
+        *self.unchecked.write().unwrap() = true;
 
         Ok(())
     }
 
+    fn report_warnings(&self, warnings: Vec<Message>) {
+        let ignored = self.ignored_warnings.read().unwrap();
+        self.messages.extend(
+            warnings
+                .into_iter()
+                .filter(|m| !matches!(m.code, Some(code) if ignored.contains(&code))),
+        );
+    }
+
+    /// Run the checks that need the whole knowledge base, such as for calls to undefined
+    /// rules, if anything has been loaded since they last ran. Only warnings that have not
+    /// been reported before are reported. Called before each query, once the policy is loaded.
+    pub fn check_knowledge_base(&self) {
+        if !std::mem::replace(&mut *self.unchecked.write().unwrap(), false) {
+            return;
+        }
+        let warnings = check_knowledge_base(&self.kb.read().unwrap());
+        let mut reported = self.reported_warnings.write().unwrap();
+        let warnings = warnings
+            .into_iter()
+            .filter(|m| reported.insert(m.msg.clone()))
+            .collect();
+        self.report_warnings(warnings);
+    }
+
     // Used in integration tests
     pub fn load_str(&self, src: &str) -> PolarResult<()> {
         self.load(src, None)
@@ -246,14 +433,21 @@ impl Polar {
         let mut kb = self.kb.write().unwrap();
         kb.rules.clear();
         kb.sources = Sources::default();
+        kb.docs = PolicyDocs::default();
+        kb.rule_types = RuleTypes::default();
+        kb.modules.clear();
         kb.inline_queries.clear();
+        kb.clear_policy_constants();
+        self.reported_warnings.write().unwrap().clear();
         self.loaded_content.write().unwrap().clear();
         self.loaded_files.write().unwrap().clear();
     }
 
+    /// Inline queries run as each source is loaded, before the rest of the policy may be, so
+    /// they do not check the knowledge base.
     pub fn next_inline_query(&self, trace: bool) -> Option<Query> {
         let term = { self.kb.write().unwrap().inline_queries.pop() };
-        term.map(|t| self.new_query_from_term(t, trace))
+        term.map(|t| self.query_from_term(t, trace))
     }
 
     pub fn new_query(&self, src: &str, trace: bool) -> PolarResult<Query> {
@@ -261,6 +455,7 @@ impl Polar {
             filename: None,
             src: src.to_owned(),
         };
+        self.check_knowledge_base();
         let term = {
             let mut kb = self.kb.write().unwrap();
             let src_id = kb.new_id();
@@ -269,12 +464,19 @@ impl Polar {
             kb.sources.add_source(source, src_id);
             term
         };
-        Ok(self.new_query_from_term(term, trace))
+        Ok(self.query_from_term(term, trace))
     }
 
-    pub fn new_query_from_term(&self, mut term: Term, trace: bool) -> Query {
+    pub fn new_query_from_term(&self, term: Term, trace: bool) -> Query {
+        self.check_knowledge_base();
+        self.query_from_term(term, trace)
+    }
+
+    fn query_from_term(&self, mut term: Term, trace: bool) -> Query {
         {
             let mut kb = self.kb.write().unwrap();
+            let constants = kb.policy_constant_values();
+            term = ConstantPatterns::new(&constants).fold_term(term);
             term = rewrite_term(term, &mut kb);
         }
         let query = Goal::Query { term: term.clone() };
@@ -296,6 +498,36 @@ impl Polar {
     pub fn next_message(&self) -> Option<Message> {
         self.messages.next()
     }
+
+    /// Stop reporting warnings with the given code.
+    pub fn ignore_warning(&self, code: LintCode) {
+        self.ignored_warnings.write().unwrap().insert(code);
+    }
+
+    /// Build the graph of calls between the loaded rules.
+    pub fn call_graph(&self) -> CallGraph {
+        CallGraph::new(&self.kb.read().unwrap())
+    }
+
+    /// Return the documentation of the loaded rules.
+    pub fn docs(&self) -> PolicyDocs {
+        self.kb.read().unwrap().docs.clone()
+    }
+
+    /// Return the annotations on each definition of the rule `name`, in the order the
+    /// definitions were loaded.
+    pub fn annotations(&self, name: &Symbol) -> Vec<Vec<Annotation>> {
+        let kb = self.kb.read().unwrap();
+        kb.docs
+            .rules
+            .get(name)
+            .map_or_else(Vec::new, |definitions| {
+                definitions
+                    .iter()
+                    .map(|definition| definition.annotations.clone())
+                    .collect()
+            })
+    }
 }
 
 #[cfg(test)]
@@ -308,4 +540,30 @@ mod tests {
         let _query = polar.new_query("1 = 1", false);
         let _ = polar.load_str("f(_);");
     }
+
+    #[test]
+    fn test_imports() {
+        let polar = Polar::new();
+        let src = "import \"shared/roles.polar\";\nimport \"../lib/./users.polar\";\nf(1);";
+        assert_eq!(
+            polar.imports(src, "policies/app.polar").unwrap(),
+            vec!["policies/shared/roles.polar", "lib/users.polar"]
+        );
+
+        let err = polar.load(src, Some("policies/app.polar".to_owned()));
+        assert_eq!(
+            err.unwrap_err().to_string(),
+            "Imported file policies/shared/roles.polar has not been loaded; load it before \
+             policies/app.polar at line 1, column 8 in file policies/app.polar"
+        );
+        polar
+            .load("g(1);", Some("policies/shared/roles.polar".to_owned()))
+            .unwrap();
+        polar
+            .load("h(1);", Some("lib/users.polar".to_owned()))
+            .unwrap();
+        polar
+            .load(src, Some("policies/app.polar".to_owned()))
+            .unwrap();
+    }
 }
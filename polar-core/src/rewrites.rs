@@ -1,7 +1,10 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
+use super::error::{ParseError, PolarError, PolarResult};
 use super::folder::*;
+use super::formatting::ToPolarString;
 use super::kb::*;
+use super::parser::Line;
 use super::rules::*;
 use super::terms::*;
 
@@ -70,6 +73,9 @@ impl<'kb> Rewriter<'kb> {
                 true
             }
             Operator::New if o.args.len() == 1 => true,
+            Operator::Format if o.args.len() == 2 => true,
+            Operator::Timestamp | Operator::Duration if o.args.len() == 1 => true,
+            Operator::Now if o.args.is_empty() => true,
             _ => false,
         }
     }
@@ -80,6 +86,9 @@ fn temp_name(o: &Operator) -> &'static str {
         Operator::Add | Operator::Div | Operator::Mul | Operator::Sub => "op",
         Operator::Dot => "value",
         Operator::New => "instance",
+        Operator::Format => "string",
+        Operator::Timestamp | Operator::Now => "timestamp",
+        Operator::Duration => "duration",
         _ => "temp",
     }
 }
@@ -89,7 +98,16 @@ fn temp_name(o: &Operator) -> &'static str {
 /// conjunction, creating one if necessary.
 impl<'kb> Folder for Rewriter<'kb> {
     /// Rewrite a rule, pushing expressions in the head into the body.
-    fn fold_rule(&mut self, Rule { name, body, params }: Rule) -> Rule {
+    fn fold_rule(
+        &mut self,
+        Rule {
+            name,
+            body,
+            params,
+            doc,
+            annotations,
+        }: Rule,
+    ) -> Rule {
         let mut body = self.fold_term(body);
 
         self.stack.push(vec![]);
@@ -102,7 +120,13 @@ impl<'kb> Folder for Rewriter<'kb> {
                 args: terms.into_iter().chain(rewrites).collect(),
             }));
         }
-        Rule { name, body, params }
+        Rule {
+            name,
+            body,
+            params,
+            doc,
+            annotations,
+        }
     }
 
     /// Rewrite an expression as a temp, and push a rewritten
@@ -140,7 +164,7 @@ impl<'kb> Folder for Rewriter<'kb> {
 
     fn fold_operation(&mut self, o: Operation) -> Operation {
         match o.operator {
-            Operator::And | Operator::Or | Operator::Not => Operation {
+            Operator::And | Operator::Or | Operator::Not | Operator::If => Operation {
                 operator: fold_operator(o.operator, self),
                 args: o
                     .args
@@ -156,6 +180,36 @@ impl<'kb> Folder for Rewriter<'kb> {
                     })
                     .collect(),
             },
+            operator if operator.is_aggregate() => {
+                // The goal of an aggregate is queried on its own, so it gets its own frame.
+                // Rewrites of the template follow the goal, which binds its variables.
+                let mut args = o.args.into_iter();
+                let (template, goal, result) = (
+                    args.next().unwrap(),
+                    args.next().unwrap(),
+                    args.next().unwrap(),
+                );
+                self.stack.push(vec![]);
+                let mut goal = self.fold_term(goal);
+                let mut rewrites = self.stack.pop().unwrap();
+                for rewrite in rewrites.drain(..).rev() {
+                    and_wrap(&mut goal, rewrite);
+                }
+                self.stack.push(vec![]);
+                let template = self.fold_term(template);
+                let rewrites = self.stack.pop().unwrap();
+                if !rewrites.is_empty() {
+                    let terms = std::iter::once(goal.clone()).chain(rewrites).collect();
+                    goal.replace_value(Value::Expression(Operation {
+                        operator: Operator::And,
+                        args: terms,
+                    }));
+                }
+                Operation {
+                    operator,
+                    args: vec![template, goal, self.fold_term(result)],
+                }
+            }
             _ => fold_operation(o, self),
         }
     }
@@ -209,6 +263,348 @@ pub fn rewrite_rule(rule: Rule, kb: &mut KnowledgeBase) -> Rule {
     fld.fold_rule(rule)
 }
 
+/// Replace the constants defined in a policy that are named in patterns with their values,
+/// so that a specializer like `role: OWNER`, or a pattern like `roles matches ADMIN_ROLES`,
+/// matches the value of the constant instead of an instance of a class with that name.
+pub struct ConstantPatterns<'c> {
+    constants: &'c HashMap<Symbol, Term>,
+    /// How many patterns the term being folded is inside.
+    depth: usize,
+}
+
+impl<'c> ConstantPatterns<'c> {
+    pub fn new(constants: &'c HashMap<Symbol, Term>) -> Self {
+        Self {
+            constants,
+            depth: 0,
+        }
+    }
+}
+
+/// A literal as a pattern, with its dictionaries as dictionary patterns.
+fn literal_pattern(term: &Term) -> Term {
+    term.clone_with_value(match term.value() {
+        Value::Dictionary(Dictionary { fields }) => {
+            let fields = fields
+                .iter()
+                .map(|(key, value)| (key.clone(), literal_pattern(value)))
+                .collect();
+            Value::Pattern(Pattern::Dictionary(Dictionary { fields }))
+        }
+        Value::List(terms) => Value::List(terms.iter().map(literal_pattern).collect()),
+        value => value.clone(),
+    })
+}
+
+impl<'c> Folder for ConstantPatterns<'c> {
+    fn fold_term(&mut self, t: Term) -> Term {
+        let constant = match t.value() {
+            Value::Pattern(Pattern::Instance(InstanceLiteral { tag, fields }))
+                if fields.fields.is_empty() =>
+            {
+                self.constants.get(tag)
+            }
+            Value::Variable(name) if self.depth > 0 => self.constants.get(name),
+            _ => None,
+        };
+        match constant {
+            Some(value) => t.clone_with_value(literal_pattern(value).value().clone()),
+            None => fold_term(t, self),
+        }
+    }
+
+    fn fold_pattern(&mut self, p: Pattern) -> Pattern {
+        self.depth += 1;
+        let p = fold_pattern(p, self);
+        self.depth -= 1;
+        p
+    }
+}
+
+/// The roles, permissions and relations declared in a resource block.
+#[derive(Default)]
+struct BlockDeclarations {
+    roles: Vec<String>,
+    permissions: Vec<String>,
+    /// Map from each relation to the class of the related resource, if the block declares its
+    /// relations.
+    relations: Option<HashMap<String, Symbol>>,
+}
+
+impl BlockDeclarations {
+    /// Return the rule that holds if an actor has `name` on a resource: `has_role` for a role,
+    /// or `has_permission` for a permission.
+    fn hook(&self, name: &str) -> Option<&'static str> {
+        if self.roles.iter().any(|role| role == name) {
+            Some("has_role")
+        } else if self.permissions.iter().any(|permission| permission == name) {
+            Some("has_permission")
+        } else {
+            None
+        }
+    }
+}
+
+fn block_error(term: &Term, msg: String) -> PolarError {
+    ParseError::ResourceBlock {
+        loc: term.offset(),
+        msg,
+    }
+    .into()
+}
+
+fn string_list(name: &str, value: &Term) -> PolarResult<Vec<String>> {
+    let strings = match value.value() {
+        Value::List(list) => list
+            .iter()
+            .map(|element| element.value().as_string().ok().map(str::to_owned))
+            .collect(),
+        _ => None,
+    };
+    strings.ok_or_else(|| {
+        let msg = format!(
+            "Expected {} to be a list of strings, found {}",
+            name,
+            value.to_polar()
+        );
+        block_error(value, msg)
+    })
+}
+
+fn relation_classes(value: &Term) -> PolarResult<HashMap<String, Symbol>> {
+    let classes = match value.value() {
+        Value::Dictionary(Dictionary { fields }) => fields
+            .iter()
+            .map(|(relation, class)| match class.value() {
+                Value::Variable(class) => Some((relation.0.clone(), class.clone())),
+                _ => None,
+            })
+            .collect(),
+        _ => None,
+    };
+    classes.ok_or_else(|| {
+        let msg = format!(
+            "Expected relations to be a dictionary from relations to classes, e.g., \
+             {{parent: Org}}, found {}",
+            value.to_polar()
+        );
+        block_error(value, msg)
+    })
+}
+
+fn block_declarations(block: &ResourceBlock) -> PolarResult<BlockDeclarations> {
+    let mut declarations = BlockDeclarations::default();
+    let mut declared = vec![];
+    for item in &block.items {
+        if let BlockItem::Declaration { name, value } = item {
+            let name_str = name.value().as_symbol()?.0.as_str();
+            if declared.contains(&name_str) {
+                let msg = format!(
+                    "{} is declared more than once in resource block {}",
+                    name_str,
+                    block.resource.to_polar()
+                );
+                return Err(block_error(name, msg));
+            }
+            declared.push(name_str);
+            match name_str {
+                "roles" => declarations.roles = string_list(name_str, value)?,
+                "permissions" => declarations.permissions = string_list(name_str, value)?,
+                "relations" => declarations.relations = Some(relation_classes(value)?),
+                _ => {
+                    let msg = format!(
+                        "Unknown declaration {} in resource block {}; expected roles, \
+                         permissions or relations",
+                        name_str,
+                        block.resource.to_polar()
+                    );
+                    return Err(block_error(name, msg));
+                }
+            }
+        }
+    }
+    if let Some(both) = declarations
+        .roles
+        .iter()
+        .find(|role| declarations.permissions.contains(role))
+    {
+        let msg = format!(
+            "\"{}\" is declared as both a role and a permission in resource block {}",
+            both,
+            block.resource.to_polar()
+        );
+        return Err(block_error(&block.resource, msg));
+    }
+    Ok(declarations)
+}
+
+/// Desugar a shorthand rule of the resource block of class `resource`, e.g., `"read" if
+/// "reader"` into
+///
+/// ```polar
+/// has_permission(actor, "read", resource: Repo) if has_role(actor, "reader", resource);
+/// ```
+///
+/// and `"reader" if "member" on "parent"` into
+///
+/// ```polar
+/// has_role(actor, "reader", resource: Repo) if
+///     has_relation(related, "parent", resource) and has_role(actor, "member", related);
+/// ```
+fn desugar_shorthand_rule(
+    resource: &Symbol,
+    blocks: &HashMap<Symbol, BlockDeclarations>,
+    head: &Term,
+    implier: &Term,
+    relation: Option<&Term>,
+) -> PolarResult<Rule> {
+    let declarations = &blocks[resource];
+    let undeclared = |term: &Term, class: &Symbol| {
+        let msg = format!(
+            "Undeclared role or permission {} in resource block {}",
+            term.to_polar(),
+            class
+        );
+        block_error(term, msg)
+    };
+    let head_name = head.value().as_string()?;
+    let head_hook = declarations
+        .hook(head_name)
+        .ok_or_else(|| undeclared(head, resource))?;
+
+    let var = |name: &str| head.clone_with_value(Value::Variable(Symbol::new(name)));
+    let mut body = vec![];
+    let implier_name = implier.value().as_string()?;
+    let implier_hook = match relation {
+        None => declarations
+            .hook(implier_name)
+            .ok_or_else(|| undeclared(implier, resource))?,
+        Some(relation) => {
+            let relation_name = relation.value().as_string()?;
+            // Without a relations declaration, the class of the related resource is unknown.
+            let related = match &declarations.relations {
+                Some(relations) => Some(relations.get(relation_name).ok_or_else(|| {
+                    let msg = format!(
+                        "Undeclared relation {} in resource block {}",
+                        relation.to_polar(),
+                        resource
+                    );
+                    block_error(relation, msg)
+                })?),
+                None => None,
+            };
+            let call = Call {
+                name: Symbol::new("has_relation"),
+                args: vec![var("related"), relation.clone(), var("resource")],
+                kwargs: None,
+            };
+            body.push(relation.clone_with_value(Value::Call(call)));
+            // The roles and permissions of a resource of unknown class, or without a block in
+            // the same source, are unknown, so the implier is assumed to be a role.
+            match related.and_then(|related| Some((related, blocks.get(related)?))) {
+                Some((related, related_declarations)) => related_declarations
+                    .hook(implier_name)
+                    .ok_or_else(|| undeclared(implier, related))?,
+                None => "has_role",
+            }
+        }
+    };
+    if head_hook == "has_role" && implier_hook == "has_permission" {
+        let msg = format!(
+            "Role {} cannot be implied by permission {}",
+            head.to_polar(),
+            implier.to_polar()
+        );
+        return Err(block_error(implier, msg));
+    }
+    let on = if relation.is_some() {
+        "related"
+    } else {
+        "resource"
+    };
+    let call = Call {
+        name: Symbol::new(implier_hook),
+        args: vec![var("actor"), implier.clone(), var(on)],
+        kwargs: None,
+    };
+    body.push(implier.clone_with_value(Value::Call(call)));
+
+    let specializer = InstanceLiteral {
+        tag: resource.clone(),
+        fields: Dictionary {
+            fields: BTreeMap::new(),
+        },
+    };
+    let params = vec![
+        Parameter {
+            parameter: var("actor"),
+            specializer: None,
+        },
+        Parameter {
+            parameter: head.clone(),
+            specializer: None,
+        },
+        Parameter {
+            parameter: var("resource"),
+            specializer: Some(
+                head.clone_with_value(Value::Pattern(Pattern::Instance(specializer))),
+            ),
+        },
+    ];
+    let body = head.clone_with_value(Value::Expression(Operation {
+        operator: Operator::And,
+        args: body,
+    }));
+    Ok(Rule {
+        name: Symbol::new(head_hook),
+        params,
+        body,
+        doc: None,
+        annotations: vec![],
+    })
+}
+
+/// Desugar the resource blocks among `lines` into the rules their shorthand rules stand for,
+/// which call the `has_role`, `has_permission` and `has_relation` rules of the policy.
+pub fn rewrite_resource_blocks(lines: Vec<Line>) -> PolarResult<Vec<Line>> {
+    let mut blocks = HashMap::new();
+    for line in &lines {
+        if let Line::ResourceBlock(block) = line {
+            let resource = block.resource.value().as_symbol()?;
+            if blocks.contains_key(resource) {
+                let msg = format!("Resource block {} is defined more than once", resource);
+                return Err(block_error(&block.resource, msg));
+            }
+            blocks.insert(resource.clone(), block_declarations(block)?);
+        }
+    }
+
+    let mut rewritten = vec![];
+    for line in lines {
+        let block = match line {
+            Line::ResourceBlock(block) => block,
+            line => {
+                rewritten.push(line);
+                continue;
+            }
+        };
+        let resource = block.resource.value().as_symbol()?;
+        for item in &block.items {
+            if let BlockItem::ShorthandRule {
+                head,
+                implier,
+                relation,
+            } = item
+            {
+                let rule =
+                    desugar_shorthand_rule(resource, &blocks, head, implier, relation.as_ref())?;
+                rewritten.push(Line::Rule(rule));
+            }
+        }
+    }
+    Ok(rewritten)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -233,6 +629,16 @@ mod tests {
         );
     }
 
+    #[test]
+    fn rewrite_aggregates() {
+        let mut kb = KnowledgeBase::new();
+        let query = parse_query("findall(x.name, x in xs.items, names)");
+        assert_eq!(
+            rewrite_term(query, &mut kb).to_polar(),
+            "findall(_value_2, xs.items = _value_1 and x in _value_1 and x.name = _value_2, names)"
+        );
+    }
+
     #[test]
     fn rewrite_rules() {
         let mut kb = KnowledgeBase::new();
@@ -326,11 +732,11 @@ mod tests {
             "0 - 0 = _op_1 and _op_1 = 0"
         );
 
-        let rules = parse_rules("sum(a, b, a + b);");
+        let rules = parse_rules("add(a, b, a + b);");
         let rule = rules[0].clone();
-        assert_eq!(rule.to_polar(), "sum(a, b, a + b);");
+        assert_eq!(rule.to_polar(), "add(a, b, a + b);");
         let rule = rewrite_rule(rule, &mut kb);
-        assert_eq!(rule.to_polar(), "sum(a, b, _op_2) if a + b = _op_2;");
+        assert_eq!(rule.to_polar(), "add(a, b, _op_2) if a + b = _op_2;");
 
         let rules = parse_rules("fib(n, a+b) if fib(n-1, a) and fib(n-2, b);");
         let rule = rules[0].clone();
@@ -408,4 +814,123 @@ mod tests {
             "not (foo.x = _value_1 and _value_1 = 1)"
         )
     }
+
+    fn rewrite_blocks(src: &str) -> PolarResult<Vec<String>> {
+        let lines = crate::parser::parse_lines(0, src).unwrap();
+        Ok(rewrite_resource_blocks(lines)?
+            .into_iter()
+            .map(|line| match line {
+                Line::Rule(rule) => rule.to_polar(),
+                line => panic!("expected a rule, found {:?}", line),
+            })
+            .collect())
+    }
+
+    #[test]
+    fn rewrite_resource_block_rules() {
+        let rules = rewrite_blocks(
+            r#"resource Org { roles = ["member"]; }
+               resource Repo {
+                   roles = ["reader", "maintainer"];
+                   permissions = ["read", "push"];
+                   relations = {parent: Org};
+                   "read" if "reader";
+                   "read" if "push";
+                   "reader" if "maintainer";
+                   "reader" if "member" on "parent";
+               }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            rules,
+            vec![
+                "has_permission(actor, \"read\", resource: Repo) if \
+                 has_role(actor, \"reader\", resource);",
+                "has_permission(actor, \"read\", resource: Repo) if \
+                 has_permission(actor, \"push\", resource);",
+                "has_role(actor, \"reader\", resource: Repo) if \
+                 has_role(actor, \"maintainer\", resource);",
+                "has_role(actor, \"reader\", resource: Repo) if \
+                 has_relation(related, \"parent\", resource) and \
+                 has_role(actor, \"member\", related);",
+            ]
+        );
+    }
+
+    #[test]
+    fn rewrite_resource_block_without_relations() {
+        let rules = rewrite_blocks(
+            r#"resource Repo {
+                   roles = ["reader", "maintainer"];
+                   permissions = ["read", "push"];
+                   "read" if "reader";
+                   "reader" if "maintainer";
+                   "reader" if "member" on "parent";
+               }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            rules,
+            vec![
+                "has_permission(actor, \"read\", resource: Repo) if \
+                 has_role(actor, \"reader\", resource);",
+                "has_role(actor, \"reader\", resource: Repo) if \
+                 has_role(actor, \"maintainer\", resource);",
+                "has_role(actor, \"reader\", resource: Repo) if \
+                 has_relation(related, \"parent\", resource) and \
+                 has_role(actor, \"member\", related);",
+            ]
+        );
+    }
+
+    #[test]
+    fn rewrite_invalid_resource_blocks() {
+        let error = |src: &str| rewrite_blocks(src).expect_err("invalid block").to_string();
+        assert_eq!(
+            error(r#"resource Repo { roles = ["reader"]; "read" if "reader"; }"#),
+            "Undeclared role or permission \"read\" in resource block Repo"
+        );
+        assert_eq!(
+            error(r#"resource Repo { roles = ["reader"]; "reader" if "owner"; }"#),
+            "Undeclared role or permission \"owner\" in resource block Repo"
+        );
+        assert_eq!(
+            error(
+                r#"resource Repo {
+                       roles = ["reader"]; permissions = ["read"]; "reader" if "read";
+                   }"#
+            ),
+            "Role \"reader\" cannot be implied by permission \"read\""
+        );
+        assert_eq!(
+            error(
+                r#"resource Repo {
+                       roles = ["reader"];
+                       relations = {parent: Org};
+                       "reader" if "member" on "org";
+                   }"#
+            ),
+            "Undeclared relation \"org\" in resource block Repo"
+        );
+        assert_eq!(
+            error(
+                r#"resource Org { roles = ["member"]; }
+                   resource Repo {
+                       roles = ["reader"];
+                       relations = {parent: Org};
+                       "reader" if "admin" on "parent";
+                   }"#
+            ),
+            "Undeclared role or permission \"admin\" in resource block Org"
+        );
+        assert_eq!(
+            error(r#"resource Repo { role = ["reader"]; }"#),
+            "Unknown declaration role in resource block Repo; expected roles, permissions or \
+             relations"
+        );
+        assert_eq!(
+            error(r#"resource Repo { roles = "reader"; }"#),
+            "Expected roles to be a list of strings, found \"reader\""
+        );
+    }
 }
@@ -0,0 +1,373 @@
+//! Rule type declarations, e.g., `type allow(actor: User, action: String, resource: Document);`.
+//!
+//! Once a type is declared for a rule, each definition of the rule and each call to it must
+//! match one of the rule's declared types: it must have as many parameters (or arguments), and
+//! none of them may be of a type that can never match the declared specializer. Application
+//! classes may be subclasses of each other, which is only known at runtime, so two different
+//! application classes are assumed to be compatible.
+
+use std::collections::HashMap;
+
+use super::error::{PolarError, PolarResult, RuntimeError};
+use super::formatting::{format_args, format_params, ToPolarString};
+use super::kb::KnowledgeBase;
+use super::numerics::Numeric;
+use super::parser::Line;
+use super::rules::{Parameter, Rule};
+use super::terms::*;
+use super::warnings::goal_calls;
+
+/// Classes whose instances are Polar values, rather than application instances.
+const BUILTIN_CLASSES: &[&str] = &[
+    "Boolean",
+    "Decimal",
+    "Dictionary",
+    "Duration",
+    "Float",
+    "Integer",
+    "List",
+    "Set",
+    "String",
+    "Timestamp",
+];
+
+#[derive(Clone, Default)]
+pub struct RuleTypes {
+    /// Map from each rule to the types declared for it, as rules without bodies.
+    types: HashMap<Symbol, Vec<Rule>>,
+}
+
+/// What is known statically about the values a parameter or argument can take.
+#[derive(Clone, Debug, PartialEq)]
+enum Shape {
+    Any,
+    /// An instance of a class, or one of its subclasses.
+    Instance(Symbol),
+    /// A dictionary, or an instance with the fields of a dictionary pattern.
+    Fields,
+    /// A literal of a builtin class.
+    Literal(&'static str),
+}
+
+impl Shape {
+    fn of_value(term: &Term) -> Self {
+        match term.value() {
+            Value::Number(Numeric::Integer(_)) => Self::Literal("Integer"),
+            Value::Number(Numeric::Float(_)) => Self::Literal("Float"),
+            Value::Number(Numeric::Decimal(_)) => Self::Literal("Decimal"),
+            Value::String(_) => Self::Literal("String"),
+            Value::Boolean(_) => Self::Literal("Boolean"),
+            Value::List(_) => Self::Literal("List"),
+            Value::Set(_) => Self::Literal("Set"),
+            Value::Timestamp(_) => Self::Literal("Timestamp"),
+            Value::Duration(_) => Self::Literal("Duration"),
+            Value::Dictionary(_) => Self::Literal("Dictionary"),
+            Value::Expression(Operation {
+                operator: Operator::New,
+                args,
+            }) => match args.first().map(Term::value) {
+                Some(Value::Call(constructor)) => Self::Instance(constructor.name.clone()),
+                _ => Self::Any,
+            },
+            _ => Self::Any,
+        }
+    }
+
+    fn of_specializer(specializer: &Term) -> Self {
+        match specializer.value() {
+            Value::Pattern(Pattern::Instance(InstanceLiteral { tag, .. })) => {
+                Self::Instance(tag.clone())
+            }
+            Value::Pattern(Pattern::Dictionary(_)) => Self::Fields,
+            _ => Self::Any,
+        }
+    }
+
+    fn of_parameter(param: &Parameter) -> Self {
+        match &param.specializer {
+            Some(specializer) => Self::of_specializer(specializer),
+            None => Self::of_value(&param.parameter),
+        }
+    }
+
+    /// Return false if no value of this shape can match a parameter of the declared shape.
+    fn can_match(&self, declared: &Shape) -> bool {
+        let is_builtin = |class: &Symbol| BUILTIN_CLASSES.contains(&class.0.as_str());
+        match (self, declared) {
+            (Self::Any, _) | (_, Self::Any) => true,
+            (Self::Literal(class), Self::Instance(declared)) => declared.0 == *class,
+            (Self::Instance(class), Self::Instance(declared)) => {
+                class == declared || !(is_builtin(class) || is_builtin(declared))
+            }
+            (Self::Fields, Self::Instance(class)) | (Self::Instance(class), Self::Fields) => {
+                class.0 == "Dictionary" || !is_builtin(class)
+            }
+            (Self::Literal(class), Self::Fields) => *class == "Dictionary",
+            (Self::Fields, Self::Fields) => true,
+            (_, Self::Literal(_)) => unreachable!("declared parameters are variables"),
+        }
+    }
+}
+
+impl RuleTypes {
+    /// Declare a type for a rule. Its parameters must be variables, optionally specialized.
+    pub fn add(&mut self, kb: &KnowledgeBase, rule_type: Rule) -> PolarResult<()> {
+        for param in &rule_type.params {
+            if !matches!(param.parameter.value(), Value::Variable(_)) {
+                let msg = format!(
+                    "Parameters of a type declaration must be variables, found {}",
+                    param.parameter.to_polar()
+                );
+                return Err(type_error(kb, msg, &param.parameter));
+            }
+        }
+        self.types
+            .entry(rule_type.name.clone())
+            .or_default()
+            .push(rule_type);
+        Ok(())
+    }
+
+    /// Whether a type is declared for the rule `name`.
+    pub fn is_declared(&self, name: &Symbol) -> bool {
+        self.types.contains_key(name)
+    }
+
+    /// Check the rules and queries in `lines`, and the rules already in the knowledge base,
+    /// against the declared types.
+    pub fn check_lines(&self, kb: &KnowledgeBase, lines: &[Line]) -> PolarResult<()> {
+        if self.types.is_empty() {
+            return Ok(());
+        }
+        for line in lines {
+            match line {
+                Line::Rule(rule) => self.check_rule(kb, rule)?,
+                Line::Query(query) => self.check_calls(kb, query)?,
+                _ => {}
+            }
+        }
+        for generic_rule in kb.rules.values() {
+            for rule in generic_rule.rules() {
+                self.check_rule(kb, &rule)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn declared(&self, name: &Symbol) -> Option<&[Rule]> {
+        self.types.get(name).map(Vec::as_slice)
+    }
+
+    /// Check a rule definition, and the calls in its body.
+    pub fn check_rule(&self, kb: &KnowledgeBase, rule: &Rule) -> PolarResult<()> {
+        if let Some(types) = self.declared(&rule.name) {
+            let shapes = rule.params.iter().map(Shape::of_parameter).collect();
+            let terms = rule.params.iter().map(|p| &p.parameter).collect::<Vec<_>>();
+            let found = format!("Rule {}({})", rule.name, format_params(&rule.params, ", "));
+            let fallback = rule.params.first().map_or(&rule.body, |p| &p.parameter);
+            check_shapes(kb, types, shapes, &terms, fallback, &found, "parameter")?;
+        }
+        self.check_calls(kb, &rule.body)
+    }
+
+    /// Check the calls in a rule body or query.
+    pub fn check_calls(&self, kb: &KnowledgeBase, body: &Term) -> PolarResult<()> {
+        for term in goal_calls(body) {
+            let call = term.value().as_call().unwrap();
+            if let Some(types) = self.declared(&call.name) {
+                let shapes = call.args.iter().map(Shape::of_value).collect();
+                let terms = call.args.iter().collect::<Vec<_>>();
+                let found = format!(
+                    "Call {}({})",
+                    call.name,
+                    format_args(Operator::And, &call.args, ", ")
+                );
+                check_shapes(kb, types, shapes, &terms, &term, &found, "argument")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Check that the shapes of the parameters or arguments of a definition or call match one of
+/// the declared `types`. Errors point at the first mismatched term, or at `fallback` if the
+/// arity is wrong.
+fn check_shapes(
+    kb: &KnowledgeBase,
+    types: &[Rule],
+    shapes: Vec<Shape>,
+    terms: &[&Term],
+    fallback: &Term,
+    found: &str,
+    noun: &str,
+) -> PolarResult<()> {
+    let candidates = types
+        .iter()
+        .filter(|rule_type| rule_type.params.len() == shapes.len())
+        .collect::<Vec<_>>();
+    if candidates.is_empty() {
+        let mut arities = types.iter().map(|t| t.params.len()).collect::<Vec<_>>();
+        arities.sort_unstable();
+        arities.dedup();
+        let arities = arities
+            .iter()
+            .map(|n| n.to_string())
+            .collect::<Vec<_>>()
+            .join(" or ");
+        let msg = format!(
+            "{} has {} {}s, but {} is declared with {}",
+            found,
+            shapes.len(),
+            noun,
+            types[0].name,
+            arities
+        );
+        return Err(type_error(kb, msg, fallback));
+    }
+
+    let mut mismatch = None;
+    for rule_type in &candidates {
+        let position = rule_type
+            .params
+            .iter()
+            .zip(&shapes)
+            .position(|(declared, shape)| {
+                let declared = declared
+                    .specializer
+                    .as_ref()
+                    .map_or(Shape::Any, Shape::of_specializer);
+                !shape.can_match(&declared)
+            });
+        match position {
+            None => return Ok(()),
+            Some(i) => {
+                mismatch.get_or_insert((rule_type, i));
+            }
+        }
+    }
+    let (rule_type, i) = mismatch.unwrap();
+    let msg = format!(
+        "{} does not match type {}({}): {} {} can never match {}",
+        found,
+        rule_type.name,
+        format_params(&rule_type.params, ", "),
+        noun,
+        i + 1,
+        rule_type.params[i].to_polar()
+    );
+    Err(type_error(kb, msg, terms[i]))
+}
+
+/// Make a type error, pointing at `term` in its source.
+fn type_error(kb: &KnowledgeBase, msg: String, term: &Term) -> PolarError {
+    let source = term
+        .get_source_id()
+        .and_then(|id| kb.sources.get_source(id));
+    PolarError::from(RuntimeError::TypeError {
+        msg,
+        stack_trace: None,
+    })
+    .set_context(source.as_ref(), Some(term))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::{ErrorKind, PolarError};
+    use crate::polar::Polar;
+
+    const TYPES: &str = r#"
+type allow(actor: User, action: String, resource: Document);
+type allow(actor: User, action: String, resource: Folder);
+type has_role(actor: User, role: String);
+"#;
+
+    fn type_error(err: PolarError) -> String {
+        assert!(matches!(err.kind, ErrorKind::Runtime(_)), "{}", err);
+        err.to_string()
+    }
+
+    #[test]
+    fn test_rule_definitions() {
+        let polar = Polar::new();
+        polar.load_str(TYPES).unwrap();
+        polar
+            .load_str(
+                r#"allow(actor: User, "read", doc: Document) if has_role(actor, "reader");
+                   allow(actor, action, folder: Folder{public: true}) if action = "read";
+                   has_role(actor: User, role) if role in actor.roles;"#,
+            )
+            .unwrap();
+
+        // The arguments are out of order.
+        let err = polar
+            .load_str("allow(_user: User, _resource: Document, _action: String);")
+            .unwrap_err();
+        assert_eq!(
+            type_error(err),
+            "Type error: Rule allow(_user: User, _resource: Document, _action: String) does not \
+             match type allow(actor: User, action: String, resource: Document): parameter 2 can \
+             never match action: String at line 1, column 20"
+        );
+
+        let err = polar.load_str("allow(_actor, \"read\");").unwrap_err();
+        assert_eq!(
+            type_error(err),
+            "Type error: Rule allow(_actor, \"read\") has 2 parameters, but allow is declared \
+             with 3 at line 1, column 7"
+        );
+
+        let err = polar.load_str("has_role(_actor, 1);").unwrap_err();
+        assert!(type_error(err).contains("parameter 2 can never match role: String"));
+
+        // Application classes may be related, so only builtin classes are known not to match.
+        polar
+            .load_str("has_role(_actor: Admin, _role: String);")
+            .unwrap();
+        let err = polar
+            .load_str("has_role(_actor: Dictionary, _role);")
+            .unwrap_err();
+        assert!(type_error(err).contains("parameter 1 can never match actor: User"));
+    }
+
+    #[test]
+    fn test_rule_calls() {
+        let polar = Polar::new();
+        polar.load_str(TYPES).unwrap();
+        polar
+            .load_str(
+                "f(actor, doc) if allow(actor, \"read\", doc) and has_role(new User(), \"a\");",
+            )
+            .unwrap();
+
+        let err = polar
+            .load_str("f(actor, doc) if allow(actor, doc, \"read\");")
+            .unwrap_err();
+        assert_eq!(
+            type_error(err),
+            "Type error: Call allow(actor, doc, \"read\") does not match type allow(actor: User, \
+             action: String, resource: Document): argument 3 can never match resource: Document \
+             at line 1, column 36"
+        );
+
+        let err = polar.load_str("?= has_role(1, \"a\", 2);").unwrap_err();
+        assert!(type_error(err).contains("Call has_role(1, \"a\", 2) has 3 arguments"));
+    }
+
+    #[test]
+    fn test_declarations_check_loaded_rules() {
+        let polar = Polar::new();
+        polar.load_str("g(x) if x = 1;\nf(1);").unwrap();
+        let err = polar.load_str("type f(x: String);").unwrap_err();
+        assert!(type_error(err).contains("Rule f(1) does not match type f(x: String)"));
+        // Nothing from the failed load was added.
+        polar.load_str("f(2);").unwrap();
+
+        let err = polar.load_str("type f(1);").unwrap_err();
+        assert!(
+            type_error(err).contains("Parameters of a type declaration must be variables, found 1")
+        );
+
+        polar.clear_rules();
+        polar.load_str("type f(x: String);\nf(\"a\");").unwrap();
+    }
+}
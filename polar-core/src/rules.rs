@@ -3,6 +3,7 @@ use std::collections::{BTreeSet, HashMap};
 use std::sync::Arc;
 
 use super::terms::*;
+use crate::tabling::TABLE_ANNOTATION;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Parameter {
@@ -21,6 +22,21 @@ pub struct Rule {
     pub name: Symbol,
     pub params: Vec<Parameter>,
     pub body: Term,
+    /// Doc comment written before the rule, without its `##`s.
+    pub doc: Option<String>,
+    /// Annotations written before the rule's head, in order.
+    #[serde(default)]
+    pub annotations: Vec<Annotation>,
+}
+
+/// An annotation on a rule, e.g., `@owner("billing-team")`. Annotations do not change what a
+/// rule means; they are metadata for the host, e.g., to route audit events. The exception is
+/// `@table`, which changes how calls to the rule are evaluated; see `tabling`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Annotation {
+    pub name: Symbol,
+    /// Literal arguments, e.g., `"billing-team"`.
+    pub args: Vec<Term>,
 }
 
 impl Rule {
@@ -29,6 +45,39 @@ impl Rule {
     }
 }
 
+/// A resource block, e.g., `resource Repo { roles = ["reader"]; "read" if "reader"; }`. It is
+/// desugared into rules before it is loaded.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ResourceBlock {
+    /// The resource class, as a variable.
+    pub resource: Term,
+    pub items: Vec<BlockItem>,
+}
+
+/// A constant defined in a policy, e.g., `const ADMIN_ROLES = ["owner", "admin"];`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Constant {
+    /// The name of the constant, as a variable.
+    pub name: Term,
+    /// A literal value.
+    pub value: Term,
+    /// Doc comment written before the constant, without its `##`s.
+    pub doc: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum BlockItem {
+    /// A declaration of the block's roles, permissions or relations, e.g.,
+    /// `roles = ["reader", "maintainer"]`.
+    Declaration { name: Term, value: Term },
+    /// A shorthand rule, e.g., `"read" if "reader"` or `"reader" if "member" on "parent"`.
+    ShorthandRule {
+        head: Term,
+        implier: Term,
+        relation: Option<Term>,
+    },
+}
+
 pub type Rules = Vec<Arc<Rule>>;
 
 type RuleSet = BTreeSet<u64>;
@@ -97,6 +146,8 @@ impl RuleIndex {
 #[derive(Clone)]
 pub struct GenericRule {
     pub name: Symbol,
+    /// Whether calls are tabled, i.e., whether any rule is annotated with `@table`.
+    pub tabled: bool,
     rules: HashMap<u64, Arc<Rule>>,
     index: RuleIndex,
     next_rule_id: u64,
@@ -106,6 +157,7 @@ impl GenericRule {
     pub fn new(name: Symbol, rules: Rules) -> Self {
         let mut generic_rule = Self {
             name,
+            tabled: false,
             rules: Default::default(),
             index: Default::default(),
             next_rule_id: 0,
@@ -120,6 +172,10 @@ impl GenericRule {
 
     pub fn add_rule(&mut self, rule: Arc<Rule>) {
         let rule_id = self.next_rule_id();
+        self.tabled |= rule
+            .annotations
+            .iter()
+            .any(|annotation| annotation.name.0 == TABLE_ANNOTATION);
 
         assert!(
             self.rules.insert(rule_id, rule.clone()).is_none(),
@@ -128,6 +184,13 @@ impl GenericRule {
         self.index.index_rule(rule_id, &rule.params[..], 0);
     }
 
+    /// Return the rules in the order they were added.
+    pub fn rules(&self) -> Rules {
+        let mut rules = self.rules.iter().collect::<Vec<_>>();
+        rules.sort_by_key(|(id, _)| **id);
+        rules.into_iter().map(|(_, rule)| rule.clone()).collect()
+    }
+
     #[allow(clippy::ptr_arg)]
     pub fn get_applicable_rules(&self, args: &TermList) -> Rules {
         self.index
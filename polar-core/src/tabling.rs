@@ -0,0 +1,117 @@
+//! Tabling for rules annotated with `@table`.
+//!
+//! The answers to each call of a tabled rule are computed once per query and memoized, keyed
+//! by the call's arguments up to the names of their variables. A call made while its own
+//! answers are being computed, e.g., by a left-recursive rule, uses the answers found so far
+//! instead of recursing, and the call is evaluated again until there are no new answers.
+
+use std::collections::HashMap;
+
+use crate::folder::Folder;
+use crate::terms::{Symbol, Term};
+
+/// The name of the annotation that makes a rule tabled.
+pub const TABLE_ANNOTATION: &str = "table";
+
+/// Rename variables in the order they appear, so that terms which differ only in the names of
+/// their variables are equal.
+struct Canonicalizer {
+    renames: HashMap<Symbol, Symbol>,
+}
+
+impl Folder for Canonicalizer {
+    fn fold_variable(&mut self, v: Symbol) -> Symbol {
+        let next = Symbol(format!("_{}", self.renames.len()));
+        self.renames.entry(v).or_insert(next).clone()
+    }
+
+    fn fold_rest_variable(&mut self, v: Symbol) -> Symbol {
+        self.fold_variable(v)
+    }
+}
+
+/// Return the canonical variant of `term`.
+pub fn variant(term: Term) -> Term {
+    Canonicalizer {
+        renames: HashMap::new(),
+    }
+    .fold_term(term)
+}
+
+#[derive(Default)]
+struct Table {
+    /// Answers so far, as lists of the call's arguments.
+    answers: Vec<Term>,
+
+    /// Whether all answers have been found.
+    complete: bool,
+
+    /// Whether the answers were used while they were being computed.
+    recursive: bool,
+
+    /// Whether computing the answers used those of an earlier call that was still in progress.
+    /// Such answers may be missing some that the earlier call has not found yet, so the table
+    /// is dropped rather than completed once the call has been answered.
+    dependent: bool,
+}
+
+/// The tables of a query, shared by the VM and its sub-VMs.
+#[derive(Default)]
+pub struct Tables {
+    tables: HashMap<Term, Table>,
+
+    /// Calls whose answers are being computed, outermost first.
+    in_progress: Vec<Term>,
+}
+
+impl Tables {
+    /// Return the answers to `call` so far, or `None` if it has not been called before, in
+    /// which case its answers must be computed and passed to `add_answers`.
+    pub fn lookup(&mut self, call: &Term) -> Option<Vec<Term>> {
+        let complete = self.tables.get(call).map(|table| table.complete);
+        match complete {
+            None => {
+                self.tables.insert(call.clone(), Table::default());
+                self.in_progress.push(call.clone());
+                None
+            }
+            Some(true) => Some(self.tables[call].answers.clone()),
+            Some(false) => {
+                // The call is recursive. Every call in progress since depends on it.
+                let index = self.in_progress.iter().position(|c| c == call).unwrap();
+                for later in &self.in_progress[index + 1..] {
+                    self.tables.get_mut(later).unwrap().dependent = true;
+                }
+                let table = self.tables.get_mut(call).unwrap();
+                table.recursive = true;
+                Some(table.answers.clone())
+            }
+        }
+    }
+
+    /// Add the answers found by evaluating `call`. Return `true` if the call must be evaluated
+    /// again because it is recursive and found new answers.
+    pub fn add_answers(&mut self, call: &Term, answers: Vec<Term>) -> bool {
+        let table = self.tables.get_mut(call).unwrap();
+        let mut new = false;
+        for answer in answers.into_iter().map(variant) {
+            if !table.answers.contains(&answer) {
+                table.answers.push(answer);
+                new = true;
+            }
+        }
+        new && table.recursive
+    }
+
+    /// Finish computing the answers to `call`, and return them.
+    pub fn finish(&mut self, call: &Term) -> Vec<Term> {
+        assert_eq!(self.in_progress.pop().as_ref(), Some(call));
+        let table = self.tables.get_mut(call).unwrap();
+        if table.dependent {
+            self.tables.remove(call).unwrap().answers
+        } else {
+            table.complete = true;
+            table.answers.clone()
+        }
+    }
+}
@@ -1,12 +1,14 @@
 use super::sources::SourceInfo;
 pub use super::{error, formatting::ToPolarString};
 use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
 use std::collections::hash_map::DefaultHasher;
 use std::collections::{BTreeMap, HashSet};
 use std::hash::{Hash, Hasher};
 use std::sync::Arc;
 
-pub use super::numerics::Numeric;
+pub use super::datetime::{Duration, Timestamp};
+pub use super::numerics::{Decimal, Numeric};
 use super::visitor::{walk_term, Visitor};
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default, Eq, PartialEq, Hash)]
@@ -56,6 +58,89 @@ pub fn has_rest_var(list: &TermList) -> bool {
     !list.is_empty() && matches!(list.last().unwrap().value(), Value::RestVariable(_))
 }
 
+/// A set of terms. The elements are kept in a canonical order without duplicates, so that
+/// equal sets have equal elements in the same order and hash the same.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, Eq, PartialEq, Hash)]
+#[serde(from = "TermList", into = "TermList")]
+pub struct Set {
+    elements: TermList,
+}
+
+impl Set {
+    pub fn new(mut elements: TermList) -> Self {
+        elements.sort_by(canonical_cmp);
+        elements.dedup();
+        Self { elements }
+    }
+
+    /// The elements of the set, in canonical order.
+    pub fn elements(&self) -> &TermList {
+        &self.elements
+    }
+
+    pub fn len(&self) -> usize {
+        self.elements.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.elements.is_empty()
+    }
+
+    pub fn contains(&self, term: &Term) -> bool {
+        self.elements
+            .binary_search_by(|e| canonical_cmp(e, term))
+            .is_ok()
+    }
+}
+
+impl From<TermList> for Set {
+    fn from(elements: TermList) -> Self {
+        Self::new(elements)
+    }
+}
+
+impl From<Set> for TermList {
+    fn from(set: Set) -> Self {
+        set.elements
+    }
+}
+
+/// The canonical order of the elements of a set: booleans, then numbers, strings, lists,
+/// sets, dictionaries and anything else, ordered by value within each kind.
+fn canonical_cmp(left: &Term, right: &Term) -> Ordering {
+    fn rank(value: &Value) -> u8 {
+        match value {
+            Value::Boolean(_) => 0,
+            Value::Number(_) => 1,
+            Value::String(_) => 2,
+            Value::List(_) => 3,
+            Value::Set(_) => 4,
+            Value::Dictionary(_) => 5,
+            _ => 6,
+        }
+    }
+
+    fn cmp_lists(left: &[Term], right: &[Term]) -> Ordering {
+        left.iter()
+            .zip(right)
+            .map(|(l, r)| canonical_cmp(l, r))
+            .find(|ord| *ord != Ordering::Equal)
+            .unwrap_or_else(|| left.len().cmp(&right.len()))
+    }
+
+    match (left.value(), right.value()) {
+        (Value::Boolean(l), Value::Boolean(r)) => l.cmp(r),
+        (Value::Number(l), Value::Number(r)) => l.partial_cmp(r).unwrap_or(Ordering::Equal),
+        (Value::String(l), Value::String(r)) => l.cmp(r),
+        (Value::List(l), Value::List(r)) => cmp_lists(l, r),
+        (Value::Set(l), Value::Set(r)) => cmp_lists(&l.elements, &r.elements),
+        _ if left == right => Ordering::Equal,
+        (l, r) => rank(l)
+            .cmp(&rank(r))
+            .then_with(|| l.to_polar().cmp(&r.to_polar())),
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub struct Symbol(pub String);
 
@@ -109,8 +194,34 @@ pub enum Operator {
     Unify,
     Or,
     And,
+    If,
     ForAll,
     Assign,
+    FindAll,
+    Count,
+    Sum,
+    Min,
+    Max,
+    RegexMatch,
+    Format,
+    Union,
+    Intersection,
+    Difference,
+    Subset,
+    Timestamp,
+    Duration,
+    Now,
+}
+
+impl Operator {
+    /// Whether this is an aggregate, e.g., `count(x, f(x), n)`. The second argument of an
+    /// aggregate is a goal, which is queried for each value of the first.
+    pub fn is_aggregate(self) -> bool {
+        matches!(
+            self,
+            Self::FindAll | Self::Count | Self::Sum | Self::Min | Self::Max
+        )
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq, Hash)]
@@ -136,6 +247,9 @@ pub enum Value {
     Pattern(Pattern),
     Call(Call),
     List(TermList),
+    Set(Set),
+    Timestamp(Timestamp),
+    Duration(Duration),
     Variable(Symbol),
     RestVariable(Symbol),
     Expression(Operation),
@@ -199,10 +313,15 @@ impl Value {
             | Value::ExternalInstance(_)
             | Value::Variable(_)
             | Value::RestVariable(_) => false,
-            Value::Number(_) | Value::String(_) | Value::Boolean(_) => true,
+            Value::Number(_)
+            | Value::String(_)
+            | Value::Boolean(_)
+            | Value::Timestamp(_)
+            | Value::Duration(_) => true,
             Value::Pattern(_) => panic!("unexpected value type"),
             Value::Dictionary(Dictionary { fields }) => fields.values().all(|t| t.is_ground()),
             Value::List(terms) => terms.iter().all(|t| t.is_ground()),
+            Value::Set(set) => set.elements().iter().all(|t| t.is_ground()),
             Value::Expression(Operation { operator: _, args }) => {
                 args.iter().all(|t| t.is_ground())
             }
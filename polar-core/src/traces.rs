@@ -1,6 +1,9 @@
 use super::rules::*;
 use super::terms::*;
-use serde::{Deserialize, Serialize};
+use regex::Regex;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Write;
 use std::rc::Rc;
 use std::sync::Arc;
 
@@ -30,4 +33,245 @@ impl Trace {
 pub struct TraceResult {
     pub trace: Rc<Trace>,
     pub formatted: String,
+    /// Exportable form of `trace`.
+    pub tree: TraceNode,
+    /// `tree` rendered as a Graphviz DOT graph.
+    pub dot: String,
+}
+
+/// What a node of an exported trace represents.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TraceNodeKind {
+    /// A rule that was applied.
+    Rule,
+    /// A query for a rule or predicate, e.g., `allow(actor, action, resource)`.
+    Call,
+    /// A builtin operation, e.g., `x = 1`, `x.name` or `x matches User`.
+    Operation,
+    /// Any other queried value.
+    Value,
+}
+
+/// Location of a node in the policy source. `row` and `column` are zero-based; `left` and
+/// `right` are byte offsets into the source.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TraceSpan {
+    pub filename: Option<String>,
+    pub row: usize,
+    pub column: usize,
+    pub left: usize,
+    pub right: usize,
+}
+
+/// A node of an exported trace tree.
+///
+/// Unlike `Trace`, this is self-contained: sources and bindings are resolved when the result is
+/// produced, so the tree can be serialized as JSON (e.g., across the FFI boundary) or rendered
+/// as a Graphviz graph with `to_dot`. Conjunctions are flattened into their parent, as they are
+/// in the formatted trace.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TraceNode {
+    pub kind: TraceNodeKind,
+    /// Name of the rule or called predicate.
+    pub name: Option<Symbol>,
+    /// Policy source of the node.
+    pub source: String,
+    pub span: Option<TraceSpan>,
+    /// Variables occurring in the node, and the values they were bound to in this result.
+    pub bindings: BTreeMap<Symbol, Term>,
+    /// Annotations of the rule, e.g., `@owner("billing-team")`. Empty for other nodes.
+    pub annotations: Vec<Annotation>,
+    pub children: Vec<TraceNode>,
+}
+
+/// Which nodes and bindings to drop from an exported trace. The children of a dropped node
+/// are kept, and take its place in the tree.
+///
+/// Hosts pass a filter as JSON, e.g., `{"builtins": true, "rules": "^_"}`; omitted options are
+/// off.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct TraceFilter {
+    /// Drop builtin operations.
+    pub builtins: bool,
+    /// Drop bindings of temporary variables.
+    pub temporaries: bool,
+    /// Drop rules (and calls to rules) whose name matches this pattern.
+    #[serde(deserialize_with = "deserialize_pattern")]
+    pub rules: Option<Regex>,
+}
+
+fn deserialize_pattern<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Regex>, D::Error> {
+    Option::<String>::deserialize(deserializer)?
+        .map(|pattern| Regex::new(&pattern))
+        .transpose()
+        .map_err(serde::de::Error::custom)
+}
+
+impl TraceFilter {
+    fn keeps(&self, node: &TraceNode) -> bool {
+        match node.kind {
+            TraceNodeKind::Operation => !self.builtins,
+            TraceNodeKind::Rule | TraceNodeKind::Call => match (&self.rules, &node.name) {
+                (Some(pattern), Some(name)) => !pattern.is_match(&name.0),
+                _ => true,
+            },
+            TraceNodeKind::Value => true,
+        }
+    }
+}
+
+impl TraceNode {
+    /// Return a copy of this tree without the nodes and bindings excluded by `filter`. The root
+    /// node is always kept.
+    pub fn filter(&self, filter: &TraceFilter) -> Self {
+        let mut node = self.clone();
+        if filter.temporaries {
+            node.bindings.retain(|var, _| !var.is_temporary_var());
+        }
+        node.children = self
+            .children
+            .iter()
+            .flat_map(|child| {
+                let child = child.filter(filter);
+                if filter.keeps(&child) {
+                    vec![child]
+                } else {
+                    child.children
+                }
+            })
+            .collect();
+        node
+    }
+
+    /// Serialize the tree as JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("trace serialization failed")
+    }
+
+    /// Render the tree as a Graphviz DOT digraph, with an edge from each node to its children.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph trace {\n");
+        self.write_dot(&mut dot, &mut 0);
+        dot.push_str("}\n");
+        dot
+    }
+
+    fn write_dot(&self, dot: &mut String, next_id: &mut usize) -> usize {
+        let id = *next_id;
+        *next_id += 1;
+
+        let mut label = self.source.clone();
+        for (var, value) in &self.bindings {
+            let _ = write!(label, "\n{} = {}", var, value.to_polar());
+        }
+        if let Some(span) = &self.span {
+            let _ = write!(label, "\nline {}, column {}", span.row + 1, span.column + 1);
+            if let Some(filename) = &span.filename {
+                let _ = write!(label, " in {}", filename);
+            }
+        }
+        let shape = match self.kind {
+            TraceNodeKind::Rule => "box",
+            TraceNodeKind::Call => "ellipse",
+            TraceNodeKind::Operation | TraceNodeKind::Value => "plaintext",
+        };
+        let _ = writeln!(
+            dot,
+            "  n{} [shape={}, label=\"{}\"];",
+            id,
+            shape,
+            escape_dot(&label)
+        );
+
+        for child in &self.children {
+            let child_id = child.write_dot(dot, next_id);
+            let _ = writeln!(dot, "  n{} -> n{};", id, child_id);
+        }
+        id
+    }
+}
+
+/// Escape a string for use in a quoted DOT label. Newlines become left-justified line breaks.
+fn escape_dot(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\l"),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push_str("\\l");
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(kind: TraceNodeKind, name: Option<&str>, children: Vec<TraceNode>) -> TraceNode {
+        TraceNode {
+            kind,
+            name: name.map(Symbol::new),
+            source: name.unwrap_or("x = 1").to_owned(),
+            span: None,
+            bindings: btreemap! {
+                sym!("x") => term!(1),
+                sym!("_x_1") => term!(1),
+            },
+            annotations: vec![],
+            children,
+        }
+    }
+
+    #[test]
+    fn test_filter_trace() {
+        let tree = node(
+            TraceNodeKind::Call,
+            Some("f"),
+            vec![node(
+                TraceNodeKind::Rule,
+                Some("f"),
+                vec![
+                    node(TraceNodeKind::Operation, None, vec![]),
+                    node(
+                        TraceNodeKind::Call,
+                        Some("_internal"),
+                        vec![node(TraceNodeKind::Rule, Some("g"), vec![])],
+                    ),
+                ],
+            )],
+        );
+
+        let filtered = tree.filter(&TraceFilter {
+            builtins: true,
+            temporaries: true,
+            rules: Some(Regex::new("^_").unwrap()),
+        });
+        assert_eq!(filtered.bindings.len(), 1);
+        let rule = &filtered.children[0];
+        assert_eq!(rule.children.len(), 1);
+        assert_eq!(rule.children[0].name, Some(sym!("g")));
+
+        let unfiltered = tree.filter(&TraceFilter::default());
+        assert_eq!(unfiltered, tree);
+    }
+
+    #[test]
+    fn test_trace_to_dot() {
+        let tree = node(
+            TraceNodeKind::Call,
+            Some("f"),
+            vec![node(TraceNodeKind::Operation, None, vec![])],
+        );
+        let dot = tree.to_dot();
+        assert!(dot.starts_with("digraph trace {\n"));
+        assert!(dot.contains("n0 [shape=ellipse, label=\"f\\l_x_1 = 1\\lx = 1\\l\"];"));
+        assert!(dot.contains("n0 -> n1;"));
+        assert!(dot.ends_with("}\n"));
+    }
 }
@@ -62,6 +62,9 @@ pub trait Visitor: Sized {
     fn visit_list(&mut self, l: &TermList) {
         walk_list(self, l)
     }
+    fn visit_set(&mut self, s: &Set) {
+        walk_set(self, s)
+    }
     fn visit_operation(&mut self, o: &Operation) {
         walk_operation(self, o)
     }
@@ -102,6 +105,8 @@ pub fn walk_term<V: Visitor>(visitor: &mut V, term: &Term) {
         Value::Pattern(p) => visitor.visit_pattern(p),
         Value::Call(c) => visitor.visit_call(c),
         Value::List(l) => visitor.visit_list(l),
+        Value::Set(s) => visitor.visit_set(s),
+        Value::Timestamp(_) | Value::Duration(_) => {}
         Value::Variable(v) => visitor.visit_variable(v),
         Value::RestVariable(r) => visitor.visit_rest_variable(r),
         Value::Expression(o) => visitor.visit_operation(o),
@@ -146,6 +151,10 @@ pub fn walk_list<V: Visitor>(visitor: &mut V, list: &TermList) {
     walk_elements!(visitor, visit_term, list);
 }
 
+pub fn walk_set<V: Visitor>(visitor: &mut V, set: &Set) {
+    walk_elements!(visitor, visit_term, set.elements());
+}
+
 pub fn walk_operation<V: Visitor>(visitor: &mut V, expr: &Operation) {
     visitor.visit_operator(&expr.operator);
     walk_elements!(visitor, visit_term, &expr.args);
@@ -10,6 +10,7 @@ use std::sync::{Arc, RwLock};
 use wasm_bindgen::prelude::*;
 
 use super::visitor::{walk_term, Visitor};
+use crate::aggregates::Collector;
 use crate::bindings::{BindingManager, BindingStack, Bindings, Bsp, FollowerId, VariableState};
 use crate::counter::Counter;
 use crate::debugger::{DebugEvent, Debugger};
@@ -21,12 +22,14 @@ use crate::inverter::Inverter;
 use crate::kb::*;
 use crate::lexer::loc_to_pos;
 use crate::messages::*;
+use crate::methods;
 use crate::numerics::*;
 use crate::partial::{simplify_bindings, simplify_partial, sub_this, IsaConstraintCheck};
 use crate::rewrites::Renamer;
 use crate::rules::*;
 use crate::runnable::Runnable;
 use crate::sources::*;
+use crate::tabling::{variant, Tables};
 use crate::terms::*;
 use crate::traces::*;
 
@@ -133,6 +136,21 @@ pub enum Goal {
     AddConstraintsBatch {
         add_constraints: Rc<RefCell<Bindings>>,
     },
+
+    /// Combine the `values` collected for the aggregate `term`,
+    /// and unify the combination with its result.
+    Aggregate {
+        term: Term,
+        values: Rc<RefCell<Vec<Term>>>,
+    },
+
+    /// Add the `values` collected for the tabled `call` to its table, and either
+    /// evaluate it again or choose among its answers.
+    CompleteTable {
+        predicate: Call,
+        call: Term,
+        values: Rc<RefCell<Vec<Term>>>,
+    },
 }
 
 #[derive(Clone, Debug)]
@@ -204,6 +222,12 @@ pub fn compare(op: Operator, left: &Term, right: &Term) -> PolarResult<bool> {
         (Value::Number(l), Value::Boolean(r)) => Ok(compare(op, l, &to_int(*r))),
         (Value::Number(l), Value::Number(r)) => Ok(compare(op, l, r)),
         (Value::String(l), Value::String(r)) => Ok(compare(op, l, r)),
+        (Value::Timestamp(l), Value::Timestamp(r)) => Ok(compare(op, l, r)),
+        (Value::Duration(l), Value::Duration(r)) => Ok(compare(op, l, r)),
+        // Sets are only equal or not.
+        (Value::Set(l), Value::Set(r)) if matches!(op, Operator::Eq | Operator::Neq) => {
+            Ok((l == r) == (op == Operator::Eq))
+        }
         _ => Err(error::RuntimeError::Unsupported {
             msg: format!("{} {} {}", left.to_polar(), op.to_polar(), right.to_polar()),
         }
@@ -211,6 +235,86 @@ pub fn compare(op: Operator, left: &Term, right: &Term) -> PolarResult<bool> {
     }
 }
 
+/// The operand of a set operation as a set. Lists are converted to sets.
+fn as_set(term: &Term) -> Option<Set> {
+    match term.value() {
+        Value::Set(set) => Some(set.clone()),
+        Value::List(list) => Some(Set::new(list.clone())),
+        _ => None,
+    }
+}
+
+/// Add or subtract timestamps and durations. A timestamp plus or minus a duration is a
+/// timestamp, the difference of two timestamps is a duration, and durations may be added to
+/// and subtracted from each other. Return `None` if the operation is not one of these, or
+/// `Some(None)` if the result is out of range.
+fn time_arithmetic(op: Operator, left: &Value, right: &Value) -> Option<Option<Value>> {
+    Some(match (op, left, right) {
+        (Operator::Add, Value::Timestamp(t), Value::Duration(d))
+        | (Operator::Add, Value::Duration(d), Value::Timestamp(t)) => {
+            t.checked_add(*d).map(Value::Timestamp)
+        }
+        (Operator::Sub, Value::Timestamp(t), Value::Duration(d)) => {
+            t.checked_sub(*d).map(Value::Timestamp)
+        }
+        (Operator::Sub, Value::Timestamp(l), Value::Timestamp(r)) => {
+            l.checked_since(*r).map(Value::Duration)
+        }
+        (Operator::Add, Value::Duration(l), Value::Duration(r)) => {
+            l.checked_add(*r).map(Value::Duration)
+        }
+        (Operator::Sub, Value::Duration(l), Value::Duration(r)) => {
+            l.checked_sub(*r).map(Value::Duration)
+        }
+        _ => return None,
+    })
+}
+
+/// The text of a string, number or boolean, for string formatting.
+fn display_string(term: &Term) -> Option<String> {
+    match term.value() {
+        Value::String(string) => Some(string.clone()),
+        Value::Number(_) | Value::Boolean(_) => Some(term.to_polar()),
+        _ => None,
+    }
+}
+
+/// Replace each `{}` in `template` with the text of the next of `values`. `{{` and `}}` are
+/// literal braces.
+fn format_string(template: &str, values: &[Term]) -> Result<String, String> {
+    let mut string = String::new();
+    let mut values = values.iter();
+    let mut chars = template.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, chars.peek()) {
+            ('{', Some('{')) | ('}', Some('}')) => {
+                chars.next();
+                string.push(c);
+            }
+            ('{', Some('}')) => {
+                chars.next();
+                let value = values
+                    .next()
+                    .ok_or_else(|| format!("too few arguments for format string {:?}", template))?;
+                let text = display_string(value)
+                    .ok_or_else(|| format!("cannot format {}", value.to_polar()))?;
+                string.push_str(&text);
+            }
+            ('{', _) | ('}', _) => {
+                return Err(format!("unmatched `{}` in format string {:?}", c, template))
+            }
+            _ => string.push(c),
+        }
+    }
+    if values.next().is_some() {
+        return Err(format!(
+            "too many arguments for format string {:?}",
+            template
+        ));
+    }
+    Ok(string)
+}
+
 #[derive(Clone)]
 pub struct PolarVirtualMachine {
     /// Stacks.
@@ -260,6 +364,15 @@ pub struct PolarVirtualMachine {
     pub query_contains_partial: bool,
     pub inverting: bool,
 
+    /// Tables of answers to calls of tabled rules, shared with sub-VMs.
+    tables: Rc<RefCell<Tables>>,
+
+    /// The time returned by `now()`. Fixed when the query starts, unless the host pins it.
+    now: Option<Timestamp>,
+
+    /// The nodes and bindings to leave out of the traces of results.
+    trace_filter: TraceFilter,
+
     /// Output messages.
     pub messages: MessageQueue,
 }
@@ -322,6 +435,9 @@ impl PolarVirtualMachine {
             polar_log_mute: false,
             query_contains_partial: false,
             inverting: false,
+            tables: Default::default(),
+            now: None,
+            trace_filter: TraceFilter::default(),
             messages,
         };
         vm.bind_constants(constants);
@@ -341,6 +457,17 @@ impl PolarVirtualMachine {
     }
 
     fn query_contains_partial(&mut self) {
+        self.query_contains_partial = self.goals.iter().any(|goal| {
+            if let Goal::Query { term } = goal.as_ref() {
+                self.term_contains_partial(term)
+            } else {
+                false
+            }
+        });
+    }
+
+    /// Does any variable in `term` have a partial value?
+    fn term_contains_partial(&self, term: &Term) -> bool {
         struct VarVisitor<'vm> {
             has_partial: bool,
             vm: &'vm PolarVirtualMachine,
@@ -356,16 +483,10 @@ impl PolarVirtualMachine {
 
         let mut visitor = VarVisitor {
             has_partial: false,
-            vm: &self,
+            vm: self,
         };
-        self.query_contains_partial = self.goals.iter().any(|goal| {
-            if let Goal::Query { term } = goal.as_ref() {
-                walk_term(&mut visitor, term);
-                visitor.has_partial
-            } else {
-                false
-            }
-        });
+        walk_term(&mut visitor, term);
+        visitor.has_partial
     }
 
     #[cfg(test)]
@@ -378,7 +499,9 @@ impl PolarVirtualMachine {
         let mut vm = Self::new(self.kb.clone(), self.tracing, goals, self.messages.clone());
         vm.binding_manager.clone_from(&self.binding_manager);
         vm.query_contains_partial = self.query_contains_partial;
+        vm.tables = self.tables.clone();
         vm.debugger = self.debugger.clone();
+        vm.now = self.now;
         vm
     }
 
@@ -513,6 +636,24 @@ impl PolarVirtualMachine {
                 )?
             }
             Goal::Run { runnable } => return self.run_runnable(runnable.clone_runnable()),
+            Goal::Aggregate { term, values } => {
+                let values = values.borrow().clone();
+                self.aggregate(&term, values)?
+            }
+            Goal::CompleteTable {
+                predicate,
+                call,
+                values,
+            } => {
+                let values = values.borrow().clone();
+                let again = self.tables.borrow_mut().add_answers(call, values);
+                if again {
+                    self.evaluate_table(predicate.clone(), call.clone())?
+                } else {
+                    let answers = self.tables.borrow_mut().finish(call);
+                    self.choose_answers(predicate, answers)?
+                }
+            }
         }
         Ok(QueryEvent::None)
     }
@@ -671,6 +812,16 @@ impl PolarVirtualMachine {
         self.csp = self.bsp();
     }
 
+    /// Pin the time returned by `now()`, e.g., to test time-bound rules.
+    pub fn set_now(&mut self, now: Timestamp) {
+        self.now = Some(now);
+    }
+
+    /// Leave the nodes and bindings excluded by `filter` out of the traces of results.
+    pub fn set_trace_filter(&mut self, filter: TraceFilter) {
+        self.trace_filter = filter;
+    }
+
     /// Retrieve the current non-constant bindings as a hash map.
     pub fn bindings(&self, include_temps: bool) -> Bindings {
         self.binding_manager.bindings_after(include_temps, self.csp)
@@ -706,7 +857,7 @@ impl PolarVirtualMachine {
     }
 
     /// Recursively dereference variables in a term, including subterms, except operations.
-    fn deep_deref(&self, term: &Term) -> Term {
+    pub fn deep_deref(&self, term: &Term) -> Term {
         self.binding_manager.deep_deref(term)
     }
 
@@ -1401,7 +1552,7 @@ impl PolarVirtualMachine {
 
         match &term.value() {
             Value::Call(predicate) => {
-                self.query_for_predicate(predicate.clone())?;
+                self.query_for_predicate(term, predicate.clone())?;
             }
             Value::Expression(_) => {
                 return self.query_for_operation(&term);
@@ -1414,13 +1565,30 @@ impl PolarVirtualMachine {
         Ok(QueryEvent::None)
     }
 
+    /// Query for a predicate, using its table if it is tabled.
+    fn query_for_predicate(&mut self, term: &Term, predicate: Call) -> PolarResult<()> {
+        assert!(predicate.kwargs.is_none());
+        let tabled = matches!(
+            self.kb.read().unwrap().rules.get(&predicate.name),
+            Some(generic_rule) if generic_rule.tabled
+        );
+        if tabled {
+            return self.query_for_tabled_predicate(term, predicate);
+        }
+        let goals = match self.filter_rules_goal(predicate) {
+            None => vec![Goal::Backtrack],
+            Some(goal) => vec![Goal::TraceStackPush, goal, Goal::TraceStackPop],
+        };
+        self.append_goals(goals)
+    }
+
     /// Select applicable rules for predicate.
     /// Sort applicable rules by specificity.
     /// Create a choice over the applicable rules.
-    fn query_for_predicate(&mut self, predicate: Call) -> PolarResult<()> {
-        assert!(predicate.kwargs.is_none());
-        let goals = match self.kb.read().unwrap().rules.get(&predicate.name) {
-            None => vec![Goal::Backtrack],
+    /// Return `None` if there are no rules for the predicate.
+    fn filter_rules_goal(&mut self, predicate: Call) -> Option<Goal> {
+        match self.kb.read().unwrap().rules.get(&predicate.name) {
+            None => None,
             Some(generic_rule) => {
                 assert_eq!(generic_rule.name, predicate.name);
 
@@ -1431,18 +1599,77 @@ impl PolarVirtualMachine {
                 self.polar_log_mute = true;
 
                 // Filter rules by applicability.
-                vec![
-                    Goal::TraceStackPush,
-                    Goal::FilterRules {
-                        applicable_rules: vec![],
-                        unfiltered_rules: pre_filter,
-                        args: predicate.args,
-                    },
-                    Goal::TraceStackPop,
-                ]
+                Some(Goal::FilterRules {
+                    applicable_rules: vec![],
+                    unfiltered_rules: pre_filter,
+                    args: predicate.args,
+                })
             }
+        }
+    }
+
+    /// Query for a call to a tabled rule. The answers to a call are computed once per query,
+    /// in a sub-VM; see `tabling`.
+    fn query_for_tabled_predicate(&mut self, term: &Term, predicate: Call) -> PolarResult<()> {
+        if self.query_contains_partial {
+            return Err(self.set_error_context(
+                term,
+                error::RuntimeError::Unsupported {
+                    msg: "cannot use tabled rules with partial evaluation".to_string(),
+                },
+            ));
+        }
+
+        let args = predicate.args.iter().map(|t| self.deep_deref(t)).collect();
+        let call = variant(term.clone_with_value(Value::Call(Call {
+            name: predicate.name.clone(),
+            args,
+            kwargs: None,
+        })));
+        let answers = self.tables.borrow_mut().lookup(&call);
+        match answers {
+            Some(answers) => self.choose_answers(&predicate, answers),
+            None => self.evaluate_table(predicate, call),
+        }
+    }
+
+    /// Collect the answers to one evaluation of the rules for a tabled call.
+    fn evaluate_table(&mut self, predicate: Call, call: Term) -> PolarResult<()> {
+        let values = Rc::new(RefCell::new(vec![]));
+        let template = Term::new_temporary(Value::List(predicate.args.clone()));
+        let goals = self
+            .filter_rules_goal(predicate.clone())
+            .into_iter()
+            .collect();
+        let collector = Box::new(Collector::new(self, template, goals, values.clone()));
+        self.append_goals(vec![
+            Goal::Run {
+                runnable: collector,
+            },
+            Goal::CompleteTable {
+                predicate,
+                call,
+                values,
+            },
+        ])
+    }
+
+    /// Make a choice over the answers to a tabled call, with fresh variables for each.
+    fn choose_answers(&mut self, predicate: &Call, answers: Vec<Term>) -> PolarResult<()> {
+        let args = Term::new_temporary(Value::List(predicate.args.clone()));
+        let alternatives = {
+            let kb = self.kb.read().unwrap();
+            answers
+                .into_iter()
+                .map(|answer| {
+                    vec![Goal::Unify {
+                        left: args.clone(),
+                        right: Renamer::new(&kb).fold_term(answer),
+                    }]
+                })
+                .collect::<Vec<_>>()
         };
-        self.append_goals(goals)
+        self.choose(alternatives)
     }
 
     fn query_for_operation(&mut self, term: &Term) -> PolarResult<QueryEvent> {
@@ -1476,6 +1703,77 @@ impl PolarVirtualMachine {
                     vec![Goal::Backtrack],
                 )?;
             }
+            Operator::If => {
+                assert_eq!(args.len(), 3);
+                let otherwise = args.pop().unwrap();
+                let then = args.pop().unwrap();
+                let condition = args.pop().unwrap();
+                if self.query_contains_partial && self.term_contains_partial(&condition) {
+                    // A partial condition need not have a single answer to commit to,
+                    // so constrain both branches: (condition and then) or
+                    // (not condition and otherwise).
+                    let negated = term.clone_with_value(value!(op!(Not, condition.clone())));
+                    let disjunction = op!(
+                        Or,
+                        term.clone_with_value(value!(op!(And, condition, then))),
+                        term.clone_with_value(value!(op!(And, negated, otherwise)))
+                    );
+                    self.push_goal(Goal::Query {
+                        term: term.clone_with_value(value!(disjunction)),
+                    })?;
+                } else {
+                    // Unlike `choose_conditional`, keep the bindings of the first answer
+                    // to the condition for the consequent. The cut commits to that answer
+                    // and discards the alternative.
+                    let choice_index = self.choices.len();
+                    self.choose(vec![
+                        vec![
+                            Goal::Query { term: condition },
+                            Goal::Cut { choice_index },
+                            Goal::Query { term: then },
+                        ],
+                        vec![Goal::Query { term: otherwise }],
+                    ])?;
+                }
+            }
+            Operator::FindAll | Operator::Count | Operator::Sum | Operator::Min | Operator::Max => {
+                if self.query_contains_partial {
+                    return Err(self.set_error_context(
+                        &term,
+                        error::RuntimeError::Unsupported {
+                            msg: format!(
+                                "cannot use {} with partial evaluation",
+                                operation.operator.to_polar()
+                            ),
+                        },
+                    ));
+                }
+
+                // Collect the template for every solution to the goal in a sub-VM.
+                assert_eq!(args.len(), 3);
+                let values = Rc::new(RefCell::new(vec![]));
+                let collector = Box::new(Collector::new(
+                    self,
+                    args[0].clone(),
+                    vec![Goal::Query {
+                        term: args[1].clone(),
+                    }],
+                    values.clone(),
+                ));
+                self.append_goals(vec![
+                    Goal::Run {
+                        runnable: collector,
+                    },
+                    Goal::Aggregate {
+                        term: term.clone(),
+                        values,
+                    },
+                ])?;
+            }
+            Operator::RegexMatch => return self.regex_match(term),
+            Operator::Union | Operator::Intersection | Operator::Difference | Operator::Subset => {
+                return self.set_op_helper(term)
+            }
             Operator::Assign => {
                 assert_eq!(args.len(), 2);
                 let right = args.pop().unwrap();
@@ -1533,6 +1831,12 @@ impl PolarVirtualMachine {
                 return self.query_op_helper(term, Self::arithmetic_op_helper, true, true);
             }
 
+            Operator::Format => return self.format_op_helper(term),
+
+            Operator::Timestamp | Operator::Duration | Operator::Now => {
+                return self.time_op_helper(term)
+            }
+
             Operator::In => {
                 return self.query_op_helper(term, Self::in_op_helper, false, true);
             }
@@ -1777,6 +2081,28 @@ impl PolarVirtualMachine {
                     args: vec![left.clone(), right.clone()],
                 })
             }
+            // Compare sets by value, like unification. A set literal is ordered before its
+            // variables are bound, so dereference and reorder the elements first.
+            (Value::Set(_), Value::Set(_)) => {
+                let (left, right) = (self.deep_deref(left), self.deep_deref(right));
+                if !left.is_ground() || !right.is_ground() {
+                    return Err(self.set_error_context(
+                        term,
+                        error::RuntimeError::Unsupported {
+                            msg: format!(
+                                "cannot compare sets with unbound variables: {} {} {}",
+                                left.to_polar(),
+                                op.to_polar(),
+                                right.to_polar()
+                            ),
+                        },
+                    ));
+                }
+                if !compare(*op, &left, &right)? {
+                    self.push_goal(Goal::Backtrack)?;
+                }
+                Ok(QueryEvent::None)
+            }
             _ => {
                 if !compare(*op, left, right)? {
                     self.push_goal(Goal::Backtrack)?;
@@ -1830,6 +2156,32 @@ impl PolarVirtualMachine {
                     ))
                 }
             }
+            // String concatenation, e.g., `"org:" + org.name`. Other values must be formatted.
+            (Value::String(l), Value::String(r)) if *op == Operator::Add => {
+                let string = l.clone() + r;
+                self.push_goal(Goal::Unify {
+                    left: term.clone_with_value(Value::String(string)),
+                    right: result.clone(),
+                })?;
+                Ok(QueryEvent::None)
+            }
+            // Timestamp and duration arithmetic, e.g., `expiry - duration("1h")`.
+            (l, r) if time_arithmetic(*op, l, r).is_some() => {
+                if let Some(answer) = time_arithmetic(*op, l, r).unwrap() {
+                    self.push_goal(Goal::Unify {
+                        left: term.clone_with_value(answer),
+                        right: result.clone(),
+                    })?;
+                    Ok(QueryEvent::None)
+                } else {
+                    Err(self.set_error_context(
+                        term,
+                        error::RuntimeError::ArithmeticError {
+                            msg: term.to_polar(),
+                        },
+                    ))
+                }
+            }
             (_, _) => Err(self.set_error_context(
                 &term,
                 error::RuntimeError::Unsupported {
@@ -1839,6 +2191,241 @@ impl PolarVirtualMachine {
         }
     }
 
+    /// Evaluate `format`, e.g., `format("{}/{}", a, b) = c`. If the template or any of the
+    /// format arguments is a variable, add a constraint instead.
+    fn format_op_helper(&mut self, term: &Term) -> PolarResult<QueryEvent> {
+        let Operation { args, .. } = term.value().as_expression().unwrap();
+        assert_eq!(args.len(), 3);
+        let template = self.deref(&args[0]);
+        let values = match self.deep_deref(&args[1]).value() {
+            Value::List(values) => values.clone(),
+            _ => unreachable!("format arguments must be a list"),
+        };
+
+        let is_variable = |term: &Term| matches!(term.value(), Value::Variable(_));
+        if is_variable(&template) || values.iter().any(is_variable) {
+            self.add_constraint(term)?;
+            return Ok(QueryEvent::None);
+        }
+
+        let string = match template.value() {
+            Value::String(template) => format_string(template, &values),
+            _ => Err(format!(
+                "format expects a string template, got {}",
+                template.to_polar()
+            )),
+        };
+        let string = string.map_err(|msg| self.type_error(term, msg))?;
+        self.push_goal(Goal::Unify {
+            left: term.clone_with_value(Value::String(string)),
+            right: args[2].clone(),
+        })?;
+        Ok(QueryEvent::None)
+    }
+
+    /// Evaluate `timestamp(s)`, `duration(s)` or `now()`, e.g., `duration("1h") = d`. Timestamps
+    /// and durations are parsed from strings. If the string is a variable, add a constraint
+    /// instead.
+    fn time_op_helper(&mut self, term: &Term) -> PolarResult<QueryEvent> {
+        let Operation { operator: op, args } = term.value().as_expression().unwrap();
+        let (value, result) = match op {
+            Operator::Now => {
+                assert_eq!(args.len(), 1);
+                let now = self.now.expect("query start time not recorded");
+                (Value::Timestamp(now), &args[0])
+            }
+            _ => {
+                assert_eq!(args.len(), 2);
+                let arg = self.deref(&args[0]);
+                let string = match arg.value() {
+                    Value::Variable(_) => {
+                        self.add_constraint(term)?;
+                        return Ok(QueryEvent::None);
+                    }
+                    Value::String(string) => string,
+                    _ => {
+                        return Err(self.type_error(
+                            &arg,
+                            format!("{} expects a string, got {}", op.to_polar(), arg.to_polar()),
+                        ))
+                    }
+                };
+                let value = if *op == Operator::Timestamp {
+                    Timestamp::parse(string).map(Value::Timestamp)
+                } else {
+                    Duration::parse(string).map(Value::Duration)
+                };
+                (value.map_err(|msg| self.type_error(&arg, msg))?, &args[1])
+            }
+        };
+        self.push_goal(Goal::Unify {
+            left: term.clone_with_value(value),
+            right: result.clone(),
+        })?;
+        Ok(QueryEvent::None)
+    }
+
+    /// Evaluate a set operation, e.g., `intersection(a, b, c)` or `subset(a, b)`. Lists are
+    /// converted to sets. If either operand is a variable, add a constraint instead.
+    fn set_op_helper(&mut self, term: &Term) -> PolarResult<QueryEvent> {
+        let Operation { operator: op, args } = term.value().as_expression().unwrap();
+        let left = self.deep_deref(&args[0]);
+        let right = self.deep_deref(&args[1]);
+
+        let is_variable = |term: &Term| matches!(term.value(), Value::Variable(_));
+        if is_variable(&left) || is_variable(&right) {
+            self.add_constraint(term)?;
+            return Ok(QueryEvent::None);
+        }
+
+        let (left, right) = match (as_set(&left), as_set(&right)) {
+            (Some(left), Some(right)) => (left, right),
+            (left_set, _) => {
+                let operand = if left_set.is_none() { &left } else { &right };
+                return Err(self.type_error(
+                    operand,
+                    format!(
+                        "{} expects sets or lists, got {}",
+                        op.to_polar(),
+                        operand.to_polar()
+                    ),
+                ));
+            }
+        };
+        let elements = left.elements().iter();
+        let result = match op {
+            Operator::Union => elements.chain(right.elements()).cloned().collect(),
+            Operator::Intersection => elements.filter(|e| right.contains(e)).cloned().collect(),
+            Operator::Difference => elements.filter(|e| !right.contains(e)).cloned().collect(),
+            Operator::Subset => {
+                if !left.elements().iter().all(|e| right.contains(e)) {
+                    self.push_goal(Goal::Backtrack)?;
+                }
+                return Ok(QueryEvent::None);
+            }
+            _ => unreachable!("{} is not a set operation", op.to_polar()),
+        };
+        self.push_goal(Goal::Unify {
+            left: term.clone_with_value(Value::Set(Set::new(result))),
+            right: args[2].clone(),
+        })?;
+        Ok(QueryEvent::None)
+    }
+
+    /// Combine the values collected for an aggregate, and unify the combination
+    /// with the aggregate's result. `min` and `max` of no values fail.
+    fn aggregate(&mut self, term: &Term, values: Vec<Term>) -> PolarResult<()> {
+        let Operation { operator: op, args } = term.value().as_expression().unwrap();
+        let result = args[2].clone();
+
+        let value = match op {
+            Operator::FindAll => Value::List(values),
+            Operator::Count => Value::Number(Numeric::Integer(values.len() as i64)),
+            Operator::Sum => {
+                let mut sum = Numeric::Integer(0);
+                for value in values.iter() {
+                    sum = match value.value() {
+                        Value::Number(n) => (sum + *n).ok_or_else(|| {
+                            self.set_error_context(
+                                term,
+                                error::RuntimeError::ArithmeticError {
+                                    msg: term.to_polar(),
+                                },
+                            )
+                        })?,
+                        _ => {
+                            return Err(self.type_error(
+                                term,
+                                format!("can only sum numbers, got {}", value.to_polar()),
+                            ))
+                        }
+                    };
+                }
+                Value::Number(sum)
+            }
+            Operator::Min | Operator::Max => {
+                let better = if *op == Operator::Min {
+                    Operator::Lt
+                } else {
+                    Operator::Gt
+                };
+                let mut best: Option<Term> = None;
+                for value in values.into_iter() {
+                    best = match best {
+                        Some(b)
+                            if !compare(better, &value, &b)
+                                .map_err(|e| self.set_error_context(term, e))? =>
+                        {
+                            Some(b)
+                        }
+                        _ => Some(value),
+                    };
+                }
+                match best {
+                    Some(best) => best.value().clone(),
+                    None => return self.push_goal(Goal::Backtrack),
+                }
+            }
+            _ => unreachable!("{} is not an aggregate", op.to_polar()),
+        };
+
+        self.push_goal(Goal::Unify {
+            left: term.clone_with_value(value),
+            right: result,
+        })
+    }
+
+    /// Match a string against a regular expression. If there is a third argument, unify it
+    /// with a list of the whole match followed by the text of each group; a group that does not
+    /// take part in the match is the empty string.
+    fn regex_match(&mut self, term: &Term) -> PolarResult<QueryEvent> {
+        let Operation { args, .. } = term.value().as_expression().unwrap();
+        let pattern = self.deref(&args[0]);
+        let string = self.deref(&args[1]);
+        let (pattern, string) = match (pattern.value(), string.value()) {
+            (Value::String(pattern), Value::String(string)) => (pattern, string),
+            _ => {
+                return Err(self.type_error(
+                    term,
+                    format!(
+                        "regex_match expects a string pattern and a string, got {} and {}",
+                        pattern.to_polar(),
+                        string.to_polar()
+                    ),
+                ))
+            }
+        };
+
+        let regex = self.kb.read().unwrap().regex(pattern);
+        let regex = regex.map_err(|e| {
+            self.set_error_context(
+                term,
+                error::RuntimeError::InvalidRegex {
+                    pattern: pattern.clone(),
+                    msg: e.to_string(),
+                },
+            )
+        })?;
+        match regex.captures(string) {
+            None => self.push_goal(Goal::Backtrack)?,
+            Some(captures) if args.len() == 3 => {
+                let groups = captures
+                    .iter()
+                    .map(|group| {
+                        let text = group.map_or("", |group| group.as_str());
+                        term.clone_with_value(Value::String(text.to_owned()))
+                    })
+                    .collect();
+                self.push_goal(Goal::Unify {
+                    left: args[2].clone(),
+                    right: term.clone_with_value(Value::List(groups)),
+                })?
+            }
+            Some(_) => (),
+        }
+        Ok(QueryEvent::None)
+    }
+
     /// Push appropriate goals for lookups on dictionaries and instances.
     fn dot_op_helper(&mut self, term: &Term) -> PolarResult<QueryEvent> {
         let Operation { operator: op, args } = term.value().as_expression().unwrap();
@@ -1850,6 +2437,23 @@ impl PolarVirtualMachine {
         let field = &args[1];
         let value = &args[2];
 
+        // Evaluate builtin methods on strings, lists and dictionaries without the host.
+        if let Value::Call(call) = field.value() {
+            let object = self.deep_deref(object);
+            let call = Call {
+                args: call.args.iter().map(|arg| self.deep_deref(arg)).collect(),
+                ..call.clone()
+            };
+            if let Some(result) = methods::call_method(&object, &call) {
+                let result = result.map_err(|msg| self.type_error(field, msg))?;
+                self.push_goal(Goal::Unify {
+                    left: field.clone_with_value(result),
+                    right: value.clone(),
+                })?;
+                return Ok(QueryEvent::None);
+            }
+        }
+
         match object.value() {
             // Push a `Lookup` goal for simple field lookups on dictionaries.
             Value::Dictionary(dict)
@@ -1975,6 +2579,27 @@ impl PolarVirtualMachine {
                         .collect::<Vec<Goals>>(),
                 )?;
             }
+            (_, Value::Set(set)) => {
+                if item.is_ground() && iterable.is_ground() {
+                    // Look up ground items.
+                    if !set.contains(item) {
+                        self.backtrack()?;
+                    }
+                } else {
+                    // Unify item with each element of the set.
+                    self.choose(
+                        set.elements()
+                            .iter()
+                            .map(|term| {
+                                vec![Goal::Unify {
+                                    left: item.clone(),
+                                    right: term.clone(),
+                                }]
+                            })
+                            .collect::<Vec<Goals>>(),
+                    )?;
+                }
+            }
             (_, Value::String(s)) => {
                 // Unify item with each element of the string
                 let item_is_ground = item.is_ground();
@@ -2108,6 +2733,26 @@ impl PolarVirtualMachine {
                 right: r.clone(),
             })?,
 
+            // Unify sets by value. Their elements are not unified, so they must be bound.
+            (Value::Set(_), Value::Set(_)) => {
+                let (left, right) = (self.deep_deref(left), self.deep_deref(right));
+                if !left.is_ground() || !right.is_ground() {
+                    return Err(self.set_error_context(
+                        &left,
+                        error::RuntimeError::Unsupported {
+                            msg: format!(
+                                "cannot unify sets with unbound variables: {} = {}",
+                                left.to_polar(),
+                                right.to_polar()
+                            ),
+                        },
+                    ));
+                }
+                if left != right {
+                    self.push_goal(Goal::Backtrack)?;
+                }
+            }
+
             (Value::Dictionary(left), Value::Dictionary(right)) => {
                 // Check that the set of keys are the same.
                 let left_fields: HashSet<&Symbol> = left.fields.keys().collect();
@@ -2152,6 +2797,18 @@ impl PolarVirtualMachine {
                 }
             }
 
+            // Unify timestamps and durations by value.
+            (Value::Timestamp(left), Value::Timestamp(right)) => {
+                if left != right {
+                    self.push_goal(Goal::Backtrack)?;
+                }
+            }
+            (Value::Duration(left), Value::Duration(right)) => {
+                if left != right {
+                    self.push_goal(Goal::Backtrack)?;
+                }
+            }
+
             // Unify predicates like unifying heads
             (Value::Call(left), Value::Call(right)) => {
                 // Handled in the parser.
@@ -2172,7 +2829,7 @@ impl PolarVirtualMachine {
             // External instances can unify if they are the same instance, i.e., have the same
             // instance ID. This is necessary for the case where an instance appears multiple times
             // in the same rule head. For example, `f(foo, foo) if ...` or `isa(x, y, x: y) if ...`
-            // or `max(x, y, x) if x > y;`.
+            // or `larger(x, y, x) if x > y;`.
             (
                 Value::ExternalInstance(ExternalInstance {
                     instance_id: left_instance,
@@ -2619,9 +3276,101 @@ impl PolarVirtualMachine {
         source_string
     }
 
+    /// Build the exportable form of a trace, resolving the source spans of its nodes and the
+    /// current bindings of the variables that occur in them.
+    pub fn trace_node(&self, trace: &Trace) -> TraceNode {
+        let mut node = match &trace.node {
+            Node::Rule(rule) => TraceNode {
+                kind: TraceNodeKind::Rule,
+                name: Some(rule.name.clone()),
+                source: self.rule_source(rule),
+                span: self.rule_span(rule),
+                bindings: BTreeMap::new(),
+                annotations: rule.annotations.clone(),
+                children: vec![],
+            },
+            Node::Term(term) => {
+                let (kind, name) = match term.value() {
+                    Value::Call(call) => (TraceNodeKind::Call, Some(call.name.clone())),
+                    Value::Expression(_) => (TraceNodeKind::Operation, None),
+                    _ => (TraceNodeKind::Value, None),
+                };
+                let mut vars = HashSet::new();
+                term.variables(&mut vars);
+                let bindings = vars
+                    .into_iter()
+                    .filter_map(|var| {
+                        let value =
+                            self.deep_deref(&term.clone_with_value(Value::Variable(var.clone())));
+                        match value.value() {
+                            Value::Variable(v) if *v == var => None,
+                            _ => Some((var, value)),
+                        }
+                    })
+                    .collect();
+                TraceNode {
+                    kind,
+                    name,
+                    source: self.term_source(term, false),
+                    span: term.span().and_then(|span| self.trace_span(term, span)),
+                    bindings,
+                    annotations: vec![],
+                    children: vec![],
+                }
+            }
+        };
+        for child in &trace.children {
+            let child_node = self.trace_node(child);
+            // Conjunctions are flattened into their parent.
+            if let Node::Term(term) = &child.node {
+                if let Value::Expression(Operation {
+                    operator: Operator::And,
+                    ..
+                }) = term.value()
+                {
+                    node.children.extend(child_node.children);
+                    continue;
+                }
+            }
+            node.children.push(child_node);
+        }
+        node
+    }
+
+    /// The span of a rule, from its first parameter through the end of its body.
+    fn rule_span(&self, rule: &Rule) -> Option<TraceSpan> {
+        let start = rule
+            .params
+            .first()
+            .map(|p| &p.parameter)
+            .unwrap_or(&rule.body);
+        let left = start.span()?.0;
+        let right = rule.body.span()?.1;
+        self.trace_span(start, (left, right))
+    }
+
+    fn trace_span(&self, term: &Term, (left, right): (usize, usize)) -> Option<TraceSpan> {
+        self.source(term).map(|source| {
+            let (row, column) = loc_to_pos(&source.src, left);
+            TraceSpan {
+                filename: source.filename,
+                row,
+                column,
+                left,
+                right,
+            }
+        })
+    }
+
     pub fn rule_source(&self, rule: &Rule) -> String {
+        let annotations = rule
+            .annotations
+            .iter()
+            .map(|annotation| annotation.to_polar() + " ")
+            .collect::<String>();
         let head = format!(
-            "{}({})",
+            "{}{}({})",
+            annotations,
             rule.name,
             rule.params.iter().fold(String::new(), |mut acc, p| {
                 if !acc.is_empty() {
@@ -2693,6 +3442,9 @@ impl Runnable for PolarVirtualMachine {
             let query_start_time = Some(js_sys::Date::now());
             self.query_start_time = query_start_time;
         }
+        if self.now.is_none() {
+            self.now = Some(Timestamp::now());
+        }
 
         if self.goals.is_empty() {
             if self.choices.is_empty() {
@@ -2724,9 +3476,14 @@ impl Runnable for PolarVirtualMachine {
 
         let trace = if self.tracing {
             let trace = self.trace.first().cloned();
-            trace.map(|trace| TraceResult {
-                formatted: trace.draw(&self),
-                trace,
+            trace.map(|trace| {
+                let tree = self.trace_node(&trace).filter(&self.trace_filter);
+                TraceResult {
+                    formatted: trace.draw(&self),
+                    dot: tree.to_dot(),
+                    tree,
+                    trace,
+                }
             })
         } else {
             None
@@ -3393,6 +4150,8 @@ mod tests {
                     ))])),
                 ],
             })),
+            doc: None,
+            annotations: vec![],
         };
 
         let renamed_rule = vm.rename_rule_vars(&rule);
@@ -1,10 +1,112 @@
 use super::formatting::source_lines;
 use super::kb::*;
+use super::lexer::loc_to_pos;
+use super::messages::*;
+use super::modules::check_ambiguous_calls;
 use super::rules::*;
 use super::terms::*;
-use super::visitor::{walk_rule, walk_term, Visitor};
+use super::visitor::{walk_operation, walk_rule, walk_term, Visitor};
 
-use std::collections::{hash_map::Entry, HashMap};
+use serde::{Deserialize, Serialize};
+use std::collections::{hash_map::Entry, HashMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
+
+/// Stable identifiers for the checks that produce load-time warnings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LintCode {
+    SingletonVariable,
+    UnknownSpecializer,
+    UndefinedRule,
+    WrongArity,
+    ShadowedRule,
+    UnusedConstant,
+    SelfComparison,
+    UnifyInNegation,
+    AmbiguousCall,
+}
+
+impl LintCode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::SingletonVariable => "singleton-variable",
+            Self::UnknownSpecializer => "unknown-specializer",
+            Self::UndefinedRule => "undefined-rule",
+            Self::WrongArity => "wrong-arity",
+            Self::ShadowedRule => "shadowed-rule",
+            Self::UnusedConstant => "unused-constant",
+            Self::SelfComparison => "self-comparison",
+            Self::UnifyInNegation => "unify-in-negation",
+            Self::AmbiguousCall => "ambiguous-call",
+        }
+    }
+
+    /// How serious the problems found by the lint are likely to be.
+    pub fn severity(&self) -> Severity {
+        match self {
+            Self::UnusedConstant => Severity::Info,
+            _ => Severity::Warning,
+        }
+    }
+}
+
+impl fmt::Display for LintCode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for LintCode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        [
+            Self::SingletonVariable,
+            Self::UnknownSpecializer,
+            Self::UndefinedRule,
+            Self::WrongArity,
+            Self::ShadowedRule,
+            Self::UnusedConstant,
+            Self::SelfComparison,
+            Self::UnifyInNegation,
+            Self::AmbiguousCall,
+        ]
+        .iter()
+        .find(|code| code.as_str() == s)
+        .copied()
+        .ok_or_else(|| format!("unknown warning code {}", s))
+    }
+}
+
+/// Make a warning message, pointing at `term` in its source if it has one.
+pub(crate) fn warning(
+    kb: &KnowledgeBase,
+    code: LintCode,
+    mut msg: String,
+    term: Option<&Term>,
+) -> Message {
+    let mut range = None;
+    if let Some(term) = term {
+        if let Some(ref source) = term
+            .get_source_id()
+            .and_then(|id| kb.sources.get_source(id))
+        {
+            msg.push('\n');
+            msg.push_str(&source_lines(source, term.offset(), 0));
+            range = term
+                .span()
+                .map(|(left, right)| SourceRange::from_offsets(source, left, right));
+        }
+    }
+    Message {
+        kind: MessageKind::Warning,
+        msg,
+        code: Some(code),
+        severity: Some(code.severity()),
+        range,
+    }
+}
 
 fn common_misspellings(t: &str) -> Option<String> {
     let misspelled_type = match t {
@@ -55,7 +157,7 @@ impl<'kb> SingletonVisitor<'kb> {
         }
     }
 
-    fn warnings(&mut self) -> Vec<String> {
+    fn warnings(&mut self) -> Vec<Message> {
         let mut singletons = self
             .singletons
             .drain()
@@ -65,29 +167,22 @@ impl<'kb> SingletonVisitor<'kb> {
         singletons
             .iter()
             .map(|(sym, term)| {
-                let mut msg = if let Value::Pattern(..) = term.value() {
+                if let Value::Pattern(..) = term.value() {
                     let mut msg = format!("Unknown specializer {}", sym);
                     if let Some(t) = common_misspellings(&sym.0) {
                         msg.push_str(&format!(", did you mean {}?", t));
                     }
-                    msg
+                    warning(self.kb, LintCode::UnknownSpecializer, msg, Some(term))
                 } else {
-                    format!(
+                    let msg = format!(
                         "Singleton variable {} is unused or undefined, \
                          see <https://docs.osohq.com/using/polar-syntax.html#variables>",
                         sym
-                    )
-                };
-                if let Some(ref source) = term
-                    .get_source_id()
-                    .and_then(|id| self.kb.sources.get_source(id))
-                {
-                    msg.push('\n');
-                    msg.push_str(&source_lines(source, term.offset(), 0));
+                    );
+                    warning(self.kb, LintCode::SingletonVariable, msg, Some(term))
                 }
-                msg
             })
-            .collect::<Vec<String>>()
+            .collect::<Vec<Message>>()
     }
 }
 
@@ -113,8 +208,299 @@ impl<'kb> Visitor for SingletonVisitor<'kb> {
     }
 }
 
-pub fn check_singletons(rule: &Rule, kb: &KnowledgeBase) -> Vec<String> {
+pub fn check_singletons(rule: &Rule, kb: &KnowledgeBase) -> Vec<Message> {
     let mut visitor = SingletonVisitor::new(kb);
     walk_rule(&mut visitor, rule);
     visitor.warnings()
 }
+
+/// Record suspicious comparisons in a rule body.
+struct ComparisonVisitor<'kb> {
+    kb: &'kb KnowledgeBase,
+    warnings: Vec<Message>,
+    /// True while visiting the goals of a negation.
+    negated: bool,
+}
+
+impl<'kb> Visitor for ComparisonVisitor<'kb> {
+    fn visit_term(&mut self, t: &Term) {
+        if let Value::Expression(Operation { operator, args }) = t.value() {
+            match operator {
+                Operator::Unify
+                | Operator::Eq
+                | Operator::Neq
+                | Operator::Geq
+                | Operator::Leq
+                | Operator::Gt
+                | Operator::Lt
+                    if args.len() == 2 && args[0] == args[1] =>
+                {
+                    let msg = format!("{} compares a value to itself", t.to_polar());
+                    self.warnings
+                        .push(warning(self.kb, LintCode::SelfComparison, msg, Some(t)));
+                }
+                Operator::Unify if self.negated => {
+                    let msg = format!(
+                        "{} cannot bind variables under `not`, did you mean `==`?",
+                        t.to_polar()
+                    );
+                    self.warnings
+                        .push(warning(self.kb, LintCode::UnifyInNegation, msg, Some(t)));
+                }
+                Operator::Not => {
+                    let negated = self.negated;
+                    self.negated = true;
+                    walk_term(self, t);
+                    self.negated = negated;
+                    return;
+                }
+                _ => {}
+            }
+        }
+        walk_term(self, t)
+    }
+
+    fn visit_operation(&mut self, o: &Operation) {
+        match o.operator {
+            Operator::And | Operator::Or | Operator::Not | Operator::If | Operator::ForAll => {
+                walk_operation(self, o)
+            }
+            // The second argument of an aggregate is a goal.
+            operator if operator.is_aggregate() => self.visit_term(&o.args[1]),
+            // Other operators do not take goals as arguments.
+            _ => {}
+        }
+    }
+}
+
+/// Check a rule body for comparisons that are always true, and for `=` where `==` was likely
+/// meant.
+pub fn check_comparisons(rule: &Rule, kb: &KnowledgeBase) -> Vec<Message> {
+    let mut visitor = ComparisonVisitor {
+        kb,
+        warnings: vec![],
+        negated: false,
+    };
+    visitor.visit_term(&rule.body);
+    visitor.warnings
+}
+
+/// Record the calls in goal position of a rule body.
+struct CallVisitor {
+    calls: Vec<Term>,
+}
+
+impl Visitor for CallVisitor {
+    fn visit_term(&mut self, t: &Term) {
+        match t.value() {
+            Value::Call(_) => self.calls.push(t.clone()),
+            Value::Expression(o) => match o.operator {
+                Operator::And | Operator::Or | Operator::Not | Operator::If | Operator::ForAll => {
+                    walk_term(self, t)
+                }
+                operator if operator.is_aggregate() => self.visit_term(&o.args[1]),
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+}
+
+/// Return the calls in goal position in a rule body or query. Calls made on application
+/// instances (`x.foo()`) are not rule calls.
+pub(crate) fn goal_calls(term: &Term) -> Vec<Term> {
+    let mut visitor = CallVisitor { calls: vec![] };
+    visitor.visit_term(term);
+    visitor.calls
+}
+
+/// Check calls to rules that are not defined, or not defined with the number of arguments
+/// they're called with.
+fn check_calls(rule: &Rule, kb: &KnowledgeBase) -> Vec<Message> {
+    goal_calls(&rule.body)
+        .iter()
+        .filter_map(|term| {
+            let call = term.value().as_call().unwrap();
+            match kb.rules.get(&call.name) {
+                None => Some(warning(
+                    kb,
+                    LintCode::UndefinedRule,
+                    format!("Call to undefined rule {}", call.name),
+                    Some(term),
+                )),
+                Some(generic_rule) => {
+                    let mut arities = generic_rule
+                        .rules()
+                        .iter()
+                        .map(|r| r.params.len())
+                        .collect::<Vec<_>>();
+                    arities.sort_unstable();
+                    arities.dedup();
+                    if arities.contains(&call.args.len()) {
+                        return None;
+                    }
+                    let arities = arities
+                        .iter()
+                        .map(|n| n.to_string())
+                        .collect::<Vec<_>>()
+                        .join(" or ");
+                    Some(warning(
+                        kb,
+                        LintCode::WrongArity,
+                        format!(
+                            "Call to {} with {} arguments, but it is defined with {}",
+                            call.name,
+                            call.args.len(),
+                            arities
+                        ),
+                        Some(term),
+                    ))
+                }
+            }
+        })
+        .collect()
+}
+
+/// Return true if the last goal of the rule body is a cut.
+fn ends_in_cut(rule: &Rule) -> bool {
+    matches!(
+        rule.body.value(),
+        Value::Expression(Operation { operator: Operator::And, args })
+            if matches!(args.last().map(Term::value), Some(Value::Expression(Operation {
+                operator: Operator::Cut,
+                ..
+            })))
+    )
+}
+
+/// Return true if `general` applies to every call that `specific` applies to, with the same
+/// specificity.
+fn subsumes(general: &Rule, specific: &Rule) -> bool {
+    let mut vars = HashSet::new();
+    general.params.len() == specific.params.len()
+        && general
+            .params
+            .iter()
+            .zip(specific.params.iter())
+            .all(|(g, s)| {
+                g.specializer == s.specializer
+                    && match g.parameter.value() {
+                        Value::Variable(v) => vars.insert(v.clone()),
+                        _ => g.parameter == s.parameter,
+                    }
+            })
+}
+
+/// Return a term to locate a rule by in its source.
+fn rule_term(rule: &Rule) -> &Term {
+    rule.params
+        .first()
+        .map(|p| &p.parameter)
+        .unwrap_or(&rule.body)
+}
+
+/// Check for rules that follow a more general rule ending in a cut. The cut stops the later
+/// rule from applying whenever the earlier one succeeds.
+fn check_shadowed(generic_rule: &GenericRule, src_id: u64, kb: &KnowledgeBase) -> Vec<Message> {
+    let rules = generic_rule.rules();
+    rules
+        .iter()
+        .enumerate()
+        .filter(|(_, rule)| rule_term(rule).get_source_id() == Some(src_id))
+        .filter_map(|(i, rule)| {
+            rules[..i]
+                .iter()
+                .find(|earlier| ends_in_cut(earlier) && subsumes(earlier, rule))
+                .map(|earlier| {
+                    let mut msg = format!("Rule {} is shadowed by an earlier rule", rule.name);
+                    let term = rule_term(earlier);
+                    if let Some(source) = term
+                        .get_source_id()
+                        .and_then(|id| kb.sources.get_source(id))
+                    {
+                        let (row, _) = loc_to_pos(&source.src, term.offset());
+                        msg.push_str(&format!(" on line {}", row + 1));
+                    }
+                    msg.push_str(" that is at least as general and ends in a cut");
+                    warning(kb, LintCode::ShadowedRule, msg, Some(rule_term(rule)))
+                })
+        })
+        .collect()
+}
+
+/// Return the constants (other than application classes and instances) that no rule uses.
+/// Constants defined in a policy are not checked, since the rules that use them have their
+/// values substituted when they are loaded.
+fn check_unused_constants(kb: &KnowledgeBase) -> Vec<Message> {
+    struct ConstantVisitor<'kb> {
+        kb: &'kb KnowledgeBase,
+        used: HashSet<Symbol>,
+    }
+
+    impl<'kb> Visitor for ConstantVisitor<'kb> {
+        fn visit_variable(&mut self, v: &Symbol) {
+            if self.kb.is_constant(v) {
+                self.used.insert(v.clone());
+            }
+        }
+
+        fn visit_instance_literal(&mut self, i: &InstanceLiteral) {
+            self.visit_variable(&i.tag);
+            self.visit_dictionary(&i.fields);
+        }
+    }
+
+    let mut visitor = ConstantVisitor {
+        kb,
+        used: HashSet::new(),
+    };
+    for generic_rule in kb.rules.values() {
+        for rule in generic_rule.rules() {
+            walk_rule(&mut visitor, &rule);
+        }
+    }
+    let mut unused = kb
+        .constants
+        .iter()
+        .filter(|(name, value)| {
+            !visitor.used.contains(name)
+                && !kb.policy_constants.contains(name)
+                && !matches!(value.value(), Value::ExternalInstance(_))
+        })
+        .map(|(name, _)| name.clone())
+        .collect::<Vec<_>>();
+    unused.sort();
+    unused
+        .into_iter()
+        .map(|name| {
+            let msg = format!("Constant {} is never used", name);
+            warning(kb, LintCode::UnusedConstant, msg, None)
+        })
+        .collect()
+}
+
+/// Checks of the rules in the source `src_id` against the rules loaded before them.
+pub fn check_source(kb: &KnowledgeBase, src_id: u64) -> Vec<Message> {
+    let mut names = kb.rules.keys().collect::<Vec<_>>();
+    names.sort();
+    names
+        .into_iter()
+        .flat_map(|name| check_shadowed(&kb.rules[name], src_id, kb))
+        .collect()
+}
+
+/// Checks that need the whole knowledge base, e.g., that every rule called is defined. A
+/// policy may be split across files, so these are run once all of them are loaded.
+pub fn check_knowledge_base(kb: &KnowledgeBase) -> Vec<Message> {
+    let mut names = kb.rules.keys().collect::<Vec<_>>();
+    names.sort();
+    let mut warnings = vec![];
+    for name in names {
+        for rule in kb.rules[name].rules() {
+            warnings.append(&mut check_calls(&rule, kb));
+            warnings.append(&mut check_ambiguous_calls(&rule, kb));
+        }
+    }
+    warnings.append(&mut check_unused_constants(kb));
+    warnings
+}
@@ -17,6 +17,7 @@ use polar_core::{
     terms::*,
     traces::*,
     value, values,
+    warnings::LintCode,
 };
 
 type QueryResults = Vec<(HashMap<Symbol, Value>, Option<TraceResult>)>;
@@ -381,6 +382,125 @@ fn test_trace() -> TestResult {
     Ok(())
 }
 
+#[test]
+fn test_trace_export() -> TestResult {
+    let p = Polar::new();
+    p.load(
+        r#"f(x) if x = 1 and g(x);
+           g(_);"#,
+        Some("trace.polar".to_string()),
+    )?;
+    let q = p.new_query("f(a)", true)?;
+    let results = query_results!(q);
+    let tree = &results[0].1.as_ref().unwrap().tree;
+
+    assert_eq!(tree.kind, TraceNodeKind::Call);
+    assert_eq!(tree.name, Some(sym!("f")));
+    assert_eq!(tree.bindings.get(&sym!("a")), Some(&term!(1)));
+
+    let rule = &tree.children[0];
+    assert_eq!(rule.kind, TraceNodeKind::Rule);
+    assert_eq!(rule.source, "f(x) if x = 1 and g(x);");
+    let span = rule.span.as_ref().unwrap();
+    assert_eq!(span.filename.as_deref(), Some("trace.polar"));
+    assert_eq!((span.row, span.column), (0, 2));
+
+    let body = rule
+        .children
+        .iter()
+        .map(|c| c.source.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(body, vec!["x = 1", "g(x)"]);
+
+    let filtered = tree.filter(&TraceFilter {
+        builtins: true,
+        temporaries: true,
+        ..Default::default()
+    });
+    let call = &filtered.children[0].children[0];
+    assert_eq!(call.name, Some(sym!("g")));
+    assert!(call.bindings.is_empty());
+
+    assert!(filtered.to_dot().contains("n1 -> n2;"));
+    let json: serde_json::Value = serde_json::from_str(&filtered.to_json()).unwrap();
+    assert_eq!(json["children"][0]["kind"], "Rule");
+    Ok(())
+}
+
+#[test]
+fn test_query_trace_filter() -> TestResult {
+    let p = Polar::new();
+    p.load_str(
+        r#"f(x) if x = 1 and _g(x);
+           _g(_);"#,
+    )?;
+    let mut q = p.new_query("f(a)", true)?;
+    q.set_trace_filter(serde_json::from_str(r#"{"builtins": true, "rules": "^_"}"#).unwrap());
+    let results = query_results!(q);
+    let trace = results[0].1.as_ref().unwrap();
+
+    let rule = &trace.tree.children[0];
+    assert_eq!(rule.source, "f(x) if x = 1 and _g(x);");
+    assert!(rule.children.is_empty());
+    assert_eq!(trace.dot, trace.tree.to_dot());
+    assert!(!trace.dot.contains("_g(_)"));
+    assert!(!trace.dot.contains("n1 -> n2;"));
+
+    let mut q = p.new_query("f(a)", true)?;
+    assert!(serde_json::from_str::<TraceFilter>(r#"{"rules": "("}"#).is_err());
+    q.set_trace_filter(TraceFilter::default());
+    let results = query_results!(q);
+    assert_eq!(
+        results[0].1.as_ref().unwrap().tree.children[0]
+            .children
+            .len(),
+        2
+    );
+    Ok(())
+}
+
+#[test]
+fn test_rule_annotations() -> TestResult {
+    let p = Polar::new();
+    p.load_str(
+        r#"@tag("pii") @owner("billing-team")
+           allow(x) if x = 1;
+           allow(2);"#,
+    )?;
+
+    let docs = p.docs();
+    let allow = &docs.rules[&sym!("allow")];
+    assert_eq!(allow[0].annotation_args("tag"), vec![&term!("pii")]);
+    assert_eq!(
+        allow[0].annotation_args("owner"),
+        vec![&term!("billing-team")]
+    );
+    assert!(allow[1].annotations.is_empty());
+    let annotations = p.annotations(&sym!("allow"));
+    assert_eq!(annotations.len(), 2);
+    assert_eq!(annotations[0][1].name, sym!("owner"));
+    assert!(annotations[1].is_empty());
+    assert!(p.annotations(&sym!("deny")).is_empty());
+    assert!(docs
+        .to_markdown()
+        .contains("```polar\n@tag(\"pii\")\n@owner(\"billing-team\")\nallow(x)\n```"));
+
+    // The rule that was applied is annotated in the trace.
+    let q = p.new_query("allow(1)", true)?;
+    let results = query_results!(q);
+    let trace = results[0].1.as_ref().unwrap();
+    let rule = &trace.tree.children[0];
+    assert_eq!(rule.annotations.len(), 2);
+    assert_eq!(
+        rule.source,
+        "@tag(\"pii\") @owner(\"billing-team\") allow(x) if x = 1;"
+    );
+    assert!(trace
+        .formatted
+        .contains("@tag(\"pii\") @owner(\"billing-team\") allow(x)"));
+    Ok(())
+}
+
 #[test]
 fn test_nested_rule() -> TestResult {
     let mut p = Polar::new();
@@ -1436,6 +1556,311 @@ fn test_unknown_specializer_suggestions() -> TestResult {
     Ok(())
 }
 
+#[test]
+fn test_lint_warnings() -> TestResult {
+    let p = Polar::new();
+    p.register_constant(sym!("MAX"), term!(10));
+    p.register_constant(sym!("MIN"), term!(0));
+    p.load_str(indoc!(
+        r#"f(x) if x < MAX and g(x) and h(x, 1);
+           g(x) if x == x;
+           g(_) if cut;
+           g(1);
+           h(x) if not x = 1;"#
+    ))?;
+    let warnings = |p: &Polar| {
+        let mut warnings = vec![];
+        while let Some(msg) = p.next_message() {
+            assert!(matches!(&msg.kind, MessageKind::Warning));
+            warnings.push((
+                msg.code.unwrap(),
+                msg.msg.lines().next().unwrap().to_owned(),
+            ));
+        }
+        warnings
+    };
+    assert_eq!(
+        warnings(&p),
+        vec![
+            (
+                LintCode::SelfComparison,
+                "x == x compares a value to itself".to_owned()
+            ),
+            (
+                LintCode::UnifyInNegation,
+                "x = 1 cannot bind variables under `not`, did you mean `==`?".to_owned()
+            ),
+            (
+                LintCode::ShadowedRule,
+                "Rule g is shadowed by an earlier rule on line 3 \
+                 that is at least as general and ends in a cut"
+                    .to_owned()
+            ),
+        ]
+    );
+
+    // Checks of the whole knowledge base run before the first query.
+    let _query = p.new_query("f(1)", false)?;
+    assert_eq!(
+        warnings(&p),
+        vec![
+            (
+                LintCode::WrongArity,
+                "Call to h with 2 arguments, but it is defined with 1".to_owned()
+            ),
+            (
+                LintCode::UnusedConstant,
+                "Constant MIN is never used".to_owned()
+            ),
+        ]
+    );
+
+    // Only new findings are reported.
+    p.ignore_warning(LintCode::UndefinedRule);
+    p.load_str("k(x) if undefined(x) and h(x, 2);")?;
+    p.check_knowledge_base();
+    assert_eq!(
+        warnings(&p),
+        vec![(
+            LintCode::WrongArity,
+            "Call to h with 2 arguments, but it is defined with 1".to_owned()
+        )]
+    );
+    p.check_knowledge_base();
+    assert!(p.next_message().is_none());
+    Ok(())
+}
+
+#[test]
+fn test_lint_policy_across_files() -> TestResult {
+    let p = Polar::new();
+    p.load("allow(x) if is_admin(x);", Some("allow.polar".to_string()))?;
+    p.load("is_admin(\"admin\");", Some("admin.polar".to_string()))?;
+    assert!(p.next_message().is_none());
+    let _query = p.new_query(r#"allow("admin")"#, false)?;
+    assert!(p.next_message().is_none());
+
+    p.load("deny(x) if is_banned(x);", Some("deny.polar".to_string()))?;
+    let _query = p.new_query(r#"deny("admin")"#, false)?;
+    let msg = p.next_message().unwrap();
+    assert_eq!(msg.code, Some(LintCode::UndefinedRule));
+    assert!(msg.msg.starts_with("Call to undefined rule is_banned"));
+    assert!(p.next_message().is_none());
+    Ok(())
+}
+
+#[test]
+fn test_warning_ranges() -> TestResult {
+    let p = Polar::new();
+    p.load(
+        "f(x) if true;\ng(y: integer) if y.foo == y.foo;",
+        Some("lint.polar".to_string()),
+    )?;
+
+    let msg = p.next_message().unwrap();
+    assert_eq!(msg.code, Some(LintCode::SingletonVariable));
+    assert_eq!(msg.severity, Some(Severity::Warning));
+    assert_eq!(
+        msg.range,
+        Some(SourceRange {
+            filename: Some("lint.polar".to_string()),
+            start: Position { row: 0, column: 2 },
+            end: Position { row: 0, column: 3 },
+        })
+    );
+
+    let msg = p.next_message().unwrap();
+    assert_eq!(msg.code, Some(LintCode::UnknownSpecializer));
+    let range = msg.range.unwrap();
+    assert_eq!(
+        (range.start, range.end),
+        (
+            Position { row: 1, column: 5 },
+            Position { row: 1, column: 12 }
+        )
+    );
+
+    let msg = p.next_message().unwrap();
+    assert_eq!(msg.code, Some(LintCode::SelfComparison));
+    let range = msg.range.unwrap();
+    assert_eq!(
+        (range.start, range.end),
+        (
+            Position { row: 1, column: 17 },
+            Position { row: 1, column: 31 }
+        )
+    );
+
+    let json: serde_json::Value = serde_json::to_value(msg.code).unwrap();
+    assert_eq!(json, "self-comparison");
+    Ok(())
+}
+
+#[test]
+fn test_resource_blocks() -> TestResult {
+    let mut p = Polar::new();
+    p.load_str(
+        r#"resource Dictionary {
+               roles = ["reader", "maintainer"];
+               permissions = ["read", "push"];
+               "read" if "reader";
+               "push" if "maintainer";
+               "reader" if "maintainer";
+           }
+           has_role("alice", "maintainer", {name: "oso"});
+           has_role("bob", "reader", {name: "oso"});
+           allow(actor, action, resource) if has_permission(actor, action, resource);"#,
+    )?;
+    qeval(&mut p, r#"allow("alice", "read", {name: "oso"})"#);
+    qeval(&mut p, r#"allow("alice", "push", {name: "oso"})"#);
+    qeval(&mut p, r#"allow("bob", "read", {name: "oso"})"#);
+    qnull(&mut p, r#"allow("bob", "push", {name: "oso"})"#);
+    qnull(&mut p, r#"allow("alice", "read", {name: "polar"})"#);
+
+    let err = p
+        .load(
+            "resource Repo {\n    roles = [\"reader\"];\n    \"read\" if \"reader\";\n}",
+            Some("repo.polar".to_string()),
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Undeclared role or permission \"read\" in resource block Repo at line 3, column 5 in \
+         file repo.polar"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_modules() -> TestResult {
+    let mut p = Polar::new();
+    p.load_str(r#"is_member(_user);"#)?;
+    p.load_str(indoc!(
+        r#"module billing;
+           export has_plan;
+           is_member("alice");
+           has_plan(user) if is_member(user);"#
+    ))?;
+    // Ambiguous calls are reported once every source is loaded, before the first query.
+    p.check_knowledge_base();
+    let mut warnings = vec![];
+    while let Some(msg) = p.next_message() {
+        warnings.push((msg.code, msg.msg.lines().next().unwrap().to_owned()));
+    }
+    assert_eq!(
+        warnings,
+        vec![(
+            Some(LintCode::AmbiguousCall),
+            "Call to is_member in module billing is ambiguous: it calls billing::is_member, \
+             not the global rule is_member"
+                .to_owned()
+        )]
+    );
+
+    p.load_str(r#"allow(user) if billing::has_plan(user);"#)?;
+    qeval(&mut p, r#"allow("alice")"#);
+    qnull(&mut p, r#"allow("bob")"#);
+    // The global is_member is not the module's.
+    qeval(&mut p, r#"is_member("bob")"#);
+
+    let err = p
+        .load(
+            "f(user) if billing::is_member(user);",
+            Some("f.polar".to_string()),
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Rule billing::is_member is private to module billing; export is_member from it to \
+         call it here at line 1, column 12 in file f.polar"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_module_calls_do_not_depend_on_load_order() -> TestResult {
+    let module = indoc!(
+        r#"module billing;
+           export has_plan;
+           has_plan(user) if is_member(user) and is_admin(user);"#
+    );
+    let global = r#"is_member("alice"); is_admin("alice");"#;
+    let own = indoc!(
+        r#"module billing;
+           is_admin(_user) if false;"#
+    );
+    for sources in &[
+        [module, global, own],
+        [global, own, module],
+        [own, module, global],
+    ] {
+        let mut p = Polar::new();
+        for src in sources {
+            p.load_str(src)?;
+        }
+        // `is_member` is only defined globally, and `is_admin` in the module too.
+        let kb = p.kb.read().unwrap();
+        let has_plan = kb.rules[&sym!("billing::has_plan")].rules();
+        assert_eq!(
+            has_plan[0].body.to_polar(),
+            "is_member(user) and billing::is_admin(user)"
+        );
+        drop(kb);
+        qnull(&mut p, r#"billing::has_plan("alice")"#);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_unicode_strings() -> TestResult {
+    let mut p = Polar::new();
+    p.load_str(
+        r#"/* Résumés are /* only */ readable by their owners. */
+           owner("caf\u{e9}", "Zoë");"#,
+    )?;
+    qeval(&mut p, r#"owner("café", "Zo\u{eb}")"#);
+    // A precomposed character is not the same as a decomposed one.
+    qnull(&mut p, r#"owner("cafe\u{301}", _)"#);
+
+    let err = p
+        .load(r#"f("Zoë", "Zo\ë");"#, Some("names.polar".to_string()))
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "'ë' is not a valid character. Found in Zo at line 1, column 14 in file names.polar"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_multiple_parse_errors() {
+    let p = Polar::new();
+    let err = p
+        .load(
+            "f(x) if x = ;\ng(x) if true;\nh(x) if x ==;",
+            Some("errors.polar".to_string()),
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "found 2 syntax errors:\n  \
+         did not expect to find the token ';' at line 1, column 13 in file errors.polar\n  \
+         did not expect to find the token ';' at line 3, column 13 in file errors.polar"
+    );
+    if let ErrorKind::Parse(ParseError::Multiple { errors }) = err.kind {
+        let rows = errors
+            .iter()
+            .map(|e| e.context.as_ref().unwrap().row)
+            .collect::<Vec<_>>();
+        assert_eq!(rows, vec![0, 2]);
+    } else {
+        panic!("expected multiple parse errors");
+    }
+
+    // Nothing is loaded from a source with errors.
+    assert!(p.kb.read().unwrap().rules.is_empty());
+}
+
 #[test]
 fn test_rest_vars() -> TestResult {
     let mut p = Polar::new();
@@ -1661,6 +2086,761 @@ fn test_forall() -> TestResult {
     Ok(())
 }
 
+#[test]
+fn test_aggregates() -> TestResult {
+    let mut p = Polar::new();
+    p.load_str(
+        r#"g(1);
+           g(2);
+           g(3);
+           g(2);"#,
+    )?;
+    qvar(
+        &mut p,
+        "findall(x, g(x), xs)",
+        "xs",
+        vec![value!([1, 2, 3, 2])],
+    );
+    qvar(
+        &mut p,
+        "findall([x, y], g(x) and x > 2 and y = x * 2, xs)",
+        "xs",
+        vec![value!([value!([3, 6])])],
+    );
+    qvar(
+        &mut p,
+        "findall(x, g(x) and x > 3, xs)",
+        "xs",
+        vec![value!([])],
+    );
+    qvar(&mut p, "count(x, g(x), n)", "n", vec![value!(4)]);
+    qvar(&mut p, "count(x, g(x) and x = 2, n)", "n", vec![value!(2)]);
+    qvar(&mut p, "count(x, g(x) and x > 3, n)", "n", vec![value!(0)]);
+    qvar(&mut p, "sum(x, g(x), n)", "n", vec![value!(8)]);
+    qvar(&mut p, "sum(x, x in [1, 2.5], n)", "n", vec![value!(3.5)]);
+    qvar(&mut p, "sum(x, g(x) and x > 3, n)", "n", vec![value!(0)]);
+    qvar(&mut p, "min(x, g(x), n)", "n", vec![value!(1)]);
+    qvar(&mut p, "max(x, g(x), n)", "n", vec![value!(3)]);
+    qvar(
+        &mut p,
+        r#"max(x, x in ["b", "c", "a"], n)"#,
+        "n",
+        vec![value!("c")],
+    );
+    qnull(&mut p, "min(x, g(x) and x > 3, n)");
+    qnull(&mut p, "max(x, g(x) and x > 3, n)");
+
+    // The result may already be bound.
+    qeval(&mut p, "count(x, g(x), 4)");
+    qnull(&mut p, "count(x, g(x), 3)");
+
+    // The template is not bound outside the aggregate.
+    qvar(&mut p, "count(x, g(x), _) and x = 5", "x", vec![value!(5)]);
+
+    // Variables bound outside the aggregate are visible inside it.
+    qvar(
+        &mut p,
+        "y = 2 and count(x, g(x) and x >= y, n)",
+        "n",
+        vec![value!(3)],
+    );
+
+    // Aggregates backtrack like any other goal.
+    p.load_str(
+        r#"parent("a", "b");
+           parent("a", "c");
+           parent("b", "d");
+           children(p, n) if p in ["a", "b", "d"] and count(c, parent(p, c), n);"#,
+    )?;
+    qvars(
+        &mut p,
+        "children(p, n)",
+        &["p", "n"],
+        vec![
+            vec![value!("a"), value!(2)],
+            vec![value!("b"), value!(1)],
+            vec![value!("d"), value!(0)],
+        ],
+    );
+
+    // Lookups in the template are made for each solution to the goal.
+    qvar(
+        &mut p,
+        "findall(x.name, x in [{name: \"a\"}, {name: \"b\"}], names)",
+        "names",
+        vec![value!([value!("a"), value!("b")])],
+    );
+
+    qruntime!(
+        &mut p,
+        "sum(x, x in [1, \"a\"], n)",
+        RuntimeError::TypeError { .. }
+    );
+    qruntime!(
+        &mut p,
+        "min(x, x in [1, \"a\"], n)",
+        RuntimeError::Unsupported { .. }
+    );
+    Ok(())
+}
+
+#[test]
+fn test_builtin_rules() -> TestResult {
+    // A call with the name and arity of a builtin is the builtin, so no rule can be defined
+    // with them.
+    let err = Polar::new()
+        .load_str("f(1);\nmax(a, b, c) if c = a;")
+        .unwrap_err();
+    assert!(matches!(
+        err.kind,
+        ErrorKind::Parse(ParseError::BuiltinRule { .. })
+    ));
+    assert_eq!(
+        err.to_string(),
+        "max/3 is a builtin aggregate and cannot be defined as a rule at line 2, column 1"
+    );
+    for src in &[
+        "findall(x, y, z);",
+        "count(x, y, z);",
+        "sum(x, y, z);",
+        "min(x, y, z);",
+        "type max(x, y, z);",
+        "@table\nmax(x, y, z);",
+        "regex_match(p, s);",
+        "regex_match(p, s, groups) if groups = [];",
+        "format(s);",
+        "format(template, a, b);",
+        "union(a, b, c);",
+        "intersection(a, b, c);",
+        "difference(a, b, c);",
+        "subset(a, b) if a = b;",
+        "set();",
+        "timestamp(s) if s = 1;",
+        "duration(_: String);",
+        "now();",
+    ] {
+        qparse!(src, ParseError::BuiltinRule { .. });
+    }
+
+    // Other arities are rules.
+    let mut p = Polar::new();
+    p.load_str("max(a, b) if a > b;\nmax(a, b) if a <= b;\ncount(1);\nregex_match(1);")?;
+    qeval(&mut p, "max(2, 1)");
+    qeval(&mut p, "count(1)");
+    qeval(&mut p, "regex_match(1)");
+    p.load_str("format();\nunion(1);\nsubset(1, 2, 3);\nset(1);")?;
+    qeval(&mut p, "format()");
+    qeval(&mut p, "union(1) and subset(1, 2, 3) and set(1)");
+    p.load_str("timestamp();\nduration(1, 2);\nnow(1);")?;
+    qeval(&mut p, "timestamp() and duration(1, 2) and now(1)");
+    Ok(())
+}
+
+#[test]
+fn test_aggregate_external_calls() -> TestResult {
+    let p = Polar::new();
+    p.register_constant(sym!("Foo"), term!(true));
+    let q = p.new_query(
+        "findall(y, x in [1, 2, 3] and y = (new Foo()).bar(x) and y > 1, ys)",
+        false,
+    )?;
+    let mut bars = vec![term!(2), term!(1), term!(3)];
+    let results = query_results!(q, |_, _, _, _, _| bars.pop());
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].0[&sym!("ys")], value!([3, 2]));
+    assert!(bars.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_tabling() -> TestResult {
+    let mut p = Polar::new();
+    p.load_str(
+        r#"edge(1, 2);
+           edge(2, 3);
+           edge(3, 1);
+           edge(3, 4);
+
+           @table
+           left(x, y) if left(x, z) and edge(z, y);
+           left(x, y) if edge(x, y);
+
+           @table
+           right(x, y) if edge(x, z) and right(z, y);
+           right(x, y) if edge(x, y);"#,
+    )?;
+
+    // Left recursion terminates.
+    qeval(&mut p, "count(y, left(1, y), 4)");
+    qeval(&mut p, "left(1, 4)");
+    qnull(&mut p, "left(4, 1)");
+    qeval(&mut p, "count([x, y], left(x, y), 12)");
+
+    // Right recursion terminates on cycles.
+    qeval(&mut p, "count(y, right(1, y), 4)");
+    qeval(&mut p, "count(y, right(2, y), 4)");
+    qnull(&mut p, "right(4, y)");
+    qeval(&mut p, "count([x, y], right(x, y), 12)");
+
+    // Answers are the same with or without tabling.
+    qeval(&mut p, "left(3, 2) and right(3, 2)");
+    qvars(
+        &mut p,
+        "left(x, 4) and x < 3",
+        &["x"],
+        vec![vec![value!(2)], vec![value!(1)]],
+    );
+
+    // Each call is evaluated once per query.
+    p.load_str(
+        r#"@table
+           fib(0, 0);
+           fib(1, 1);
+           fib(n, f) if n > 1 and fib(n - 1, a) and fib(n - 2, b) and f = a + b;"#,
+    )?;
+    qvar(&mut p, "fib(50, f)", "f", vec![value!(12586269025)]);
+    Ok(())
+}
+
+#[test]
+fn test_regex_match() -> TestResult {
+    let mut p = Polar::new();
+    p.load_str(
+        r#"corp_email(email) if regex_match("@corp\\.com$", email);
+           settings(path, project) if
+               regex_match("^/projects/([^/]+)/settings$", path, [_, project]);"#,
+    )?;
+    qeval(&mut p, r#"corp_email("alice@corp.com")"#);
+    qnull(&mut p, r#"corp_email("alice@corp.co")"#);
+    qnull(&mut p, r#"corp_email("alice@corpxcom")"#);
+    qvar(
+        &mut p,
+        r#"settings("/projects/oso/settings", project)"#,
+        "project",
+        vec![value!("oso")],
+    );
+    qnull(&mut p, r#"settings("/projects/oso/members", project)"#);
+
+    // Groups that do not take part in the match are empty.
+    qvar(
+        &mut p,
+        r#"regex_match("a(b)?(c)", "xac", groups)"#,
+        "groups",
+        vec![value!(["ac", "", "c"])],
+    );
+
+    // Patterns may be bound at runtime.
+    qeval(&mut p, r#"x = "^[a-z]+$" and regex_match(x, "abc")"#);
+
+    qruntime!(
+        &mut p,
+        r#"regex_match(1, "abc")"#,
+        RuntimeError::TypeError { .. }
+    );
+    qruntime!(
+        &mut p,
+        r#"regex_match("[a-", "abc")"#,
+        RuntimeError::InvalidRegex { pattern, .. },
+        pattern == "[a-"
+    );
+
+    // Invalid patterns are reported with the source of the call.
+    p.load_str(r#"bad(x) if regex_match("(", x);"#)?;
+    let error = p.new_query(r#"bad("x")"#, false)?.next_event().unwrap_err();
+    assert!(matches!(
+        error.kind,
+        ErrorKind::Runtime(RuntimeError::InvalidRegex { .. })
+    ));
+    assert!(
+        error.to_string().contains("at line 1, column 11"),
+        "{}",
+        error
+    );
+    Ok(())
+}
+
+#[test]
+fn test_string_concatenation_and_format() -> TestResult {
+    let mut p = Polar::new();
+    p.load_str(
+        r#"org_key(org, key) if key = "org:" + org.name;
+           resource_path(t, r, path) if path = f"tenants/{t.id}/resources/{r}";"#,
+    )?;
+    qvar(&mut p, r#"x = "a" + "b""#, "x", vec![value!("ab")]);
+    qvar(
+        &mut p,
+        r#"org_key({name: "acme"}, key)"#,
+        "key",
+        vec![value!("org:acme")],
+    );
+    qvar(
+        &mut p,
+        r#"x = format("{}/{}", "a", 1)"#,
+        "x",
+        vec![value!("a/1")],
+    );
+    qvar(
+        &mut p,
+        r#"x = format("{{{}}}", "a")"#,
+        "x",
+        vec![value!("{a}")],
+    );
+    qvar(
+        &mut p,
+        r#"resource_path({id: "acme"}, 7, path)"#,
+        "path",
+        vec![value!("tenants/acme/resources/7")],
+    );
+    qvar(
+        &mut p,
+        r#"x = f"{ {a: "}"}.a }{{}}\n""#,
+        "x",
+        vec![value!("}{}\n")],
+    );
+    qeval(&mut p, r#"format("{}", "a") = "a""#);
+    qnull(&mut p, r#"format("{}", "a") = "b""#);
+
+    qruntime!(&mut p, r#"x = "a" + [1]"#, RuntimeError::Unsupported { .. });
+    qruntime!(&mut p, r#"x = "a" + 1"#, RuntimeError::Unsupported { .. });
+    qruntime!(
+        &mut p,
+        r#"x = true + "a""#,
+        RuntimeError::Unsupported { .. }
+    );
+    qruntime!(
+        &mut p,
+        r#"org_key({name: 1}, key)"#,
+        RuntimeError::Unsupported { .. }
+    );
+    qruntime!(
+        &mut p,
+        r#"x = format("{}/{}", 1)"#,
+        RuntimeError::TypeError { .. }
+    );
+    qruntime!(
+        &mut p,
+        r#"x = format("{}", 1, 2)"#,
+        RuntimeError::TypeError { .. }
+    );
+    qruntime!(
+        &mut p,
+        r#"x = format("{", 1)"#,
+        RuntimeError::TypeError { .. }
+    );
+    qruntime!(
+        &mut p,
+        r#"x = format(1, 2)"#,
+        RuntimeError::TypeError { .. }
+    );
+    Ok(())
+}
+
+#[test]
+fn test_builtin_methods() -> TestResult {
+    let mut p = Polar::new();
+    p.load_str(r#"admin_email(email) if email.lower().ends_with("@corp.com");"#)?;
+    qeval(&mut p, r#"admin_email("Alice@CORP.com")"#);
+    qnull(&mut p, r#"admin_email("alice@corp.co")"#);
+
+    qvar(&mut p, r#"x = "héllo".len()"#, "x", vec![value!(5)]);
+    qeval(&mut p, r#""hello".contains("ell")"#);
+    qeval(
+        &mut p,
+        r#""hello".starts_with("he") and not "hello".starts_with("lo")"#,
+    );
+    qvar(&mut p, r#"x = "Hello".upper()"#, "x", vec![value!("HELLO")]);
+    qvar(
+        &mut p,
+        r#"x = "hello".slice(1, 3)"#,
+        "x",
+        vec![value!("el")],
+    );
+    qvar(&mut p, r#"x = "hello".slice(-3)"#, "x", vec![value!("llo")]);
+
+    qvar(&mut p, "x = [1, 2, 3].len()", "x", vec![value!(3)]);
+    qeval(&mut p, "y = 2 and [1, y, 3].contains(2)");
+    qnull(&mut p, "[1, 2, 3].contains(4)");
+    qvar(&mut p, "x = [1, 2, 3].slice(1)", "x", vec![value!([2, 3])]);
+    qvar(
+        &mut p,
+        "x = [1, 2, 3].slice(0, -1)",
+        "x",
+        vec![value!([1, 2])],
+    );
+    qvar(&mut p, "x = [1, 2, 3].slice(2, 10)", "x", vec![value!([3])]);
+    qvar(&mut p, "x = [1, 2, 3].slice(2, 1)", "x", vec![value!([])]);
+
+    qvar(
+        &mut p,
+        "x = {b: 2, a: 1}.keys()",
+        "x",
+        vec![value!(["a", "b"])],
+    );
+    qvar(
+        &mut p,
+        "x = {b: 2, a: 1}.values()",
+        "x",
+        vec![value!([1, 2])],
+    );
+    qvar(&mut p, "x = {b: 2, a: 1}.len()", "x", vec![value!(2)]);
+
+    qruntime!(
+        &mut p,
+        r#""hello".starts_with(1)"#,
+        RuntimeError::TypeError { .. }
+    );
+    qruntime!(
+        &mut p,
+        r#"x = "hello".len(1)"#,
+        RuntimeError::TypeError { .. }
+    );
+    qruntime!(
+        &mut p,
+        r#"x = [1].slice("a")"#,
+        RuntimeError::TypeError { .. }
+    );
+    Ok(())
+}
+
+#[test]
+fn test_sets() -> TestResult {
+    let mut p = Polar::new();
+    p.load_str(
+        r#"shares_group(user, resource) if
+               intersection(user.groups, resource.groups, shared) and shared.len() > 0;"#,
+    )?;
+    let set = |elements: Vec<Term>| value!(Set::new(elements));
+
+    qvar(
+        &mut p,
+        "x = {3, 1, 2, 1}",
+        "x",
+        vec![set(vec![term!(1), term!(2), term!(3)])],
+    );
+    qvar(
+        &mut p,
+        "y = 2 and x = {y, 1}",
+        "x",
+        vec![set(vec![term!(1), term!(2)])],
+    );
+    qvar(&mut p, "x = set()", "x", vec![set(vec![])]);
+    qeval(&mut p, "{1, 2} = {2, 1} and {1, 2} == {2, 1, 1}");
+    qnull(&mut p, "{1, 2} = {1, 3}");
+    qnull(&mut p, "{1, 2} != {2, 1}");
+    // Variables in set literals are bound before the sets are compared.
+    qeval(&mut p, "x = 1 and {x, 2} == {1, 2}");
+    qeval(&mut p, "x = 2 and s = {x, 1} and s == {1, 2}");
+    qnull(&mut p, "x = 2 and {x, 1} != {1, 2}");
+    qvar(
+        &mut p,
+        r#"x in {"b", "a"}"#,
+        "x",
+        vec![value!("a"), value!("b")],
+    );
+    qeval(&mut p, "[1, 2] in {[1, 2], [3]}");
+    qnull(&mut p, "3 in {1, 2}");
+    qvar(&mut p, "x = {1, 2}.len()", "x", vec![value!(2)]);
+    qeval(&mut p, "{1, 2}.contains(2)");
+
+    qvar(
+        &mut p,
+        "union({1, 2}, [2, 3], x)",
+        "x",
+        vec![set(vec![term!(1), term!(2), term!(3)])],
+    );
+    qvar(
+        &mut p,
+        "intersection({1, 2, 3}, [2, 3, 4], x)",
+        "x",
+        vec![set(vec![term!(2), term!(3)])],
+    );
+    qvar(
+        &mut p,
+        "difference({1, 2, 3}, {2}, x)",
+        "x",
+        vec![set(vec![term!(1), term!(3)])],
+    );
+    qeval(&mut p, "difference({1}, [1], set())");
+    qeval(&mut p, "subset({1, 2}, [3, 2, 1]) and subset(set(), {1})");
+    qnull(&mut p, "subset({1, 4}, {1, 2, 3})");
+
+    qeval(
+        &mut p,
+        r#"shares_group({groups: ["eng", "ops"]}, {groups: {"ops", "sales"}})"#,
+    );
+    qnull(
+        &mut p,
+        r#"shares_group({groups: ["eng"]}, {groups: {"ops", "sales"}})"#,
+    );
+
+    qruntime!(&mut p, "union(1, {1}, x)", RuntimeError::TypeError { .. });
+    qruntime!(&mut p, "{x} = {1}", RuntimeError::Unsupported { .. });
+    qruntime!(&mut p, "{x} == {1}", RuntimeError::Unsupported { .. });
+    Ok(())
+}
+
+#[test]
+fn test_timestamps_and_durations() -> TestResult {
+    let mut p = Polar::new();
+    p.load_str(r#"allow(grant) if now() < grant.expiry - duration("1m");"#)?;
+    let timestamp = |s| Value::Timestamp(Timestamp::parse(s).unwrap());
+    let duration = |s| Value::Duration(Duration::parse(s).unwrap());
+
+    qvar(
+        &mut p,
+        r#"x = timestamp("2021-06-01T11:00:00+02:00")"#,
+        "x",
+        vec![timestamp("2021-06-01T09:00:00Z")],
+    );
+    qeval(
+        &mut p,
+        r#"timestamp("2021-06-01T09:00:00Z") < timestamp("2021-06-01T09:00:00.5Z")"#,
+    );
+    qeval(
+        &mut p,
+        r#"duration("90m") == duration("1h30m") and duration("1s") > duration("999ms")"#,
+    );
+    qeval(
+        &mut p,
+        r#"timestamp("9999-12-31T23:59:59Z") - duration("1d") > timestamp("2262-04-12T00:00:00Z")"#,
+    );
+    qvar(
+        &mut p,
+        r#"x = timestamp("2021-06-01T09:00:00Z") + duration("1d")"#,
+        "x",
+        vec![timestamp("2021-06-02T09:00:00Z")],
+    );
+    qvar(
+        &mut p,
+        r#"x = timestamp("2021-06-01T09:00:00Z") - timestamp("2021-06-01T10:30:00Z")"#,
+        "x",
+        vec![duration("-1h30m")],
+    );
+    qvar(
+        &mut p,
+        r#"x = duration("1h") - duration("1m") + duration("1s")"#,
+        "x",
+        vec![duration("59m1s")],
+    );
+
+    // `now()` is the same throughout a query.
+    qeval(
+        &mut p,
+        r#"now() = now() and now() > timestamp("2021-01-01T00:00:00Z")"#,
+    );
+    let expiring = r#"allow({expiry: timestamp("2021-06-01T09:00:00Z")})"#;
+    let mut q = p.new_query(expiring, false)?;
+    q.set_now(Timestamp::parse("2021-06-01T08:58:59Z").unwrap());
+    assert_eq!(query_results!(q).len(), 1);
+    let mut q = p.new_query(expiring, false)?;
+    q.set_now(Timestamp::parse("2021-06-01T08:59:00Z").unwrap());
+    assert!(query_results!(q).is_empty());
+
+    // The pinned time is also used by negations, aggregates and tabled rules.
+    p.load_str(
+        r#"@table
+           before_noon() if now() < timestamp("2021-06-01T12:00:00Z");"#,
+    )?;
+    let pinned = |p: &mut Polar, query| -> Result<QueryResults, PolarError> {
+        let mut q = p.new_query(query, false)?;
+        q.set_now(Timestamp::parse("2021-06-01T09:00:00Z").unwrap());
+        Ok(query_results!(q))
+    };
+    assert_eq!(
+        pinned(&mut p, r#"not now() > timestamp("2021-06-01T10:00:00Z")"#)?.len(),
+        1
+    );
+    let results = pinned(&mut p, "findall(t, t = now(), ts)")?;
+    assert_eq!(
+        results[0].0[&sym!("ts")],
+        value!([timestamp("2021-06-01T09:00:00Z")])
+    );
+    assert_eq!(pinned(&mut p, "before_noon()")?.len(), 1);
+
+    qruntime!(
+        &mut p,
+        r#"x = timestamp("yesterday")"#,
+        RuntimeError::TypeError { .. }
+    );
+    qruntime!(
+        &mut p,
+        r#"x = duration(60)"#,
+        RuntimeError::TypeError { .. }
+    );
+    qruntime!(
+        &mut p,
+        r#"timestamp("2021-06-01T09:00:00Z") < duration("1s")"#,
+        RuntimeError::Unsupported { .. }
+    );
+    qruntime!(
+        &mut p,
+        r#"x = timestamp("2021-06-01T09:00:00Z") + 1"#,
+        RuntimeError::Unsupported { .. }
+    );
+    qruntime!(
+        &mut p,
+        r#"x = duration("9223372036854775807s") + duration("1s")"#,
+        RuntimeError::ArithmeticError { .. }
+    );
+    qruntime!(
+        &mut p,
+        r#"x = timestamp("9999-12-31T23:59:59Z") + duration("1000000h")"#,
+        RuntimeError::ArithmeticError { .. }
+    );
+    qruntime!(
+        &mut p,
+        r#"x = timestamp("0000-01-01T00:00:00Z") - duration("1ns")"#,
+        RuntimeError::ArithmeticError { .. }
+    );
+    Ok(())
+}
+
+#[test]
+fn test_policy_constants() -> TestResult {
+    let mut p = Polar::new();
+    p.load_str(indoc! {r#"
+        const ADMIN_ROLES = ["owner", "admin"];
+        const OWNER = "owner";
+        const LIMITS = {daily: 100, monthly: 1000};
+        const GRACE = duration("7d");
+
+        is_admin(role) if role in ADMIN_ROLES;
+        is_owner(_role: OWNER);
+        all_admins(roles) if roles matches ADMIN_ROLES;
+        daily_limit(limits: LIMITS, limits.daily);
+        has_limits(x) if x matches {limits: LIMITS};
+        grace_period(GRACE);
+    "#})?;
+
+    qeval(&mut p, r#"is_admin("admin")"#);
+    qnull(&mut p, r#"is_admin("member")"#);
+    qeval(&mut p, r#"is_owner("owner")"#);
+    qnull(&mut p, r#"is_owner("admin")"#);
+    qeval(&mut p, r#"all_admins(["owner", "admin"])"#);
+    qvar(
+        &mut p,
+        "daily_limit({daily: 100, monthly: 1000, yearly: 5000}, x)",
+        "x",
+        values![100],
+    );
+    qeval(&mut p, "has_limits({limits: {daily: 100, monthly: 1000}})");
+    qnull(&mut p, "has_limits({limits: {daily: 1}})");
+    qeval(&mut p, r#"grace_period(d) and d = duration("168h")"#);
+    qeval(&mut p, r#""owner" matches OWNER and OWNER = "owner""#);
+
+    // Constants are defined once, whether by a policy or by the host.
+    let err = p.load_str("const OWNER = \"admin\";").unwrap_err();
+    assert!(matches!(
+        err.kind,
+        ErrorKind::Parse(ParseError::Constant { .. })
+    ));
+    assert_eq!(
+        err.to_string(),
+        "Constant OWNER is already defined at line 1, column 7"
+    );
+    qparse!("const X = 1;\nconst X = 2;", ParseError::Constant { .. });
+    let p2 = Polar::new();
+    p2.register_constant(sym!("Y"), term!(true));
+    assert!(matches!(
+        p2.load_str("const Y = false;").unwrap_err().kind,
+        ErrorKind::Parse(ParseError::Constant { .. })
+    ));
+    qparse!("const X = y;", ParseError::Constant { .. });
+    qparse!("const X = 1 + 2;", ParseError::Constant { .. });
+    qparse!("const X = [1, *rest];", ParseError::Constant { .. });
+    qparse!("constant X = 1;", ParseError::UnrecognizedToken { .. });
+
+    // A constant in a specializer is a value, not a class.
+    let p2 = Polar::new();
+    p2.load_str(indoc! {r#"
+        const VIEWER = "viewer";
+        type has_role(role: String);
+        has_role(_role: VIEWER);
+    "#})?;
+    assert!(p2.next_message().is_none());
+
+    // Policy constants are cleared with the rules.
+    p.clear_rules();
+    p.load_str(r#"const OWNER = "admin"; is_owner(_role: OWNER);"#)?;
+    qeval(&mut p, r#"is_owner("admin")"#);
+    Ok(())
+}
+
+#[test]
+fn test_decimals() -> TestResult {
+    let mut p = Polar::new();
+    p.load_str("under_limit(spent, price) if spent + price <= 100.00d;")?;
+    let decimal = |s: &str| Value::Number(Numeric::Decimal(s.parse().unwrap()));
+
+    qeval(&mut p, "0.1d + 0.2d == 0.3d");
+    qnull(&mut p, "0.1 + 0.2 == 0.3");
+    qvar(&mut p, "x = 19.99d * 3", "x", vec![decimal("59.97")]);
+    qvar(&mut p, "x = -10.00d / 4", "x", vec![decimal("-2.50")]);
+    qvar(&mut p, "x = 1.5d + 0.25", "x", vec![value!(1.75)]);
+    qeval(&mut p, "1.50d == 1.5 and 2.0d = 2 and 0.1d < 0.1");
+    qeval(&mut p, "{1.50d, 1.5d, 1.5}.len() = 1");
+    qeval(&mut p, "under_limit(99.89d, 0.11d)");
+    qnull(&mut p, "under_limit(99.90d, 0.11d)");
+    qeval(&mut p, "under_limit(99, 1)");
+
+    qruntime!(
+        &mut p,
+        "x = 99999999999999999999999999999999999999d + 1",
+        RuntimeError::ArithmeticError { .. }
+    );
+    qruntime!(&mut p, "x = 1d / 0", RuntimeError::ArithmeticError { .. });
+    qparse!(
+        "f(1.00000000000000000000000000001d);",
+        ParseError::IntegerOverflow { .. }
+    );
+    Ok(())
+}
+
+#[test]
+fn test_if_then_else() -> TestResult {
+    let mut p = Polar::new();
+    p.load_str(indoc! {r#"
+        role(1, "admin");
+        role(1, "member");
+        role(2, "member");
+
+        level(user, level) if
+            if role(user, role) then level = role else level = "guest";
+        access(user, access) if
+            if role(user, "admin") then access = "all" else access = "own";
+        strict(user) if if role(user, _) then user = 2 else true;
+        sign(x, s) if
+            if x < 0 then s = -1 else (if x = 0 then s = 0 else s = 1);
+        grouped(x) if (if x = 1 then true else x = 2) and x < 10;
+    "#})?;
+
+    // The consequent sees the bindings of the first answer to the condition.
+    qvar(&mut p, "level(1, level)", "level", values!["admin"]);
+    qvar(&mut p, "level(2, level)", "level", values!["member"]);
+    qvar(&mut p, "level(3, level)", "level", values!["guest"]);
+    qvar(&mut p, "access(1, access)", "access", values!["all"]);
+    qvar(&mut p, "access(2, access)", "access", values!["own"]);
+
+    // A failing consequent does not fall through to the alternative.
+    qnull(&mut p, "strict(1)");
+    qeval(&mut p, "strict(2)");
+    qeval(&mut p, "strict(3)");
+
+    qvar(&mut p, "sign(-5, s)", "s", values![-1]);
+    qvar(&mut p, "sign(0, s)", "s", values![0]);
+    qvar(&mut p, "sign(5, s)", "s", values![1]);
+    qvar(&mut p, "x in [1, 2, 3] and grouped(x)", "x", values![1, 2]);
+
+    // Choices made before the conditional survive it.
+    qvar(
+        &mut p,
+        "x in [1, 2] and (if x = 1 then y = \"a\" else y = \"b\")",
+        "y",
+        values!["a", "b"],
+    );
+    Ok(())
+}
+
 #[test]
 fn test_emoji_policy() -> TestResult {
     let mut p = Polar::new();
@@ -74,7 +74,54 @@ mod tests {
                 operator: Operator::And,
                 args: vec![dict.clone(), dict.clone(), dict],
             })),
+            doc: None,
+            annotations: vec![],
         };
         eprintln!("{}", rule);
     }
+
+    #[test]
+    fn serialize_set_test() {
+        let set = Term::new_from_test(Value::Set(Set::new(vec![term!("b"), term!(1), term!("b")])));
+        let json =
+            r#"{"value":{"Set":[{"value":{"Number":{"Integer":1}}},{"value":{"String":"b"}}]}}"#;
+        assert_eq!(serde_json::to_string(&set).unwrap(), json);
+
+        // Sets from the host are put in canonical order.
+        let term: Term = serde_json::from_str(
+            r#"{"value":{"Set":[{"value":{"String":"b"}},{"value":{"Number":{"Integer":1}}},{"value":{"String":"b"}}]}}"#,
+        )
+        .unwrap();
+        assert_eq!(term, set);
+        assert_eq!(serde_json::to_string(&term).unwrap(), json);
+    }
+
+    #[test]
+    fn serialize_time_test() {
+        // Timestamps and durations are serialized as seconds and nanoseconds.
+        let list = Term::new_from_test(Value::List(vec![
+            term!(Value::Timestamp(Timestamp {
+                seconds: 1_622_538_000,
+                nanos: 500,
+            })),
+            term!(Value::Duration(Duration {
+                seconds: -2,
+                nanos: 500_000_000,
+            })),
+        ]));
+        let json = r#"{"value":{"List":[{"value":{"Timestamp":{"seconds":1622538000,"nanos":500}}},{"value":{"Duration":{"seconds":-2,"nanos":500000000}}}]}}"#;
+        assert_eq!(serde_json::to_string(&list).unwrap(), json);
+        assert_eq!(serde_json::from_str::<Term>(json).unwrap(), list);
+    }
+
+    #[test]
+    fn deserialize_rule_without_annotations_test() {
+        // Rules serialized before annotations existed still deserialize.
+        let rule: Rule = serde_json::from_str(
+            r#"{"name":"f","params":[],"body":{"value":{"Boolean":true}},"doc":null}"#,
+        )
+        .unwrap();
+        assert_eq!(rule.name, Symbol::new("f"));
+        assert!(rule.annotations.is_empty());
+    }
 }
@@ -0,0 +1,128 @@
+//! Errors and warnings found by loading a document into Polar.
+
+use polar_core::error::{ErrorKind, ParseError, PolarError};
+use polar_core::messages::{Message, MessageKind, Severity};
+use polar_core::polar::Polar;
+use polar_core::warnings::LintCode;
+use std::collections::BTreeMap;
+
+use crate::document::Document;
+use crate::protocol::{Diagnostic, DiagnosticSeverity, Position, Range};
+
+const SOURCE: &str = "polar";
+
+/// Load every open document into one knowledge base and report the errors and warnings found,
+/// by document.
+pub fn diagnostics(documents: &BTreeMap<String, Document>) -> BTreeMap<String, Vec<Diagnostic>> {
+    let polar = Polar::new();
+    // Classes are registered by the application at runtime, so every class looks unknown here.
+    polar.ignore_warning(LintCode::UnknownSpecializer);
+
+    let mut diagnostics: BTreeMap<String, Vec<Diagnostic>> = BTreeMap::new();
+    // Polar refuses to load the same text twice, so a copy of a document shares the
+    // diagnostics of the first document with that text.
+    let mut copies = vec![];
+    for (uri, document) in documents {
+        let loaded = documents
+            .iter()
+            .take_while(|(other_uri, _)| *other_uri != uri)
+            .find(|(_, other)| other.text == document.text);
+        if let Some((original, _)) = loaded {
+            copies.push((uri, original));
+            continue;
+        }
+        let found = diagnostics.entry(uri.clone()).or_default();
+        if let Err(error) = polar.load(&document.text, Some(uri.clone())) {
+            error_diagnostics(document, error, found);
+        }
+    }
+    // Calls between documents are only checked once every document is loaded.
+    polar.check_knowledge_base();
+    while let Some(message) = polar.next_message() {
+        let uri = match message
+            .range
+            .as_ref()
+            .and_then(|range| range.filename.as_ref())
+        {
+            Some(uri) => uri.clone(),
+            None => continue,
+        };
+        if let Some(document) = documents.get(&uri) {
+            let found = diagnostics.entry(uri).or_default();
+            found.extend(warning_diagnostic(document, message));
+        }
+    }
+    for (uri, original) in copies {
+        let found = diagnostics.get(original).cloned().unwrap_or_default();
+        diagnostics.insert(uri.clone(), found);
+    }
+    diagnostics
+}
+
+fn error_diagnostics(document: &Document, error: PolarError, diagnostics: &mut Vec<Diagnostic>) {
+    if let ErrorKind::Parse(ParseError::Multiple { errors }) = error.kind {
+        for error in errors {
+            error_diagnostics(document, error, diagnostics);
+        }
+        return;
+    }
+    let start = error
+        .context
+        .as_ref()
+        .map(|context| document.position_at(context.row, context.column))
+        .unwrap_or_default();
+    let width = match &error.kind {
+        ErrorKind::Parse(ParseError::IntegerOverflow { token, .. })
+        | ErrorKind::Parse(ParseError::UnrecognizedToken { token, .. })
+        | ErrorKind::Parse(ParseError::ExtraToken { token, .. })
+        | ErrorKind::Parse(ParseError::ReservedWord { token, .. })
+        | ErrorKind::Parse(ParseError::InvalidFloat { token, .. }) => {
+            token.encode_utf16().count() as u32
+        }
+        _ => 0,
+    };
+    let end = Position {
+        character: start.character + width,
+        ..start
+    };
+    // `PolarError` appends the location to its message, which the editor shows anyway.
+    let message = match &error.kind {
+        ErrorKind::Parse(e) => e.to_string(),
+        ErrorKind::Runtime(e) => e.to_string(),
+        ErrorKind::Operational(e) => e.to_string(),
+        ErrorKind::Parameter(e) => e.to_string(),
+    };
+    diagnostics.push(Diagnostic {
+        range: Range { start, end },
+        severity: DiagnosticSeverity::Error,
+        code: None,
+        source: SOURCE,
+        message,
+    });
+}
+
+/// Convert a warning about `document` into a diagnostic.
+fn warning_diagnostic(document: &Document, message: Message) -> Option<Diagnostic> {
+    if !matches!(message.kind, MessageKind::Warning) {
+        return None;
+    }
+    let range = match &message.range {
+        Some(range) => Range {
+            start: document.position_at(range.start.row, range.start.column),
+            end: document.position_at(range.end.row, range.end.column),
+        },
+        None => Range::default(),
+    };
+    let severity = match message.severity {
+        Some(Severity::Info) => DiagnosticSeverity::Information,
+        _ => DiagnosticSeverity::Warning,
+    };
+    Some(Diagnostic {
+        range,
+        severity,
+        code: message.code.map(|code| code.as_str().to_owned()),
+        source: SOURCE,
+        // The rest of the message quotes the source, which the editor shows anyway.
+        message: message.msg.lines().next().unwrap_or_default().to_owned(),
+    })
+}
@@ -0,0 +1,245 @@
+//! Index of the rules defined and called in a Polar document.
+
+use polar_core::formatting::ToPolarString;
+use polar_core::parser::{parse_lines, Line};
+use polar_core::rules::{Parameter, Rule};
+use polar_core::terms::{Operation, Operator, Term, Value};
+
+use crate::protocol::{Position, Range};
+
+/// A rule defined in a document.
+#[derive(Clone, Debug)]
+pub struct RuleDefinition {
+    pub name: String,
+    pub arity: usize,
+    /// Head of the rule, e.g., `allow(actor: User, "read", resource)`.
+    pub signature: String,
+    pub name_range: Range,
+    pub range: Range,
+}
+
+/// A call to a rule, from a rule body or an inline query.
+#[derive(Clone, Debug)]
+pub struct RuleCall {
+    pub name: String,
+    pub arity: usize,
+    pub name_range: Range,
+}
+
+pub struct Document {
+    pub text: String,
+    pub rules: Vec<RuleDefinition>,
+    pub calls: Vec<RuleCall>,
+}
+
+impl Document {
+    pub fn new(text: String) -> Self {
+        let mut document = Self {
+            text,
+            rules: vec![],
+            calls: vec![],
+        };
+        document.index();
+        document
+    }
+
+    /// Replace the text of the document. If the new text does not parse, the rules and calls
+    /// of the last version that did are kept, so that navigation keeps working while the
+    /// document is being edited.
+    pub fn update(&mut self, text: String) {
+        self.text = text;
+        self.index();
+    }
+
+    fn index(&mut self) {
+        let lines = match parse_lines(0, &self.text) {
+            Ok(lines) => lines,
+            Err(_) => return,
+        };
+        let mut rules = vec![];
+        let mut calls = vec![];
+        for line in lines {
+            match line {
+                Line::Rule(rule) => {
+                    rules.extend(self.definition(&rule));
+                    self.find_calls(&rule.body, &mut calls);
+                }
+                Line::Query(query) => self.find_calls(&query, &mut calls),
+                // Declarations and resource blocks are neither definitions nor calls.
+                _ => {}
+            }
+        }
+        self.rules = rules;
+        self.calls = calls;
+    }
+
+    fn definition(&self, rule: &Rule) -> Option<RuleDefinition> {
+        let name = &rule.name.0;
+        let (body_left, body_right) = rule.body.span()?;
+        let spans = rule
+            .params
+            .iter()
+            .filter_map(|param| param.parameter.span())
+            .collect::<Vec<_>>();
+        // The parser does not record where the rule name is, but it is the last occurrence of
+        // the name before the first parameter (or before the body, if there are none).
+        let head_end = spans.first().map_or(body_left, |(left, _)| *left);
+        let left = self.text[..head_end].rfind(name.as_str())?;
+        let right = spans
+            .iter()
+            .map(|(_, right)| *right)
+            .chain(Some(body_right))
+            .max()?;
+        let params = rule
+            .params
+            .iter()
+            .map(Parameter::to_polar)
+            .collect::<Vec<_>>();
+        Some(RuleDefinition {
+            name: name.clone(),
+            arity: rule.params.len(),
+            signature: format!("{}({})", name, params.join(", ")),
+            name_range: self.range(left, left + name.len()),
+            range: self.range(left, right),
+        })
+    }
+
+    /// Find the calls in goal position in `term`. Calls made on application instances
+    /// (`x.foo()`) are method calls, not rule calls.
+    fn find_calls(&self, term: &Term, calls: &mut Vec<RuleCall>) {
+        match term.value() {
+            Value::Call(call) => {
+                if let Some((left, _)) = term.span() {
+                    calls.push(RuleCall {
+                        name: call.name.0.clone(),
+                        arity: call.args.len(),
+                        name_range: self.range(left, left + call.name.0.len()),
+                    });
+                }
+            }
+            Value::Expression(Operation {
+                operator:
+                    Operator::And | Operator::Or | Operator::Not | Operator::If | Operator::ForAll,
+                args,
+            }) => {
+                for arg in args {
+                    self.find_calls(arg, calls);
+                }
+            }
+            // The second argument of an aggregate is a goal.
+            Value::Expression(Operation { operator, args }) if operator.is_aggregate() => {
+                self.find_calls(&args[1], calls);
+            }
+            _ => {}
+        }
+    }
+
+    /// Return the name and arity of the rule defined or called at `position`, and the range of
+    /// the name.
+    pub fn rule_at(&self, position: Position) -> Option<(&str, usize, Range)> {
+        let definitions = self
+            .rules
+            .iter()
+            .map(|rule| (rule.name.as_str(), rule.arity, rule.name_range));
+        let calls = self
+            .calls
+            .iter()
+            .map(|call| (call.name.as_str(), call.arity, call.name_range));
+        definitions
+            .chain(calls)
+            .find(|(_, _, range)| range.contains(position))
+    }
+
+    /// Convert a byte offset into the text, as used by the parser, to a position.
+    pub fn position(&self, offset: usize) -> Position {
+        let before = &self.text[..offset.min(self.text.len())];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        Position {
+            line: before.matches('\n').count() as u32,
+            character: before[line_start..].encode_utf16().count() as u32,
+        }
+    }
+
+    pub fn range(&self, left: usize, right: usize) -> Range {
+        Range {
+            start: self.position(left),
+            end: self.position(right),
+        }
+    }
+
+    /// Convert a row and a column counted in characters, as in Polar errors and warnings, to a
+    /// position.
+    pub fn position_at(&self, row: usize, column: usize) -> Position {
+        let line = self.text.split('\n').nth(row).unwrap_or("");
+        let character: usize = line.chars().take(column).map(char::len_utf16).sum();
+        Position {
+            line: row as u32,
+            character: character as u32,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn position(line: u32, character: u32) -> Position {
+        Position { line, character }
+    }
+
+    #[test]
+    fn test_index() {
+        let document = Document::new(
+            "allow(actor: User, \"read\", resource) if\n    owns(actor, resource) or admin(actor);\n\
+             admin(_actor: User{role: \"admin\"});\n\
+             ?= allow(new User(), \"read\", 1) and actor.allowed();"
+                .to_owned(),
+        );
+        let signatures = document
+            .rules
+            .iter()
+            .map(|rule| rule.signature.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            signatures,
+            vec![
+                "allow(actor: User, \"read\", resource)",
+                "admin(_actor: User{role: \"admin\"})"
+            ]
+        );
+        assert_eq!(
+            document.rules[1].name_range,
+            Range {
+                start: position(2, 0),
+                end: position(2, 5)
+            }
+        );
+
+        let calls = document
+            .calls
+            .iter()
+            .map(|call| (call.name.as_str(), call.arity))
+            .collect::<Vec<_>>();
+        assert_eq!(calls, vec![("owns", 2), ("admin", 1), ("allow", 3)]);
+        assert_eq!(
+            document.rule_at(position(1, 31)),
+            Some((
+                "admin",
+                1,
+                Range {
+                    start: position(1, 29),
+                    end: position(1, 34)
+                }
+            ))
+        );
+        assert_eq!(document.rule_at(position(1, 27)), None);
+    }
+
+    #[test]
+    fn test_keeps_index_of_last_valid_text() {
+        let mut document = Document::new("f(x) if g(x);".to_owned());
+        document.update("f(x) if g(x".to_owned());
+        assert_eq!(document.rules.len(), 1);
+        assert_eq!(document.calls.len(), 1);
+    }
+}
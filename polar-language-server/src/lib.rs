@@ -0,0 +1,19 @@
+//! A language server for Polar.
+//!
+//! The server speaks the Language Server Protocol over stdin and stdout, and provides:
+//!
+//! - diagnostics for parse errors and lint warnings, when a document is opened or saved
+//! - go to definition, from a call to every rule it matches
+//! - find references to a rule
+//! - hover, showing the signatures and specializers of the rules a call matches
+//! - document symbols, one per rule
+//!
+//! Rules are resolved across all open documents, by name and number of arguments.
+
+pub mod diagnostics;
+pub mod document;
+pub mod protocol;
+pub mod rpc;
+mod server;
+
+pub use server::{run, Server};
@@ -0,0 +1,11 @@
+use std::io;
+use std::process;
+
+fn main() {
+    let stdin = io::stdin();
+    let stdout = io::stdout();
+    if let Err(e) = polar_language_server::run(stdin.lock(), stdout.lock()) {
+        eprintln!("polar-language-server: {}", e);
+        process::exit(1);
+    }
+}
@@ -0,0 +1,262 @@
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::io::{self, BufRead, Write};
+
+use crate::diagnostics::diagnostics;
+use crate::document::{Document, RuleDefinition};
+use crate::protocol::*;
+use crate::rpc::{read_message, write_message};
+
+/// Serve requests read from `input` until the client sends `exit` or closes the input.
+pub fn run(mut input: impl BufRead, output: impl Write) -> io::Result<()> {
+    let mut server = Server::new(output);
+    while let Some(message) = read_message(&mut input)? {
+        if !server.handle(message)? {
+            break;
+        }
+    }
+    Ok(())
+}
+
+pub struct Server<W: Write> {
+    output: W,
+    /// Open documents by URI.
+    documents: BTreeMap<String, Document>,
+}
+
+impl<W: Write> Server<W> {
+    pub fn new(output: W) -> Self {
+        Self {
+            output,
+            documents: BTreeMap::new(),
+        }
+    }
+
+    /// Handle a message from the client. Returns false once the client has asked the server to
+    /// exit.
+    pub fn handle(&mut self, message: Value) -> io::Result<bool> {
+        let method = message["method"].as_str();
+        let params = message["params"].clone();
+        match (method, message.get("id")) {
+            (Some("exit"), _) => return Ok(false),
+            (Some(method), Some(id)) => {
+                let response = match self.request(method, params) {
+                    Ok(result) => json!({"jsonrpc": "2.0", "id": id, "result": result}),
+                    Err(error) => json!({"jsonrpc": "2.0", "id": id, "error": error}),
+                };
+                write_message(&mut self.output, &response)?;
+            }
+            (Some(method), None) => self.notification(method, params)?,
+            // A response; the server does not send any requests.
+            (None, _) => {}
+        }
+        Ok(true)
+    }
+
+    fn request(&mut self, method: &str, params: Value) -> Result<Value, ResponseError> {
+        match method {
+            "initialize" => Ok(json!({
+                "capabilities": {
+                    "textDocumentSync": {
+                        "openClose": true,
+                        // Full text on every change.
+                        "change": 1,
+                        "save": {"includeText": true},
+                    },
+                    "definitionProvider": true,
+                    "referencesProvider": true,
+                    "hoverProvider": true,
+                    "documentSymbolProvider": true,
+                },
+                "serverInfo": {
+                    "name": env!("CARGO_PKG_NAME"),
+                    "version": env!("CARGO_PKG_VERSION"),
+                },
+            })),
+            "shutdown" => Ok(Value::Null),
+            "textDocument/definition" => Ok(json!(self.definition(parse_params(params)?))),
+            "textDocument/references" => Ok(json!(self.references(parse_params(params)?))),
+            "textDocument/hover" => Ok(json!(self.hover(parse_params(params)?))),
+            "textDocument/documentSymbol" => {
+                Ok(json!(self.document_symbols(parse_params(params)?)))
+            }
+            _ => Err(ResponseError {
+                code: METHOD_NOT_FOUND,
+                message: format!("unknown method {}", method),
+            }),
+        }
+    }
+
+    fn notification(&mut self, method: &str, params: Value) -> io::Result<()> {
+        // Notifications cannot be answered, so ones with invalid parameters are ignored.
+        match method {
+            "textDocument/didOpen" => {
+                if let Ok(DidOpenTextDocumentParams { text_document }) = parse_params(params) {
+                    let document = Document::new(text_document.text);
+                    self.documents.insert(text_document.uri, document);
+                    self.publish_diagnostics()?;
+                }
+            }
+            "textDocument/didChange" => {
+                if let Ok(params) = parse_params::<DidChangeTextDocumentParams>(params) {
+                    let document = self.documents.get_mut(&params.text_document.uri);
+                    if let (Some(document), Some(change)) =
+                        (document, params.content_changes.last())
+                    {
+                        document.update(change.text.clone());
+                    }
+                }
+            }
+            "textDocument/didSave" => {
+                if let Ok(params) = parse_params::<DidSaveTextDocumentParams>(params) {
+                    let document = self.documents.get_mut(&params.text_document.uri);
+                    if let (Some(document), Some(text)) = (document, params.text) {
+                        document.update(text);
+                    }
+                    self.publish_diagnostics()?;
+                }
+            }
+            "textDocument/didClose" => {
+                if let Ok(params) = parse_params::<DidCloseTextDocumentParams>(params) {
+                    let uri = params.text_document.uri;
+                    self.documents.remove(&uri);
+                    self.notify(
+                        "textDocument/publishDiagnostics",
+                        json!({"uri": uri, "diagnostics": []}),
+                    )?;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn notify(&mut self, method: &str, params: Value) -> io::Result<()> {
+        let notification = json!({"jsonrpc": "2.0", "method": method, "params": params});
+        write_message(&mut self.output, &notification)
+    }
+
+    /// Publish diagnostics for every open document. A change to one document can fix or cause
+    /// warnings in the others, e.g., by defining a rule they call.
+    fn publish_diagnostics(&mut self) -> io::Result<()> {
+        for (uri, diagnostics) in diagnostics(&self.documents) {
+            self.notify(
+                "textDocument/publishDiagnostics",
+                json!({"uri": uri, "diagnostics": diagnostics}),
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Return the name and arity of the rule defined or called at a position.
+    fn rule_at(&self, params: &TextDocumentPositionParams) -> Option<(String, usize, Range)> {
+        let document = self.documents.get(&params.text_document.uri)?;
+        let (name, arity, range) = document.rule_at(params.position)?;
+        Some((name.to_owned(), arity, range))
+    }
+
+    /// Every rule, in any open document, that a call to `name` with `arity` arguments matches.
+    fn matching_rules<'a>(
+        &'a self,
+        name: &'a str,
+        arity: usize,
+    ) -> impl Iterator<Item = (&'a String, &'a RuleDefinition)> {
+        self.documents.iter().flat_map(move |(uri, document)| {
+            document
+                .rules
+                .iter()
+                .filter(move |rule| rule.name == name && rule.arity == arity)
+                .map(move |rule| (uri, rule))
+        })
+    }
+
+    fn definition(&self, params: TextDocumentPositionParams) -> Vec<Location> {
+        let (name, arity, _) = match self.rule_at(&params) {
+            Some(rule) => rule,
+            None => return vec![],
+        };
+        self.matching_rules(&name, arity)
+            .map(|(uri, rule)| Location {
+                uri: uri.clone(),
+                range: rule.name_range,
+            })
+            .collect()
+    }
+
+    fn references(&self, params: ReferenceParams) -> Vec<Location> {
+        let position = TextDocumentPositionParams {
+            text_document: params.text_document,
+            position: params.position,
+        };
+        let (name, arity, _) = match self.rule_at(&position) {
+            Some(rule) => rule,
+            None => return vec![],
+        };
+        let mut locations = vec![];
+        if params.context.include_declaration {
+            locations.extend(
+                self.matching_rules(&name, arity)
+                    .map(|(uri, rule)| Location {
+                        uri: uri.clone(),
+                        range: rule.name_range,
+                    }),
+            );
+        }
+        for (uri, document) in &self.documents {
+            let calls = document
+                .calls
+                .iter()
+                .filter(|call| call.name == name && call.arity == arity);
+            locations.extend(calls.map(|call| Location {
+                uri: uri.clone(),
+                range: call.name_range,
+            }));
+        }
+        locations
+    }
+
+    /// Show the signatures of the rules that a call matches.
+    fn hover(&self, params: TextDocumentPositionParams) -> Option<Hover> {
+        let (name, arity, range) = self.rule_at(&params)?;
+        let signatures = self
+            .matching_rules(&name, arity)
+            .map(|(_, rule)| rule.signature.as_str())
+            .collect::<Vec<_>>();
+        if signatures.is_empty() {
+            return None;
+        }
+        Some(Hover {
+            contents: MarkupContent {
+                kind: "markdown",
+                value: format!("```polar\n{}\n```", signatures.join("\n")),
+            },
+            range,
+        })
+    }
+
+    fn document_symbols(&self, params: DocumentSymbolParams) -> Vec<DocumentSymbol> {
+        let document = match self.documents.get(&params.text_document.uri) {
+            Some(document) => document,
+            None => return vec![],
+        };
+        document
+            .rules
+            .iter()
+            .map(|rule| DocumentSymbol {
+                name: rule.name.clone(),
+                detail: rule.signature.clone(),
+                kind: SYMBOL_KIND_FUNCTION,
+                range: rule.range,
+                selection_range: rule.name_range,
+            })
+            .collect()
+    }
+}
+
+fn parse_params<T: DeserializeOwned>(params: Value) -> Result<T, ResponseError> {
+    serde_json::from_value(params).map_err(|e| ResponseError {
+        code: INVALID_PARAMS,
+        message: e.to_string(),
+    })
+}
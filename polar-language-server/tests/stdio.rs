@@ -0,0 +1,212 @@
+//! Drive the server binary with a scripted client over stdin and stdout.
+
+use polar_language_server::rpc::{read_message, write_message};
+use serde_json::{json, Value};
+use std::io::BufReader;
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+
+struct Client {
+    server: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+    next_id: u64,
+    /// Notifications received while waiting for a response.
+    notifications: Vec<Value>,
+}
+
+impl Client {
+    fn start() -> Self {
+        let mut server = Command::new(env!("CARGO_BIN_EXE_polar-language-server"))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("failed to start the server");
+        let stdin = server.stdin.take().unwrap();
+        let stdout = BufReader::new(server.stdout.take().unwrap());
+        Self {
+            server,
+            stdin,
+            stdout,
+            next_id: 0,
+            notifications: vec![],
+        }
+    }
+
+    fn request(&mut self, method: &str, params: Value) -> Value {
+        self.next_id += 1;
+        let id = self.next_id;
+        let request = json!({"jsonrpc": "2.0", "id": id, "method": method, "params": params});
+        write_message(&mut self.stdin, &request).unwrap();
+        loop {
+            let message = read_message(&mut self.stdout)
+                .unwrap()
+                .expect("no response");
+            if message["id"] == id {
+                return message;
+            }
+            self.notifications.push(message);
+        }
+    }
+
+    fn notify(&mut self, method: &str, params: Value) {
+        let notification = json!({"jsonrpc": "2.0", "method": method, "params": params});
+        write_message(&mut self.stdin, &notification).unwrap();
+    }
+
+    /// Wait for the next diagnostics published for `uri`.
+    fn diagnostics(&mut self, uri: &str) -> Vec<Value> {
+        if let Some(i) = self
+            .notifications
+            .iter()
+            .position(|n| n["params"]["uri"] == uri)
+        {
+            let notification = self.notifications.remove(i);
+            return notification["params"]["diagnostics"]
+                .as_array()
+                .unwrap()
+                .clone();
+        }
+        let notification = read_message(&mut self.stdout).unwrap().unwrap();
+        assert_eq!(notification["method"], "textDocument/publishDiagnostics");
+        self.notifications.push(notification);
+        self.diagnostics(uri)
+    }
+
+    fn exit(mut self) {
+        let response = self.request("shutdown", Value::Null);
+        assert_eq!(response["result"], Value::Null);
+        self.notify("exit", Value::Null);
+        assert!(self.server.wait().unwrap().success());
+    }
+}
+
+fn position(uri: &str, line: u32, character: u32) -> Value {
+    json!({
+        "textDocument": {"uri": uri},
+        "position": {"line": line, "character": character},
+    })
+}
+
+fn range(start: (u32, u32), end: (u32, u32)) -> Value {
+    json!({
+        "start": {"line": start.0, "character": start.1},
+        "end": {"line": end.0, "character": end.1},
+    })
+}
+
+const POLICY: &str = "file:///policy.polar";
+const ROLES: &str = "file:///roles.polar";
+const COPY: &str = "file:///copy.polar";
+
+#[test]
+fn test_language_server() {
+    let mut client = Client::start();
+    let response = client.request("initialize", json!({"capabilities": {}}));
+    assert_eq!(
+        response["result"]["capabilities"]["definitionProvider"],
+        true
+    );
+    client.notify("initialized", json!({}));
+
+    client.notify(
+        "textDocument/didOpen",
+        json!({"textDocument": {
+            "uri": ROLES,
+            "languageId": "polar",
+            "version": 1,
+            "text": "is_admin(user: User) if user.admin = true;\nis_admin(\"root\");\n",
+        }}),
+    );
+    assert_eq!(client.diagnostics(ROLES), Vec::<Value>::new());
+
+    client.notify(
+        "textDocument/didOpen",
+        json!({"textDocument": {
+            "uri": POLICY,
+            "languageId": "polar",
+            "version": 1,
+            "text": "allow(actor, _action, _resource) if\n    is_admin(actor) or is_owner(actor);\n",
+        }}),
+    );
+    let diagnostics = client.diagnostics(POLICY);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0]["code"], "undefined-rule");
+    assert_eq!(diagnostics[0]["severity"], 2);
+    assert_eq!(diagnostics[0]["range"], range((1, 23), (1, 38)));
+
+    // Go to definition of `is_admin`, in the other document.
+    let response = client.request("textDocument/definition", position(POLICY, 1, 6));
+    assert_eq!(
+        response["result"],
+        json!([
+            {"uri": ROLES, "range": range((0, 0), (0, 8))},
+            {"uri": ROLES, "range": range((1, 0), (1, 8))},
+        ])
+    );
+
+    let mut params = position(ROLES, 1, 2);
+    params["context"] = json!({"includeDeclaration": false});
+    let response = client.request("textDocument/references", params);
+    assert_eq!(
+        response["result"],
+        json!([{"uri": POLICY, "range": range((1, 4), (1, 12))}])
+    );
+
+    let response = client.request("textDocument/hover", position(POLICY, 1, 6));
+    assert_eq!(
+        response["result"]["contents"]["value"],
+        "```polar\nis_admin(user: User)\nis_admin(\"root\")\n```"
+    );
+    let response = client.request("textDocument/hover", position(POLICY, 1, 17));
+    assert_eq!(response["result"], Value::Null);
+
+    let response = client.request(
+        "textDocument/documentSymbol",
+        json!({"textDocument": {"uri": POLICY}}),
+    );
+    let symbols = response["result"].as_array().unwrap();
+    assert_eq!(symbols.len(), 1);
+    assert_eq!(symbols[0]["name"], "allow");
+    assert_eq!(symbols[0]["detail"], "allow(actor, _action, _resource)");
+    assert_eq!(symbols[0]["selectionRange"], range((0, 0), (0, 5)));
+
+    // Diagnostics are only recomputed on save.
+    client.notify(
+        "textDocument/didChange",
+        json!({
+            "textDocument": {"uri": POLICY, "version": 2},
+            "contentChanges": [{"text": "allow(actor, _action, _resource) if\n    is_admin(actor) if true;\n"}],
+        }),
+    );
+    client.notify(
+        "textDocument/didSave",
+        json!({"textDocument": {"uri": POLICY}}),
+    );
+    let diagnostics = client.diagnostics(POLICY);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0]["severity"], 1);
+    assert_eq!(diagnostics[0]["range"], range((1, 20), (1, 22)));
+    assert!(diagnostics[0]["message"]
+        .as_str()
+        .unwrap()
+        .starts_with("did not expect to find the token 'if'"));
+
+    // A copy of a document gets the same diagnostics, even though Polar loads it only once.
+    client.notify(
+        "textDocument/didOpen",
+        json!({"textDocument": {
+            "uri": COPY,
+            "languageId": "polar",
+            "version": 1,
+            "text": "allow(actor, _action, _resource) if\n    is_admin(actor) if true;\n",
+        }}),
+    );
+    let diagnostics = client.diagnostics(COPY);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0]["range"], range((1, 20), (1, 22)));
+
+    let response = client.request("textDocument/formatting", json!({}));
+    assert_eq!(response["error"]["code"], -32601);
+
+    client.exit();
+}
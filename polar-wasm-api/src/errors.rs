@@ -33,9 +33,16 @@ fn kind(err: &PolarError) -> String {
         Parse(ReservedWord { .. }) => "ParseError::ReservedWord",
         Parse(InvalidFloat { .. }) => "ParseError::InvalidFloat",
         Parse(WrongValueType { .. }) => "ParseError::WrongValueType",
+        Parse(ResourceBlock { .. }) => "ParseError::ResourceBlock",
+        Parse(Module { .. }) => "ParseError::Module",
+        Parse(Import { .. }) => "ParseError::Import",
+        Parse(Constant { .. }) => "ParseError::Constant",
+        Parse(BuiltinRule { .. }) => "ParseError::BuiltinRule",
+        Parse(Multiple { .. }) => "ParseError::Multiple",
         Runtime(Application { .. }) => "RuntimeError::Application",
         Runtime(ArithmeticError { .. }) => "RuntimeError::ArithmeticError",
         Runtime(FileLoading { .. }) => "RuntimeError::FileLoading",
+        Runtime(InvalidRegex { .. }) => "RuntimeError::InvalidRegex",
         Runtime(IncompatibleBindings { .. }) => "RuntimeError::IncompatibleBindings",
         Runtime(QueryTimeout { .. }) => "RuntimeError::QueryTimeout",
         Runtime(Serialization { .. }) => "RuntimeError::Serialization",
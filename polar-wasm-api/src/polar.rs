@@ -1,3 +1,4 @@
+use polar_core::error::{PolarError, RuntimeError};
 use polar_core::{polar, terms::Symbol};
 use wasm_bindgen::prelude::*;
 
@@ -24,6 +25,18 @@ impl Polar {
             .map_err(Error::into)
     }
 
+    /// The files that `src`, the contents of the policy file `filename`, imports and that have
+    /// not been loaded yet.
+    #[wasm_bindgen(js_class = Polar, js_name = imports)]
+    pub fn wasm_imports(&self, src: &str, filename: &str) -> JsResult<Vec<JsValue>> {
+        let imports = self.0.imports(src, filename).map_err(Error::from)?;
+        Ok(imports
+            .into_iter()
+            .filter(|import| !self.0.is_loaded(import))
+            .map(JsValue::from)
+            .collect())
+    }
+
     #[wasm_bindgen(js_class = Polar, js_name = clearRules)]
     pub fn wasm_clear_rules(&self) {
         self.0.clear_rules()
@@ -38,24 +51,49 @@ impl Polar {
         Ok(())
     }
 
+    #[wasm_bindgen(js_class = Polar, js_name = ignoreWarning)]
+    pub fn wasm_ignore_warning(&self, code: &str) -> JsResult<()> {
+        let code = code
+            .parse()
+            .map_err(|msg| Error::from(PolarError::from(RuntimeError::Unsupported { msg })))?;
+        self.0.ignore_warning(code);
+        Ok(())
+    }
+
+    /// The annotations on each definition of the rule `name`, in the order the definitions
+    /// were loaded.
+    #[wasm_bindgen(js_class = Polar, js_name = annotations)]
+    pub fn wasm_annotations(&self, name: &str) -> JsResult<JsValue> {
+        let annotations = self.0.annotations(&Symbol::new(name));
+        serde_wasm_bindgen::to_value(&annotations).map_err(|e| serialization_error(e.to_string()))
+    }
+
+    /// The documentation of the loaded rules, rule types and constants.
+    #[wasm_bindgen(js_class = Polar, js_name = docs)]
+    pub fn wasm_docs(&self) -> JsResult<JsValue> {
+        serde_wasm_bindgen::to_value(&self.0.docs()).map_err(|e| serialization_error(e.to_string()))
+    }
+
     #[wasm_bindgen(js_class = Polar, js_name = nextInlineQuery)]
     pub fn wasm_next_inline_query(&self) -> Option<Query> {
         self.0.next_inline_query(false).map(Query::from)
     }
 
+    /// Pass `trace` to include a trace with each result.
     #[wasm_bindgen(js_class = Polar, js_name = newQueryFromStr)]
-    pub fn wasm_new_query_from_str(&self, src: &str) -> JsResult<Query> {
+    pub fn wasm_new_query_from_str(&self, src: &str, trace: Option<bool>) -> JsResult<Query> {
         self.0
-            .new_query(src, false)
+            .new_query(src, trace.unwrap_or(false))
             .map(Query::from)
             .map_err(Error::from)
             .map_err(Error::into)
     }
 
+    /// Pass `trace` to include a trace with each result.
     #[wasm_bindgen(js_class = Polar, js_name = newQueryFromTerm)]
-    pub fn wasm_new_query_from_term(&self, value: &str) -> JsResult<Query> {
+    pub fn wasm_new_query_from_term(&self, value: &str, trace: Option<bool>) -> JsResult<Query> {
         serde_json::from_str(value)
-            .map(|term| Query::from(self.0.new_query_from_term(term, false)))
+            .map(|term| Query::from(self.0.new_query_from_term(term, trace.unwrap_or(false))))
             .map_err(serde_serialization_error)
     }
 
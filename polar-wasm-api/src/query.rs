@@ -1,4 +1,9 @@
-use polar_core::{polar, terms::Term};
+use polar_core::{
+    error::{PolarError, RuntimeError},
+    polar,
+    terms::{Term, Timestamp},
+    traces::TraceFilter,
+};
 use wasm_bindgen::prelude::*;
 
 use crate::errors::{serde_serialization_error, serialization_error, Error};
@@ -78,6 +83,30 @@ impl Query {
         serde_wasm_bindgen::to_value(&source).map_err(|e| serialization_error(e.to_string()))
     }
 
+    /// Pin the time returned by `now()` to an RFC 3339 timestamp, e.g., from
+    /// `Date.prototype.toISOString()`.
+    #[wasm_bindgen(js_class = Query, js_name = setNow)]
+    pub fn wasm_set_now(&mut self, now: &str) -> JsResult<()> {
+        let now = Timestamp::parse(now).map_err(|msg| {
+            Error::from(PolarError::from(RuntimeError::TypeError {
+                msg,
+                stack_trace: None,
+            }))
+        })?;
+        self.0.set_now(now);
+        Ok(())
+    }
+
+    /// Leave the nodes and bindings excluded by `filter`, a serialized trace filter such as
+    /// `{"builtins": true, "temporaries": true, "rules": "^_"}`, out of the traces of results.
+    #[wasm_bindgen(js_class = Query, js_name = setTraceFilter)]
+    pub fn wasm_set_trace_filter(&mut self, filter: &str) -> JsResult<()> {
+        let filter: TraceFilter =
+            serde_json::from_str(filter).map_err(serde_serialization_error)?;
+        self.0.set_trace_filter(filter);
+        Ok(())
+    }
+
     #[cfg(target_arch = "wasm32")]
     #[wasm_bindgen(js_class = Query, js_name = setLoggingOptions)]
     pub fn wasm_set_logging_options(
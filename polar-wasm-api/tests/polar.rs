@@ -22,7 +22,7 @@ fn load_file_errors() {
     assert_eq!(err.name(), "ParseError::UnrecognizedToken");
     assert_eq!(
         err.message(),
-        "did not expect to find the token ';' at line 1, column 1"
+        "did not expect to find the token ';', expected `?=`, `@` or a name at line 1, column 1"
     );
 }
 